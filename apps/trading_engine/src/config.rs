@@ -1,6 +1,7 @@
 use anyhow::Context;
 use serde::Deserialize;
 use std::fs;
+use std::net::{IpAddr, SocketAddr};
 
 #[derive(Debug, Deserialize, Clone)]
 #[allow(dead_code)]
@@ -8,6 +9,14 @@ pub struct AppConfig {
     pub network: NetworkConfig,
     pub trading: TradingConfig,
     pub risk: RiskConfig,
+    #[serde(default)]
+    pub server: ServerConfig,
+    #[serde(default)]
+    pub telemetry: TelemetryConfig,
+    #[serde(default)]
+    pub database: DatabaseConfig,
+    #[serde(default)]
+    pub performance: PerformanceConfig,
 }
 
 #[derive(Debug, Deserialize, Clone)]
@@ -16,12 +25,24 @@ pub struct NetworkConfig {
     pub name: String,
     pub rest_url: String,
     pub ws_url: String,
+    /// Market-data source: `binance` (default) or `coinbase`. Execution is Binance-only.
+    #[serde(default = "default_exchange")]
+    pub exchange: String,
+    /// `recvWindow` (ms) for signed REST requests. Defaults to 5000.
+    pub recv_window_ms: Option<u64>,
 }
 
 #[derive(Debug, Deserialize, Clone)]
 pub struct TradingConfig {
+    /// Instrument to trade (e.g. `ETHUSDT`). Defaults to `BTCUSDT`.
+    #[serde(default = "default_symbol", deserialize_with = "uppercase")]
+    pub symbol: String,
     pub api_key: Option<String>,
     pub secret_key: Option<String>,
+    /// `hmac_sha256` (default) or `ed25519`; with `ed25519`, `secret_key` is the PEM
+    /// private key.
+    #[serde(default)]
+    pub sign_scheme: execution::SignScheme,
     pub enabled: bool,
     pub dry_run: bool,
     pub fee_maker: f64,
@@ -30,6 +51,63 @@ pub struct TradingConfig {
     pub strategy_threshold: Option<f64>,
     pub price_threshold: Option<f64>,
     pub volume_multiplier: Option<f64>,
+    pub limit_offset_ticks: Option<f64>,
+    pub use_limit_orders: Option<bool>,
+    pub order_quantity: Option<f64>,
+    pub cooldown_ns: Option<u64>,
+    /// Pre-fill the strategy window from the latest aggTrades at startup instead of waiting
+    /// for `strategy_window` live ticks. Defaults to true.
+    #[serde(alias = "seed_from_klines")]
+    pub seed_from_trades: Option<bool>,
+    /// Tick-to-strategy latency above which a tick counts as stale (0 disables).
+    pub latency_warn_ns: Option<u64>,
+    /// Stop trading if no tick arrives for this many seconds (0 disables). Defaults to 30.
+    pub stale_timeout_secs: Option<u64>,
+    /// Also stream the top `depth_levels` (5, 10 or 20) of the order book and feed
+    /// top-of-book updates to the strategy. Required by `MARKET_MAKER`. Unset disables.
+    pub depth_levels: Option<usize>,
+    /// Capacity of the feed -> strategy ring buffer. Defaults to 4096.
+    pub market_buffer_capacity: Option<usize>,
+    /// Capacity of the strategy -> execution ring buffer. Defaults to 4096.
+    pub signal_buffer_capacity: Option<usize>,
+    /// Max points kept for the PnL chart; older points are thinned out, not dropped.
+    /// Defaults to 5000.
+    pub pnl_history_capacity: Option<usize>,
+    /// Tee live market events to a JSONL file for post-mortems.
+    pub record_live: Option<bool>,
+    /// Output of `record_live`. Defaults to `data/live_ticks.jsonl` (appended).
+    pub record_live_path: Option<String>,
+    /// Dry-run orders get simulated fills (latency + slippage off `last_price`)
+    /// instead of filling instantly at the signal price.
+    pub paper_trading: Option<bool>,
+    /// Adverse slippage applied to simulated market fills, in basis points. Defaults to 1.
+    pub paper_slippage_bps: Option<f64>,
+    /// Simulated order latency before a paper fill is priced, in ms. Defaults to 50.
+    pub paper_latency_ms: Option<u64>,
+    /// Leverage set for `symbol` at startup (1-125). Unset leaves the account setting as is.
+    pub leverage: Option<u8>,
+    /// Margin mode set for `symbol` at startup: `true` = ISOLATED, `false` = CROSSED.
+    /// Unset leaves the account setting as is.
+    pub margin_isolated: Option<bool>,
+    /// Force-close any position held longer than this many seconds (0 disables). Defaults to 0.
+    pub max_hold_secs: Option<u64>,
+    /// Kill-switch file: while it exists, trading is stopped and risk disarmed.
+    /// Defaults to `HALT` in the working directory.
+    pub kill_switch_path: Option<String>,
+    /// Must be explicitly `true` to trade live against a Mainnet endpoint.
+    pub allow_mainnet: Option<bool>,
+}
+
+fn default_exchange() -> String {
+    "binance".to_string()
+}
+
+fn default_symbol() -> String {
+    "BTCUSDT".to_string()
+}
+
+fn uppercase<'de, D: serde::Deserializer<'de>>(deserializer: D) -> Result<String, D::Error> {
+    String::deserialize(deserializer).map(|s| s.trim().to_uppercase())
 }
 
 #[derive(Debug, Deserialize, Clone)]
@@ -38,6 +116,64 @@ pub struct RiskConfig {
     pub max_position: f64,
     pub max_drawdown: f64,
     pub max_order_size: f64,
+    /// Stop and disarm after this many consecutive failed orders (0 disables). Defaults to 5.
+    pub max_consecutive_failures: Option<u32>,
+    /// Reject market orders once the price moved this far (%) against the signal (0 disables).
+    pub max_slippage_pct: Option<f64>,
+    /// Trailing stop: close the position once price retraces this % from its best level
+    /// since entry (0 disables).
+    pub trailing_pct: Option<f64>,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct ServerConfig {
+    pub bind_addr: String,
+    pub port: u16,
+}
+
+impl Default for ServerConfig {
+    fn default() -> Self {
+        Self {
+            bind_addr: "0.0.0.0".to_string(),
+            port: 3000,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Clone, Default)]
+pub struct TelemetryConfig {
+    /// Per-target log filter, e.g. `info,feed_handler=warn,strategy=debug`. Applies to
+    /// console and file and takes precedence over `RUST_LOG`. Unset keeps the defaults.
+    pub log_filter: Option<String>,
+    /// JSON log file rotation: `"daily"` (default), `"hourly"` or `{ size_mb = N }`.
+    #[serde(default)]
+    pub rotation: telemetry::Rotation,
+}
+
+#[derive(Debug, Deserialize, Clone, Default)]
+pub struct DatabaseConfig {
+    /// SQLite pool size shared by the trade writer and history queries. Defaults to 5.
+    pub max_connections: Option<u32>,
+}
+
+#[derive(Debug, Deserialize, Clone, Default)]
+pub struct PerformanceConfig {
+    /// Pin the strategy thread to this CPU core. Unset leaves it to the OS scheduler.
+    pub strategy_core: Option<usize>,
+    /// Strategy thread behavior on an empty ring buffer: `"spin"`, `"yield"` (default)
+    /// or `{ park_us = N }`.
+    #[serde(default)]
+    pub wait_strategy: strategy::WaitStrategy,
+}
+
+impl ServerConfig {
+    pub fn socket_addr(&self) -> Result<SocketAddr, anyhow::Error> {
+        let ip: IpAddr = self
+            .bind_addr
+            .parse()
+            .with_context(|| format!("Invalid server.bind_addr: {}", self.bind_addr))?;
+        Ok(SocketAddr::new(ip, self.port))
+    }
 }
 
 pub fn load(path: &str) -> Result<AppConfig, anyhow::Error> {
@@ -49,3 +185,124 @@ pub fn load(path: &str) -> Result<AppConfig, anyhow::Error> {
 
     Ok(config)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BASE: &str = r#"
+        [network]
+        name = "testnet"
+        rest_url = "https://testnet.binancefuture.com"
+        ws_url = "wss://stream.binancefuture.com/ws"
+
+        [trading]
+        enabled = false
+        dry_run = true
+        fee_maker = 0.0002
+        fee_taker = 0.0005
+
+        [risk]
+        max_position = 1.0
+        max_drawdown = 100.0
+        max_order_size = 0.1
+    "#;
+
+    #[test]
+    fn test_symbol_defaults_and_normalizes() {
+        let config: AppConfig = toml::from_str(BASE).expect("Failed to parse");
+        assert_eq!(config.trading.symbol, "BTCUSDT");
+
+        let raw = BASE.replace("[trading]", "[trading]\nsymbol = \"ethusdt\"");
+        let config: AppConfig = toml::from_str(&raw).expect("Failed to parse");
+        assert_eq!(config.trading.symbol, "ETHUSDT");
+    }
+
+    #[test]
+    fn test_exchange_defaults_to_binance() {
+        let config: AppConfig = toml::from_str(BASE).expect("Failed to parse");
+        assert_eq!(config.network.exchange, "binance");
+
+        let raw = BASE.replace("[network]", "[network]\nexchange = \"coinbase\"");
+        let config: AppConfig = toml::from_str(&raw).expect("Failed to parse");
+        assert_eq!(config.network.exchange, "coinbase");
+    }
+
+    #[test]
+    fn test_telemetry_section_is_optional() {
+        let config: AppConfig = toml::from_str(BASE).expect("Failed to parse");
+        assert!(config.telemetry.log_filter.is_none());
+
+        let raw = format!(
+            "{}\n[telemetry]\nlog_filter = \"info,feed_handler=warn\"\n",
+            BASE
+        );
+        let config: AppConfig = toml::from_str(&raw).expect("Failed to parse");
+        assert_eq!(
+            config.telemetry.log_filter.as_deref(),
+            Some("info,feed_handler=warn")
+        );
+        assert_eq!(config.telemetry.rotation, telemetry::Rotation::Daily);
+
+        let raw = format!("{}\n[telemetry]\nrotation = {{ size_mb = 100 }}\n", BASE);
+        let config: AppConfig = toml::from_str(&raw).expect("Failed to parse");
+        assert_eq!(config.telemetry.rotation, telemetry::Rotation::SizeMb(100));
+    }
+
+    #[test]
+    fn test_performance_section_is_optional() {
+        let config: AppConfig = toml::from_str(BASE).expect("Failed to parse");
+        assert_eq!(config.performance.strategy_core, None);
+
+        assert_eq!(
+            config.performance.wait_strategy,
+            strategy::WaitStrategy::Yield
+        );
+
+        let raw = format!(
+            "{}\n[performance]\nstrategy_core = 3\nwait_strategy = \"spin\"\n",
+            BASE
+        );
+        let config: AppConfig = toml::from_str(&raw).expect("Failed to parse");
+        assert_eq!(config.performance.strategy_core, Some(3));
+        assert_eq!(
+            config.performance.wait_strategy,
+            strategy::WaitStrategy::Spin
+        );
+
+        let raw = format!(
+            "{}\n[performance]\nwait_strategy = {{ park_us = 50 }}\n",
+            BASE
+        );
+        let config: AppConfig = toml::from_str(&raw).expect("Failed to parse");
+        assert_eq!(
+            config.performance.wait_strategy,
+            strategy::WaitStrategy::Park(std::time::Duration::from_micros(50))
+        );
+    }
+
+    #[test]
+    fn test_server_section_defaults_when_missing() {
+        let config: AppConfig = toml::from_str(BASE).expect("Failed to parse");
+        let addr = config.server.socket_addr().expect("Invalid addr");
+        assert_eq!(addr, SocketAddr::from(([0, 0, 0, 0], 3000)));
+    }
+
+    #[test]
+    fn test_server_section_overrides_bind() {
+        let raw = format!(
+            "{}\n[server]\nbind_addr = \"127.0.0.1\"\nport = 8080\n",
+            BASE
+        );
+        let config: AppConfig = toml::from_str(&raw).expect("Failed to parse");
+        let addr = config.server.socket_addr().expect("Invalid addr");
+        assert_eq!(addr, SocketAddr::from(([127, 0, 0, 1], 8080)));
+
+        let raw = format!(
+            "{}\n[server]\nbind_addr = \"localhost:80\"\nport = 8080\n",
+            BASE
+        );
+        let config: AppConfig = toml::from_str(&raw).expect("Failed to parse");
+        assert!(config.server.socket_addr().is_err());
+    }
+}
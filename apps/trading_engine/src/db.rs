@@ -1,9 +1,13 @@
 use anyhow::Context;
+use common::TradeInstruction;
 use serde::{Deserialize, Serialize};
-use sqlx::{sqlite::SqlitePoolOptions, Pool, Sqlite};
+use sqlx::{
+    sqlite::{SqlitePoolOptions, SqliteRow},
+    Pool, QueryBuilder, Sqlite,
+};
 use std::str::FromStr;
-use std::time::{Duration, Instant};
-use tokio::sync::mpsc;
+use std::time::Duration;
+use tokio::sync::{mpsc, oneshot};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TradeRecord {
@@ -20,87 +24,205 @@ pub struct TradeRecord {
     pub fee: Option<f64>,
     pub fee_currency: Option<String>,
     pub raw: Option<String>,
+    /// Signal timestamp to order send.
+    pub decision_latency_ns: Option<u64>,
+    /// Triggering tick's receipt to order send.
+    pub tick_to_order_ns: Option<u64>,
+}
+
+/// Signal-path latency of the order behind a trade, taken when the order is sent.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct OrderLatency {
+    pub decision_ns: Option<u64>,
+    pub tick_to_order_ns: Option<u64>,
+}
+
+impl OrderLatency {
+    /// Latencies of `instruction` sent at `sent_ns` (`common::now_nanos()` clock).
+    /// Unset instruction timestamps give `None`.
+    pub fn measure(instruction: &TradeInstruction, sent_ns: u64) -> Self {
+        let since = |ts: u64| (ts > 0).then(|| sent_ns.saturating_sub(ts));
+        Self {
+            decision_ns: since(instruction.timestamp),
+            tick_to_order_ns: since(instruction.tick_timestamp),
+        }
+    }
+}
+
+/// Optional `WHERE` conditions for `TradeStorage::query_trades`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TradeFilter {
+    pub symbol: Option<String>,
+    /// Stored side, `Buy` or `Sell`.
+    pub side: Option<String>,
+}
+
+/// Aggregate performance summary over a trade sequence.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct TradeStats {
+    pub total_trades: usize,
+    pub win_count: usize,
+    pub loss_count: usize,
+    pub gross_profit: f64,
+    pub gross_loss: f64,
+    /// Largest peak-to-trough decline of cumulative realized PnL.
+    pub max_drawdown: f64,
+}
+
+impl TradeStats {
+    /// Computes stats from trades in chronological order.
+    pub fn from_trades(trades: &[TradeRecord]) -> Self {
+        let mut stats = TradeStats {
+            total_trades: trades.len(),
+            ..Default::default()
+        };
+        let mut cumulative = 0.0;
+        let mut peak = 0.0_f64;
+
+        for trade in trades {
+            if trade.pnl > 0.0 {
+                stats.win_count += 1;
+                stats.gross_profit += trade.pnl;
+            } else if trade.pnl < 0.0 {
+                stats.loss_count += 1;
+                stats.gross_loss += trade.pnl.abs();
+            }
+
+            cumulative += trade.pnl;
+            peak = peak.max(cumulative);
+            stats.max_drawdown = stats.max_drawdown.max(peak - cumulative);
+        }
+
+        stats
+    }
+}
+
+/// One schema change. A DB at `PRAGMA user_version` N has had the first N applied.
+/// Append new steps; never edit one that has shipped.
+enum Migration {
+    Sql(&'static [&'static str]),
+    /// `ALTER TABLE trades ADD COLUMN`, skipped if the column already exists.
+    AddColumn(&'static str, &'static str),
+}
+
+const MIGRATIONS: &[Migration] = &[
+    // v1: Initial schema, with indexes for time-range and per-symbol queries
+    Migration::Sql(&[
+        r#"
+        CREATE TABLE IF NOT EXISTS trades (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            exchange_ts_ms INTEGER,
+            monotonic_ns INTEGER,
+            symbol TEXT,
+            side TEXT,
+            price REAL,
+            quantity REAL,
+            pnl REAL,
+            strategy TEXT,
+            order_id TEXT,
+            exec_id TEXT,
+            fee REAL,
+            fee_currency TEXT,
+            raw TEXT
+        )
+        "#,
+        "CREATE INDEX IF NOT EXISTS idx_trades_ts ON trades(exchange_ts_ms)",
+        "CREATE INDEX IF NOT EXISTS idx_trades_symbol ON trades(symbol)",
+    ]),
+    // v2, v3: Per-trade latency
+    Migration::AddColumn("decision_latency_ns", "INTEGER"),
+    Migration::AddColumn("tick_to_order_ns", "INTEGER"),
+];
+
+/// Schema version of a fully migrated trades DB.
+pub const SCHEMA_VERSION: i64 = MIGRATIONS.len() as i64;
+
+/// `(ts_ms, equity)` after each trade: `initial_balance` plus cumulative realized PnL.
+/// Unrealized PnL isn't stored per trade, so between trades this is realized-only.
+pub fn equity_curve(trades: &[TradeRecord], initial_balance: f64) -> Vec<(u64, f64)> {
+    let mut equity = initial_balance;
+    trades
+        .iter()
+        .map(|trade| {
+            equity += trade.pnl;
+            (trade.exchange_ts_ms as u64, equity)
+        })
+        .collect()
+}
+
+/// Messages consumed by the background writer task.
+enum WriterCommand {
+    Insert(Box<TradeRecord>),
+    /// Commit everything buffered so far, then acknowledge.
+    Flush(oneshot::Sender<()>),
 }
 
 #[derive(Clone)]
 pub struct TradeStorage {
     pool: Pool<Sqlite>,
-    tx: mpsc::Sender<TradeRecord>,
+    tx: mpsc::Sender<WriterCommand>,
 }
 
+/// Default pool size (`[database] max_connections`).
+pub const DEFAULT_MAX_CONNECTIONS: u32 = 5;
+
 impl TradeStorage {
     pub async fn new(path: &str) -> anyhow::Result<Self> {
+        Self::with_max_connections(path, DEFAULT_MAX_CONNECTIONS).await
+    }
+
+    pub async fn with_max_connections(path: &str, max_connections: u32) -> anyhow::Result<Self> {
         let db_url = format!("sqlite:{}", path);
 
         // 1. Configure Options
+        // Wait on the writer's lock instead of failing with "database is locked";
+        // NORMAL sync is durable enough under WAL and keeps commits cheap.
         let options = sqlx::sqlite::SqliteConnectOptions::from_str(&db_url)?
             .create_if_missing(true)
-            .journal_mode(sqlx::sqlite::SqliteJournalMode::Wal);
+            .journal_mode(sqlx::sqlite::SqliteJournalMode::Wal)
+            .synchronous(sqlx::sqlite::SqliteSynchronous::Normal)
+            .busy_timeout(Duration::from_secs(5));
 
         // 2. Connect
         let pool = SqlitePoolOptions::new()
-            .max_connections(5)
+            .max_connections(max_connections.max(1))
             .connect_with(options)
             .await
             .context("Failed to connect to database")?;
 
-        // 3. Create Table
-        sqlx::query(
-            r#"
-            CREATE TABLE IF NOT EXISTS trades (
-                id INTEGER PRIMARY KEY AUTOINCREMENT,
-                exchange_ts_ms INTEGER,
-                monotonic_ns INTEGER,
-                symbol TEXT,
-                side TEXT,
-                price REAL,
-                quantity REAL,
-                pnl REAL,
-                strategy TEXT,
-                order_id TEXT,
-                exec_id TEXT,
-                fee REAL,
-                fee_currency TEXT,
-                raw TEXT
-            );
-            "#,
-        )
-        .execute(&pool)
-        .await?;
+        // 3. Create / Upgrade Schema
+        Self::migrate(&pool).await?;
 
         // 4. Spawn Writer Task
-        let (tx, mut rx) = mpsc::channel::<TradeRecord>(10_000);
+        let (tx, mut rx) = mpsc::channel::<WriterCommand>(10_000);
         let pool_clone = pool.clone();
 
         tokio::spawn(async move {
             let mut buffer = Vec::with_capacity(100);
-            let mut last_flush = Instant::now();
-            let flush_interval = Duration::from_millis(100);
+            let mut interval = tokio::time::interval(Duration::from_millis(100));
 
             loop {
-                match rx.recv().await {
-                    Some(record) => {
-                        buffer.push(record);
-
-                        let should_flush =
-                            buffer.len() >= 100 || last_flush.elapsed() >= flush_interval;
-
-                        if should_flush {
-                            if let Err(e) = Self::flush_buffer(&pool_clone, &buffer).await {
-                                tracing::error!("Failed to flush trades to DB: {}", e);
+                tokio::select! {
+                    cmd = rx.recv() => match cmd {
+                        Some(WriterCommand::Insert(record)) => {
+                            buffer.push(*record);
+                            if buffer.len() >= 100 {
+                                Self::flush_and_clear(&pool_clone, &mut buffer).await;
                             }
-                            buffer.clear();
-                            last_flush = Instant::now();
                         }
-                    }
-                    None => {
-                        // Channel closed, flush remaining
-                        if !buffer.is_empty() {
-                            if let Err(e) = Self::flush_buffer(&pool_clone, &buffer).await {
-                                tracing::error!("Failed to flush remaining trades: {}", e);
-                            }
+                        Some(WriterCommand::Flush(ack)) => {
+                            Self::flush_and_clear(&pool_clone, &mut buffer).await;
+                            let _ = ack.send(());
+                        }
+                        None => {
+                            // Channel closed, flush remaining
+                            Self::flush_and_clear(&pool_clone, &mut buffer).await;
+                            break;
                         }
-                        break;
+                    },
+                    // Time-based flush so a lone trade doesn't sit in memory
+                    _ = interval.tick() => {
+                        Self::flush_and_clear(&pool_clone, &mut buffer).await;
                     }
                 }
             }
@@ -109,6 +231,68 @@ impl TradeStorage {
         Ok(Self { pool, tx })
     }
 
+    /// Brings the DB from its `PRAGMA user_version` up to `SCHEMA_VERSION`, one
+    /// transaction per step. Refuses DBs written by a newer build.
+    async fn migrate(pool: &Pool<Sqlite>) -> anyhow::Result<()> {
+        let version: i64 = sqlx::query_scalar("PRAGMA user_version")
+            .fetch_one(pool)
+            .await?;
+        if version > SCHEMA_VERSION {
+            anyhow::bail!(
+                "Trades DB schema v{} is newer than this build supports (v{})",
+                version,
+                SCHEMA_VERSION
+            );
+        }
+
+        for (index, step) in MIGRATIONS.iter().enumerate().skip(version as usize) {
+            let target = index + 1;
+            let mut tx = pool.begin().await?;
+            match step {
+                Migration::Sql(statements) => {
+                    for statement in *statements {
+                        sqlx::query(statement).execute(&mut *tx).await?;
+                    }
+                }
+                Migration::AddColumn(column, ty) => {
+                    let exists = sqlx::query("PRAGMA table_info(trades)")
+                        .fetch_all(&mut *tx)
+                        .await?
+                        .iter()
+                        .any(|row| {
+                            use sqlx::Row;
+                            row.try_get::<String, _>("name")
+                                .is_ok_and(|name| name == *column)
+                        });
+                    if !exists {
+                        sqlx::query(&format!("ALTER TABLE trades ADD COLUMN {} {}", column, ty))
+                            .execute(&mut *tx)
+                            .await?;
+                    }
+                }
+            }
+            // PRAGMA doesn't take bind parameters
+            sqlx::query(&format!("PRAGMA user_version = {}", target))
+                .execute(&mut *tx)
+                .await?;
+            tx.commit()
+                .await
+                .with_context(|| format!("Failed to migrate trades DB to schema v{}", target))?;
+            tracing::info!("Trades DB migrated to schema v{}", target);
+        }
+        Ok(())
+    }
+
+    async fn flush_and_clear(pool: &Pool<Sqlite>, buffer: &mut Vec<TradeRecord>) {
+        if buffer.is_empty() {
+            return;
+        }
+        if let Err(e) = Self::flush_buffer(pool, buffer).await {
+            tracing::error!("Failed to flush trades to DB: {}", e);
+        }
+        buffer.clear();
+    }
+
     async fn flush_buffer(pool: &Pool<Sqlite>, buffer: &[TradeRecord]) -> anyhow::Result<()> {
         let mut tx = pool.begin().await?;
 
@@ -117,8 +301,9 @@ impl TradeStorage {
                 r#"
                 INSERT INTO trades (
                     exchange_ts_ms, monotonic_ns, symbol, side, price, quantity, pnl, strategy,
-                    order_id, exec_id, fee, fee_currency, raw
-                ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+                    order_id, exec_id, fee, fee_currency, raw, decision_latency_ns,
+                    tick_to_order_ns
+                ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
                 "#,
             )
             .bind(trade.exchange_ts_ms)
@@ -134,6 +319,8 @@ impl TradeStorage {
             .bind(trade.fee)
             .bind(&trade.fee_currency)
             .bind(&trade.raw)
+            .bind(trade.decision_latency_ns.map(|ns| ns as i64))
+            .bind(trade.tick_to_order_ns.map(|ns| ns as i64))
             .execute(&mut *tx)
             .await?;
         }
@@ -144,7 +331,7 @@ impl TradeStorage {
 
     pub async fn insert_trade(&self, trade: TradeRecord) {
         // Non-blocking send. If full, drop and log.
-        match self.tx.try_send(trade) {
+        match self.tx.try_send(WriterCommand::Insert(Box::new(trade))) {
             Ok(_) => {}
             Err(mpsc::error::TrySendError::Full(_)) => {
                 tracing::warn!("TradeStorage channel full! Dropping trade record.");
@@ -155,49 +342,113 @@ impl TradeStorage {
         }
     }
 
-    pub async fn get_recent_trades(&self, limit: i64) -> anyhow::Result<Vec<TradeRecord>> {
+    fn trade_from_row(row: &SqliteRow) -> anyhow::Result<TradeRecord> {
+        use sqlx::Row;
+        Ok(TradeRecord {
+            exchange_ts_ms: row.try_get("exchange_ts_ms")?,
+            monotonic_ns: row.try_get::<i64, _>("monotonic_ns")? as u64,
+            symbol: row.try_get("symbol")?,
+            side: row.try_get("side")?,
+            price: row.try_get("price")?,
+            quantity: row.try_get("quantity")?,
+            pnl: row.try_get("pnl")?,
+            strategy: row.try_get("strategy")?,
+            order_id: row.try_get("order_id")?,
+            exec_id: row.try_get("exec_id")?,
+            fee: row.try_get("fee")?,
+            fee_currency: row.try_get("fee_currency")?,
+            raw: row.try_get("raw")?,
+            decision_latency_ns: row
+                .try_get::<Option<i64>, _>("decision_latency_ns")?
+                .map(|ns| ns as u64),
+            tick_to_order_ns: row
+                .try_get::<Option<i64>, _>("tick_to_order_ns")?
+                .map(|ns| ns as u64),
+        })
+    }
+
+    /// Newest first, skipping the `offset` most recent.
+    pub async fn get_recent_trades(
+        &self,
+        limit: i64,
+        offset: i64,
+    ) -> anyhow::Result<Vec<TradeRecord>> {
         let rows = sqlx::query(
             r#"
             SELECT 
                 exchange_ts_ms, monotonic_ns, symbol, side, price, quantity, pnl, strategy,
-                order_id, exec_id, fee, fee_currency, raw
+                order_id, exec_id, fee, fee_currency, raw, decision_latency_ns, tick_to_order_ns
             FROM trades 
             ORDER BY id DESC 
-            LIMIT ?
+            LIMIT ? OFFSET ?
             "#,
         )
         .bind(limit)
+        .bind(offset)
         .fetch_all(&self.pool)
         .await?;
 
-        let mut trades = Vec::new();
-        for row in rows {
-            use sqlx::Row;
-            trades.push(TradeRecord {
-                exchange_ts_ms: row.try_get("exchange_ts_ms")?,
-                monotonic_ns: row.try_get::<i64, _>("monotonic_ns")? as u64,
-                symbol: row.try_get("symbol")?,
-                side: row.try_get("side")?,
-                price: row.try_get("price")?,
-                quantity: row.try_get("quantity")?,
-                pnl: row.try_get("pnl")?,
-                strategy: row.try_get("strategy")?,
-                order_id: row.try_get("order_id")?,
-                exec_id: row.try_get("exec_id")?,
-                fee: row.try_get("fee")?,
-                fee_currency: row.try_get("fee_currency")?,
-                raw: row.try_get("raw")?,
-            });
-        }
-        Ok(trades)
-    }
-
-    pub async fn flush(&self) {
-        // In a real implementation, we might send a special flush signal or wait for empty.
-        // For now, we rely on the channel drop behavior in main to finish writing.
-        // But to be safe, we can sleep briefly or implement a proper flush command.
-        // Since main awaits handles, dropping the sender in main will cause the loop to exit
-        // and flush remaining buffer.
+        rows.iter().map(Self::trade_from_row).collect()
+    }
+
+    /// Trades matching `filter`, newest first: `limit` of them after skipping `offset`.
+    pub async fn query_trades(
+        &self,
+        filter: &TradeFilter,
+        limit: i64,
+        offset: i64,
+    ) -> anyhow::Result<Vec<TradeRecord>> {
+        let mut query = QueryBuilder::<Sqlite>::new(
+            r#"
+            SELECT
+                exchange_ts_ms, monotonic_ns, symbol, side, price, quantity, pnl, strategy,
+                order_id, exec_id, fee, fee_currency, raw, decision_latency_ns, tick_to_order_ns
+            FROM trades"#,
+        );
+        Self::push_filter(&mut query, filter);
+        query
+            .push(" ORDER BY id DESC LIMIT ")
+            .push_bind(limit)
+            .push(" OFFSET ")
+            .push_bind(offset);
+
+        let rows = query.build().fetch_all(&self.pool).await?;
+        rows.iter().map(Self::trade_from_row).collect()
+    }
+
+    /// Number of trades matching `filter`, for paging.
+    pub async fn count_trades(&self, filter: &TradeFilter) -> anyhow::Result<i64> {
+        let mut query = QueryBuilder::<Sqlite>::new("SELECT COUNT(*) FROM trades");
+        Self::push_filter(&mut query, filter);
+        Ok(query
+            .build_query_scalar::<i64>()
+            .fetch_one(&self.pool)
+            .await?)
+    }
+
+    fn push_filter<'a>(query: &mut QueryBuilder<'a, Sqlite>, filter: &'a TradeFilter) {
+        query.push(" WHERE 1 = 1");
+        if let Some(symbol) = &filter.symbol {
+            query.push(" AND symbol = ").push_bind(symbol);
+        }
+        if let Some(side) = &filter.side {
+            query.push(" AND side = ").push_bind(side);
+        }
+    }
+
+    /// Waits until every trade queued before this call has been committed.
+    pub async fn flush(&self) -> anyhow::Result<()> {
+        let (ack_tx, ack_rx) = oneshot::channel();
+        self.tx
+            .send(WriterCommand::Flush(ack_tx))
+            .await
+            .map_err(|_| anyhow::anyhow!("TradeStorage writer is not running"))?;
+
+        tokio::time::timeout(Duration::from_secs(5), ack_rx)
+            .await
+            .context("Timed out waiting for DB flush")?
+            .context("TradeStorage writer dropped flush request")?;
+        Ok(())
     }
 
     pub async fn get_all_trades_asc(&self) -> anyhow::Result<Vec<TradeRecord>> {
@@ -205,7 +456,7 @@ impl TradeStorage {
             r#"
             SELECT 
                 exchange_ts_ms, monotonic_ns, symbol, side, price, quantity, pnl, strategy,
-                order_id, exec_id, fee, fee_currency, raw
+                order_id, exec_id, fee, fee_currency, raw, decision_latency_ns, tick_to_order_ns
             FROM trades 
             ORDER BY id ASC
             "#,
@@ -213,26 +464,65 @@ impl TradeStorage {
         .fetch_all(&self.pool)
         .await?;
 
-        let mut trades = Vec::new();
+        rows.iter().map(Self::trade_from_row).collect()
+    }
+
+    /// Trades with `start_ms <= exchange_ts_ms < end_ms`, oldest first.
+    pub async fn get_trades_between(
+        &self,
+        start_ms: i64,
+        end_ms: i64,
+    ) -> anyhow::Result<Vec<TradeRecord>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT 
+                exchange_ts_ms, monotonic_ns, symbol, side, price, quantity, pnl, strategy,
+                order_id, exec_id, fee, fee_currency, raw, decision_latency_ns, tick_to_order_ns
+            FROM trades 
+            WHERE exchange_ts_ms >= ? AND exchange_ts_ms < ?
+            ORDER BY exchange_ts_ms ASC, id ASC
+            "#,
+        )
+        .bind(start_ms)
+        .bind(end_ms)
+        .fetch_all(&self.pool)
+        .await?;
+
+        rows.iter().map(Self::trade_from_row).collect()
+    }
+
+    /// Realized PnL summed per UTC day, oldest first: `(YYYY-MM-DD, pnl)`.
+    pub async fn get_daily_pnl(&self) -> anyhow::Result<Vec<(String, f64)>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT
+                date(exchange_ts_ms / 1000, 'unixepoch') AS day,
+                SUM(pnl) AS pnl
+            FROM trades
+            GROUP BY day
+            ORDER BY day ASC
+            "#,
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        let mut days = Vec::with_capacity(rows.len());
         for row in rows {
             use sqlx::Row;
-            trades.push(TradeRecord {
-                exchange_ts_ms: row.try_get("exchange_ts_ms")?,
-                monotonic_ns: row.try_get::<i64, _>("monotonic_ns")? as u64,
-                symbol: row.try_get("symbol")?,
-                side: row.try_get("side")?,
-                price: row.try_get("price")?,
-                quantity: row.try_get("quantity")?,
-                pnl: row.try_get("pnl")?,
-                strategy: row.try_get("strategy")?,
-                order_id: row.try_get("order_id")?,
-                exec_id: row.try_get("exec_id")?,
-                fee: row.try_get("fee")?,
-                fee_currency: row.try_get("fee_currency")?,
-                raw: row.try_get("raw")?,
-            });
-        }
-        Ok(trades)
+            days.push((row.try_get("day")?, row.try_get("pnl")?));
+        }
+        Ok(days)
+    }
+
+    pub async fn get_stats(&self) -> anyhow::Result<TradeStats> {
+        let trades = self.get_all_trades_asc().await?;
+        Ok(TradeStats::from_trades(&trades))
+    }
+
+    /// Cheap connectivity check for readiness probes.
+    pub async fn ping(&self) -> anyhow::Result<()> {
+        sqlx::query("SELECT 1").execute(&self.pool).await?;
+        Ok(())
     }
 
     pub async fn clear_trades(&self) -> anyhow::Result<()> {
@@ -242,3 +532,386 @@ impl TradeStorage {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn temp_storage(name: &str) -> (TradeStorage, std::path::PathBuf) {
+        let path = std::env::temp_dir().join(format!(
+            "hft_{}_{}_{}.db",
+            name,
+            std::process::id(),
+            common::now_nanos()
+        ));
+        let storage = TradeStorage::new(path.to_str().unwrap())
+            .await
+            .expect("Failed to open DB");
+        (storage, path)
+    }
+
+    fn cleanup(path: &std::path::Path) {
+        for suffix in ["", "-wal", "-shm"] {
+            let _ = std::fs::remove_file(format!("{}{}", path.display(), suffix));
+        }
+    }
+
+    fn trade(exchange_ts_ms: i64, pnl: f64) -> TradeRecord {
+        TradeRecord {
+            exchange_ts_ms,
+            monotonic_ns: 0,
+            symbol: "BTCUSDT".to_string(),
+            side: "Buy".to_string(),
+            price: 50_000.0,
+            quantity: 0.01,
+            pnl,
+            strategy: "PING_PONG".to_string(),
+            order_id: None,
+            exec_id: None,
+            fee: None,
+            fee_currency: None,
+            raw: None,
+            decision_latency_ns: None,
+            tick_to_order_ns: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_daily_pnl_buckets() {
+        let (storage, path) = temp_storage("daily_pnl").await;
+
+        // 2023-11-14 and 2023-11-15 (UTC)
+        let trades = vec![
+            trade(1_700_000_000_000, 1.5),
+            trade(1_700_000_100_000, -0.5),
+            trade(1_700_090_000_000, 2.0),
+        ];
+        TradeStorage::flush_buffer(&storage.pool, &trades)
+            .await
+            .expect("Failed to insert");
+
+        let daily = storage.get_daily_pnl().await.expect("Query failed");
+        assert_eq!(
+            daily,
+            vec![
+                ("2023-11-14".to_string(), 1.0),
+                ("2023-11-15".to_string(), 2.0)
+            ]
+        );
+
+        cleanup(&path);
+    }
+
+    #[tokio::test]
+    async fn test_ping() {
+        let (storage, path) = temp_storage("ping").await;
+        storage.ping().await.expect("Ping failed");
+
+        storage.pool.close().await;
+        assert!(storage.ping().await.is_err());
+        cleanup(&path);
+    }
+
+    #[tokio::test]
+    async fn test_trades_between_is_half_open() {
+        let (storage, path) = temp_storage("between").await;
+
+        let trades: Vec<TradeRecord> = (0..5).map(|i| trade(1_000 * i, i as f64)).collect();
+        TradeStorage::flush_buffer(&storage.pool, &trades)
+            .await
+            .expect("Failed to insert");
+
+        let range = storage
+            .get_trades_between(1_000, 4_000)
+            .await
+            .expect("Query failed");
+        let ts: Vec<i64> = range.iter().map(|t| t.exchange_ts_ms).collect();
+        assert_eq!(ts, vec![1_000, 2_000, 3_000]);
+
+        cleanup(&path);
+    }
+
+    #[tokio::test]
+    async fn test_flush_persists_all_queued_trades() {
+        let (storage, path) = temp_storage("flush").await;
+
+        for i in 0..150 {
+            storage
+                .insert_trade(trade(1_700_000_000_000 + i, 0.0))
+                .await;
+        }
+        storage.flush().await.expect("Flush failed");
+
+        let trades = storage
+            .get_recent_trades(1000, 0)
+            .await
+            .expect("Query failed");
+        assert_eq!(trades.len(), 150);
+
+        cleanup(&path);
+    }
+
+    #[tokio::test]
+    async fn test_migrates_unversioned_db() {
+        let path = std::env::temp_dir().join(format!(
+            "hft_migrate_{}_{}.db",
+            std::process::id(),
+            common::now_nanos()
+        ));
+
+        // Fixture: a DB from before schema versioning (user_version 0, no latency columns)
+        {
+            let options =
+                sqlx::sqlite::SqliteConnectOptions::from_str(&format!("sqlite:{}", path.display()))
+                    .unwrap()
+                    .create_if_missing(true);
+            let pool = SqlitePoolOptions::new()
+                .connect_with(options)
+                .await
+                .expect("Failed to connect");
+            sqlx::query(
+                "CREATE TABLE trades (id INTEGER PRIMARY KEY AUTOINCREMENT, exchange_ts_ms INTEGER, \
+                 monotonic_ns INTEGER, symbol TEXT, side TEXT, price REAL, quantity REAL, pnl REAL, \
+                 strategy TEXT, order_id TEXT, exec_id TEXT, fee REAL, fee_currency TEXT, raw TEXT)",
+            )
+            .execute(&pool)
+            .await
+            .expect("Failed to create legacy table");
+            sqlx::query(
+                "INSERT INTO trades (exchange_ts_ms, symbol, pnl) VALUES (1600000000000, 'BTCUSDT', 2.5)",
+            )
+            .execute(&pool)
+            .await
+            .expect("Failed to insert legacy row");
+            pool.close().await;
+        }
+
+        // Reopening a migrated DB is a no-op
+        drop(
+            TradeStorage::new(path.to_str().unwrap())
+                .await
+                .expect("Migration failed"),
+        );
+        let storage = TradeStorage::new(path.to_str().unwrap())
+            .await
+            .expect("Reopen failed");
+        let version: i64 = sqlx::query_scalar("PRAGMA user_version")
+            .fetch_one(&storage.pool)
+            .await
+            .unwrap();
+        assert_eq!(version, SCHEMA_VERSION);
+
+        let mut record = trade(1_700_000_000_000, 1.0);
+        record.decision_latency_ns = Some(42_000);
+        record.tick_to_order_ns = Some(65_000);
+        TradeStorage::flush_buffer(&storage.pool, &[record])
+            .await
+            .expect("Failed to insert");
+
+        let trades = storage.get_all_trades_asc().await.expect("Query failed");
+        assert_eq!(trades.len(), 2);
+        // Legacy row kept, new columns empty
+        assert_eq!(trades[0].pnl, 2.5);
+        assert_eq!(trades[0].decision_latency_ns, None);
+        assert_eq!(trades[1].decision_latency_ns, Some(42_000));
+        assert_eq!(trades[1].tick_to_order_ns, Some(65_000));
+
+        cleanup(&path);
+    }
+
+    #[test]
+    fn test_order_latency_measure() {
+        let instruction = TradeInstruction {
+            dry_run: true,
+            tick_timestamp: 400,
+            ..TradeInstruction::market("BTCUSDT", common::Side::Buy, 50_000.0, 0.01, 1_000)
+        };
+        let latency = OrderLatency::measure(&instruction, 1_500);
+        assert_eq!(latency.decision_ns, Some(500));
+        assert_eq!(latency.tick_to_order_ns, Some(1_100));
+
+        let untimed = TradeInstruction {
+            tick_timestamp: 0,
+            ..instruction
+        };
+        assert_eq!(
+            OrderLatency::measure(&untimed, 1_500).tick_to_order_ns,
+            None
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn test_concurrent_reads_during_writes() {
+        let (storage, path) = temp_storage("concurrent").await;
+
+        let readers: Vec<_> = (0..10)
+            .map(|_| {
+                let storage = storage.clone();
+                tokio::spawn(async move {
+                    for _ in 0..20 {
+                        storage.get_recent_trades(50, 0).await?;
+                        tokio::task::yield_now().await;
+                    }
+                    anyhow::Ok(())
+                })
+            })
+            .collect();
+
+        for i in 0..500 {
+            storage
+                .insert_trade(trade(1_700_000_000_000 + i, 0.0))
+                .await;
+            if i % 100 == 0 {
+                storage.flush().await.expect("Flush failed");
+            }
+        }
+        storage.flush().await.expect("Flush failed");
+
+        for reader in readers {
+            reader
+                .await
+                .expect("Reader panicked")
+                .expect("Read failed while writing");
+        }
+        let trades = storage
+            .get_recent_trades(1000, 0)
+            .await
+            .expect("Query failed");
+        assert_eq!(trades.len(), 500);
+
+        cleanup(&path);
+    }
+
+    #[tokio::test]
+    async fn test_query_trades_filters() {
+        let (storage, path) = temp_storage("query_filter").await;
+
+        let mut trades = Vec::new();
+        for (i, (symbol, side)) in [
+            ("BTCUSDT", "Buy"),
+            ("ETHUSDT", "Buy"),
+            ("BTCUSDT", "Sell"),
+            ("BTCUSDT", "Buy"),
+        ]
+        .iter()
+        .enumerate()
+        {
+            let mut record = trade(1_700_000_000_000 + i as i64, i as f64);
+            record.symbol = symbol.to_string();
+            record.side = side.to_string();
+            trades.push(record);
+        }
+        TradeStorage::flush_buffer(&storage.pool, &trades)
+            .await
+            .expect("Failed to insert");
+
+        let pnls = |trades: Vec<TradeRecord>| trades.iter().map(|t| t.pnl).collect::<Vec<_>>();
+        let btc_buys = TradeFilter {
+            symbol: Some("BTCUSDT".to_string()),
+            side: Some("Buy".to_string()),
+        };
+        assert_eq!(
+            pnls(storage.query_trades(&btc_buys, 10, 0).await.unwrap()),
+            vec![3.0, 0.0]
+        );
+
+        let sells = TradeFilter {
+            side: Some("Sell".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(
+            pnls(storage.query_trades(&sells, 10, 0).await.unwrap()),
+            vec![2.0]
+        );
+
+        // Bound, not interpolated
+        let injected = TradeFilter {
+            symbol: Some("BTCUSDT' OR '1'='1".to_string()),
+            ..Default::default()
+        };
+        assert!(storage
+            .query_trades(&injected, 10, 0)
+            .await
+            .unwrap()
+            .is_empty());
+        assert_eq!(
+            storage
+                .query_trades(&TradeFilter::default(), 2, 0)
+                .await
+                .unwrap()
+                .len(),
+            2
+        );
+
+        cleanup(&path);
+    }
+
+    #[tokio::test]
+    async fn test_offset_paging() {
+        let (storage, path) = temp_storage("paging").await;
+
+        let trades: Vec<TradeRecord> = (0..7)
+            .map(|i| trade(1_700_000_000_000 + i, i as f64))
+            .collect();
+        TradeStorage::flush_buffer(&storage.pool, &trades)
+            .await
+            .expect("Failed to insert");
+
+        let mut pages = Vec::new();
+        for offset in (0..).step_by(3).take(3) {
+            let page = storage.get_recent_trades(3, offset).await.unwrap();
+            pages.push(page.iter().map(|t| t.pnl).collect::<Vec<_>>());
+        }
+        // Newest first, no gaps or overlaps, short last page
+        assert_eq!(
+            pages,
+            vec![vec![6.0, 5.0, 4.0], vec![3.0, 2.0, 1.0], vec![0.0]]
+        );
+        assert!(storage.get_recent_trades(3, 9).await.unwrap().is_empty());
+
+        let buys = TradeFilter {
+            side: Some("Buy".to_string()),
+            ..Default::default()
+        };
+        let page = storage.query_trades(&buys, 2, 5).await.unwrap();
+        assert_eq!(
+            page.iter().map(|t| t.pnl).collect::<Vec<_>>(),
+            vec![1.0, 0.0]
+        );
+        assert_eq!(storage.count_trades(&buys).await.unwrap(), 7);
+        let sells = TradeFilter {
+            side: Some("Sell".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(storage.count_trades(&sells).await.unwrap(), 0);
+
+        cleanup(&path);
+    }
+
+    #[test]
+    fn test_equity_curve() {
+        let trades = vec![trade(1_000, 10.0), trade(2_000, -4.0), trade(3_000, 1.5)];
+        assert_eq!(
+            equity_curve(&trades, 1_000.0),
+            vec![(1_000, 1_010.0), (2_000, 1_006.0), (3_000, 1_007.5)]
+        );
+        assert!(equity_curve(&[], 1_000.0).is_empty());
+    }
+
+    #[test]
+    fn test_stats_max_drawdown() {
+        // Cumulative: 10, 5, 25, 5, -5, 15 -> peak 25, trough -5 => drawdown 30
+        let trades: Vec<TradeRecord> = [10.0, -5.0, 20.0, -20.0, -10.0, 20.0]
+            .iter()
+            .map(|&pnl| trade(0, pnl))
+            .collect();
+
+        let stats = TradeStats::from_trades(&trades);
+        assert_eq!(stats.total_trades, 6);
+        assert_eq!(stats.win_count, 3);
+        assert_eq!(stats.loss_count, 3);
+        assert_eq!(stats.gross_profit, 50.0);
+        assert_eq!(stats.gross_loss, 35.0);
+        assert_eq!(stats.max_drawdown, 30.0);
+    }
+}
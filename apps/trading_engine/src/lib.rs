@@ -0,0 +1,9 @@
+//! Engine modules shared by the `trading_engine` binary and offline tools (e.g. `backtest`).
+
+pub mod config;
+pub mod db;
+pub mod paper;
+pub mod recording;
+pub mod server;
+pub mod state;
+pub mod trailing;
@@ -1,15 +1,13 @@
-mod config;
-mod db;
-mod server;
-mod state;
-
-use crate::state::EngineState;
 use execution::ExecutionClient;
+use std::collections::HashMap;
 use std::sync::{
     atomic::{AtomicBool, Ordering},
     Arc,
 };
 use tokio::sync::mpsc;
+use trading_engine::paper::PaperFillSimulator;
+use trading_engine::state::EngineState;
+use trading_engine::{config, db, server, state, trailing};
 
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
@@ -24,7 +22,15 @@ async fn main() -> anyhow::Result<()> {
     };
 
     // 2. Initialize Telemetry (Once)
-    let _guard = telemetry::init("./logs");
+    let recent_logs = telemetry::new_log_buffer();
+    let log_stream = telemetry::new_log_stream();
+    let _guard = telemetry::init(
+        "./logs",
+        config.telemetry.log_filter.as_deref(),
+        config.telemetry.rotation,
+        recent_logs.clone(),
+        log_stream.clone(),
+    );
     tracing::info!("Starting Trading Engine...");
     tracing::info!(
         "Config loaded: Network={}, DryRun={}",
@@ -50,7 +56,7 @@ async fn main() -> anyhow::Result<()> {
 
     // 4. Main Loop
     loop {
-        match run_engine(&config, &mut reload_rx).await {
+        match run_engine(&config, &recent_logs, &log_stream, &mut reload_rx).await {
             Ok(should_reload) => {
                 if !should_reload {
                     break;
@@ -73,23 +79,114 @@ async fn main() -> anyhow::Result<()> {
 
 async fn run_engine(
     config: &config::AppConfig,
+    recent_logs: &telemetry::LogBuffer,
+    log_stream: &tokio::sync::broadcast::Sender<telemetry::LogLine>,
     reload_rx: &mut mpsc::UnboundedReceiver<()>,
 ) -> anyhow::Result<bool> {
     // 3. Initialize Shared State
-    let state = Arc::new(EngineState::new());
+    let state = Arc::new(EngineState::with_logs(
+        recent_logs.clone(),
+        log_stream.clone(),
+    ));
+    state
+        .dry_run
+        .store(config.trading.dry_run, Ordering::Relaxed);
+    state.allow_mainnet.store(
+        config.trading.allow_mainnet.unwrap_or(false),
+        Ordering::Relaxed,
+    );
+    *state.symbol.lock() = config.trading.symbol.clone();
+    if let Some(capacity) = config.trading.pnl_history_capacity {
+        state
+            .pnl_history_capacity
+            .store(capacity, Ordering::Relaxed);
+    }
     // Initialize limits from config
     *state.max_loss_limit.lock() = config.risk.max_drawdown; // Using max_drawdown as initial max_loss
                                                              // target_profit is 0.0 by default, can be set via API
+    *state.risk_limits.lock() = risk_engine::RiskLimits {
+        max_order_size: config.risk.max_order_size,
+        max_daily_loss: config.risk.max_drawdown,
+        max_position: config.risk.max_position,
+    };
 
     // 4. Initialize Database
-    let db = db::TradeStorage::new("trading.db").await?;
+    let db = db::TradeStorage::with_max_connections(
+        "trading.db",
+        config
+            .database
+            .max_connections
+            .unwrap_or(db::DEFAULT_MAX_CONNECTIONS),
+    )
+    .await?;
     tracing::info!("Database connected");
 
-    // 5. Spawn Web Server
-    let server_state = state.clone();
-    let server_db = db.clone();
+    // 5. Spawn Feed Watchdog (auto-stop on a silently stalled feed)
+    let stale_timeout_secs = config.trading.stale_timeout_secs.unwrap_or(30);
+    if stale_timeout_secs > 0 {
+        let watchdog_state = state.clone();
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(std::time::Duration::from_secs(1));
+            loop {
+                interval.tick().await;
+                let now_ms = std::time::SystemTime::now()
+                    .duration_since(std::time::UNIX_EPOCH)
+                    .unwrap()
+                    .as_millis() as u64;
+                if watchdog_state.stop_if_feed_stale(now_ms, stale_timeout_secs * 1000) {
+                    risk_engine::disarm();
+                    tracing::error!(
+                        "CRITICAL: No market data for {}s. Trading STOPPED and risk disarmed.",
+                        stale_timeout_secs
+                    );
+                    watchdog_state.add_log(format!(
+                        "CRITICAL: Feed stale for {}s - trading stopped",
+                        stale_timeout_secs
+                    ));
+                }
+            }
+        });
+    }
+
+    // 5b. Spawn Kill-Switch Watcher (ops halt independent of the HTTP API).
+    // While the file exists trading stays disarmed; removing it does NOT re-arm.
+    let kill_switch_path = std::path::PathBuf::from(
+        config
+            .trading
+            .kill_switch_path
+            .clone()
+            .unwrap_or_else(|| "HALT".to_string()),
+    );
+    let kill_switch_state = state.clone();
     tokio::spawn(async move {
-        server::run(server_state, server_db).await;
+        let mut interval = tokio::time::interval(std::time::Duration::from_millis(500));
+        let mut halted = false;
+        loop {
+            interval.tick().await;
+            if !kill_switch_path.exists() {
+                if halted {
+                    tracing::warn!(
+                        "Kill switch {} removed. Re-arm explicitly via the API",
+                        kill_switch_path.display()
+                    );
+                    halted = false;
+                }
+                continue;
+            }
+            let was_running = kill_switch_state.is_running.swap(false, Ordering::SeqCst);
+            if !halted || was_running || risk_engine::is_armed() {
+                risk_engine::disarm();
+                tracing::error!(
+                    "CRITICAL: Kill switch {} present. Trading STOPPED and risk disarmed.",
+                    kill_switch_path.display()
+                );
+                kill_switch_state.add_log(format!(
+                    "CRITICAL: Kill switch {} - trading stopped",
+                    kill_switch_path.display()
+                ));
+                halted = true;
+            }
+        }
     });
 
     // 6. Spawn Speed Meter Task
@@ -109,32 +206,299 @@ async fn run_engine(
     let api_key = config.trading.api_key.clone().unwrap_or_default();
     let secret_key = config.trading.secret_key.clone().unwrap_or_default();
 
+    // Orders and fills go to Binance: other feeds are for dry-run / paper trading only
+    if config.trading.enabled
+        && !config.trading.dry_run
+        && !config.network.exchange.eq_ignore_ascii_case("binance")
+    {
+        tracing::error!(
+            "Live trading requires exchange = \"binance\" (got {:?})",
+            config.network.exchange
+        );
+        return Ok(false);
+    }
+    let (status_tx, mut status_rx) =
+        tokio::sync::watch::channel(feed_handler::FeedStatus::Disconnected);
+    let feed_source = match feed_handler::feed_source(
+        &config.network.exchange,
+        &config.network.ws_url,
+        Some(status_tx),
+        Some(state.feed_dropped_ticks.clone()),
+    ) {
+        Ok(source) => source,
+        Err(e) => {
+            tracing::error!("Invalid feed config: {}", e);
+            return Ok(false);
+        }
+    };
+
     if config.trading.enabled && (api_key.is_empty() || secret_key.is_empty()) {
         tracing::error!("Trading enabled but API keys missing!");
         return Ok(false);
     }
 
-    let execution_client = Arc::new(ExecutionClient::new(
-        api_key,
-        secret_key,
+    let mut execution_client = ExecutionClient::new(
+        api_key.clone(),
+        secret_key.clone(),
         config.network.rest_url.clone(),
-    ));
+        config.network.recv_window_ms,
+    );
+    if config.trading.sign_scheme != execution::SignScheme::HmacSha256 && !secret_key.is_empty() {
+        match execution::BinanceSigner::with_scheme(api_key, secret_key, config.trading.sign_scheme)
+        {
+            Ok(signer) => execution_client = execution_client.with_signer(signer),
+            Err(e) => {
+                tracing::error!("CRITICAL: {}", e);
+                return Ok(false);
+            }
+        }
+    }
+    let execution_client = Arc::new(execution_client);
+
+    // Guardrail: live Mainnet trading must be explicitly acknowledged
+    if execution_client.is_mainnet()
+        && config.trading.enabled
+        && !config.trading.dry_run
+        && !config.trading.allow_mainnet.unwrap_or(false)
+    {
+        tracing::error!(
+            "CRITICAL: {} is a MAINNET endpoint. Set `allow_mainnet = true` in [trading] to trade live.",
+            config.network.rest_url
+        );
+        return Ok(false);
+    }
+    tracing::info!("Execution network: {:?}", execution_client.network());
+
+    // 7b. Server Time Sync (periodic, to keep signed timestamps inside recvWindow)
+    match execution_client.sync_time().await {
+        Ok(offset) => tracing::info!("Server time synced: offset {} ms", offset),
+        Err(e) => tracing::warn!("Failed to sync server time: {}", e),
+    }
+
+    // Credentials check: a wrong secret only surfaces as a rejected signature, so fail
+    // now rather than on the first trade
+    if config.trading.enabled {
+        match execution_client.verify_credentials().await {
+            Ok(()) => tracing::info!("API credentials verified"),
+            Err(e) if execution::is_auth_error(&e) => {
+                tracing::error!(
+                    "CRITICAL: API key/secret rejected by {} ({}). Check api_key and secret_key in [trading]. Exiting.",
+                    config.network.rest_url,
+                    e
+                );
+                return Ok(false);
+            }
+            Err(e) => tracing::warn!("Could not verify API credentials: {}", e),
+        }
+    }
+
+    let time_sync_client = execution_client.clone();
+    let time_sync_handle = tokio::spawn(async move {
+        let mut interval = tokio::time::interval(std::time::Duration::from_secs(600));
+        interval.tick().await; // First tick fires immediately; already synced above
+        loop {
+            interval.tick().await;
+            match time_sync_client.sync_time().await {
+                Ok(offset) => tracing::debug!("Server time re-synced: offset {} ms", offset),
+                Err(e) => tracing::warn!("Failed to re-sync server time: {}", e),
+            }
+        }
+    });
+
+    // Symbol precision, so live orders respect tickSize/stepSize/minQty
+    match execution_client
+        .load_symbol_filters(&config.trading.symbol)
+        .await
+    {
+        Ok(filters) => tracing::info!(
+            "Symbol filters for {}: {:?}",
+            config.trading.symbol,
+            filters
+        ),
+        Err(e) => tracing::warn!(
+            "Failed to load symbol filters for {}: {}. Orders are sent unrounded",
+            config.trading.symbol,
+            e
+        ),
+    }
+
+    // 7c. Leverage / Margin Mode (must be in place before the first order)
+    if config.trading.enabled {
+        if let Some(isolated) = config.trading.margin_isolated {
+            if let Err(e) = execution_client
+                .set_margin_type(&config.trading.symbol, isolated)
+                .await
+            {
+                tracing::error!("CRITICAL: Failed to set margin type: {}", e);
+                return Ok(false);
+            }
+            tracing::info!(
+                "Margin type for {}: {}",
+                config.trading.symbol,
+                if isolated { "ISOLATED" } else { "CROSSED" }
+            );
+        }
+        if let Some(leverage) = config.trading.leverage {
+            if let Err(e) = execution_client
+                .set_leverage(&config.trading.symbol, leverage)
+                .await
+            {
+                tracing::error!("CRITICAL: Failed to set leverage: {}", e);
+                return Ok(false);
+            }
+            tracing::info!("Leverage for {}: {}x", config.trading.symbol, leverage);
+        }
+    }
+
+    // 7d. User-Data Stream: confirmed fills drive PnL/position for live orders.
+    // Without it (no keys, listenKey failure) live orders fall back to optimistic accounting.
+    let mut fill_rx = None;
+    let mut listen_key_handle = None;
+    if config.trading.enabled {
+        match execution_client.create_listen_key().await {
+            Ok(listen_key) => {
+                let (key_tx, key_rx) = tokio::sync::watch::channel(listen_key);
+                let key_invalid = Arc::new(tokio::sync::Notify::new());
+                let reconnected = Arc::new(tokio::sync::Notify::new());
+                match execution::start_user_stream(
+                    &config.network.ws_url,
+                    key_rx,
+                    key_invalid.clone(),
+                    reconnected.clone(),
+                )
+                .await
+                {
+                    Ok(rx) => {
+                        fill_rx = Some(rx);
+                        // Fills missed while the stream was down: take the position from
+                        // the exchange instead
+                        let resync_client = execution_client.clone();
+                        let resync_state = state.clone();
+                        let resync_symbol = config.trading.symbol.clone();
+                        tokio::spawn(async move {
+                            loop {
+                                reconnected.notified().await;
+                                resync_position(
+                                    &resync_state,
+                                    &resync_client,
+                                    &resync_symbol,
+                                    "user stream reconnected",
+                                )
+                                .await;
+                            }
+                        });
+                        listen_key_handle = Some(tokio::spawn(maintain_listen_key(
+                            execution_client.clone(),
+                            key_tx,
+                            key_invalid,
+                        )));
+                    }
+                    Err(e) => tracing::warn!("Failed to start user-data stream: {}", e),
+                }
+            }
+            Err(e) => tracing::warn!("Failed to obtain listenKey: {}", e),
+        }
+    }
+    let fills_confirmed = fill_rx.is_some();
+    if !fills_confirmed {
+        tracing::warn!("No user-data stream: live fills are accounted optimistically");
+    }
+    // Strategy name and latency per live order, keyed by client_order_id so confirmed fills
+    // are attributed correctly. Entered before the order is sent: the fill can arrive on
+    // the user stream before the REST response does.
+    let order_strategies: Arc<parking_lot::Mutex<HashMap<String, (String, db::OrderLatency)>>> =
+        Arc::default();
+
+    // 5. Spawn Web Server
+    // High-priority FLATTEN requests bypass the strategy ring buffer
+    let (flatten_tx, mut flatten_rx) = mpsc::channel::<()>(1);
+
+    // 5c. Spawn Max-Hold Watchdog (force-close positions held too long, via FLATTEN)
+    let max_hold_secs = config.trading.max_hold_secs.unwrap_or(0);
+    if max_hold_secs > 0 {
+        let hold_state = state.clone();
+        let hold_flatten_tx = flatten_tx.clone();
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(std::time::Duration::from_secs(1));
+            loop {
+                interval.tick().await;
+                let now_ms = std::time::SystemTime::now()
+                    .duration_since(std::time::UNIX_EPOCH)
+                    .unwrap()
+                    .as_millis() as u64;
+                if hold_state.take_expired_position(now_ms, max_hold_secs * 1000) {
+                    let position = *hold_state.current_position.lock();
+                    request_flatten(
+                        &hold_state,
+                        &hold_flatten_tx,
+                        format!(
+                            "MAX HOLD: Position {} held > {}s - forcing exit",
+                            position, max_hold_secs
+                        ),
+                    );
+                }
+            }
+        });
+    }
+
+    // 5d. Spawn Trailing-Stop Watchdog (reduce-only close via FLATTEN)
+    let trailing_pct = config.risk.trailing_pct.unwrap_or(0.0);
+    if trailing_pct > 0.0 {
+        let trail_state = state.clone();
+        let trail_flatten_tx = flatten_tx.clone();
+        tokio::spawn(async move {
+            let mut stop = trailing::TrailingStop::new(trailing_pct);
+            let mut interval = tokio::time::interval(std::time::Duration::from_millis(100));
+            loop {
+                interval.tick().await;
+                let position = *trail_state.current_position.lock();
+                let avg_entry = *trail_state.avg_entry_price.lock();
+                let last_price = *trail_state.last_price.lock();
+                if stop.update(position, avg_entry, last_price) {
+                    request_flatten(
+                        &trail_state,
+                        &trail_flatten_tx,
+                        format!(
+                            "TRAILING STOP: Closing {} @ {} ({}% retrace)",
+                            position, last_price, trailing_pct
+                        ),
+                    );
+                }
+            }
+        });
+    }
+    let server_state = state.clone();
+    let server_db = db.clone();
+    let server_client = execution_client.clone();
+    let server_addr = config.server.socket_addr()?;
+    let (shutdown_tx, _) = tokio::sync::broadcast::channel(1);
+    let shutdown_rx_server = shutdown_tx.subscribe();
+    tokio::spawn(async move {
+        if let Err(e) = server::run(
+            server_state,
+            server_db,
+            flatten_tx,
+            server_client,
+            server_addr,
+            shutdown_rx_server,
+        )
+        .await
+        {
+            tracing::error!("Web server failed: {:#}", e);
+        }
+    });
 
     // 8. Initialize Risk Engine
-    let mut risk_engine =
-        risk_engine::RiskEngine::new(config.risk.max_order_size, config.risk.max_drawdown);
+    let mut risk_engine = risk_engine::RiskEngine::with_shared_limits(state.risk_limits.clone())
+        .with_max_slippage_pct(config.risk.max_slippage_pct.unwrap_or(0.0));
 
     // 9. Position Sync
     tracing::info!("Syncing positions...");
-    match execution_client.sync_positions().await {
+    let symbol = config.trading.symbol.clone();
+    match execution_client.sync_positions(Some(&symbol)).await {
         Ok(positions) => {
             tracing::info!("Position sync OK: {} positions found", positions.len());
-            for p in positions {
-                if p.symbol == "BTCUSDT" {
-                    *state.current_position.lock() = p.position_amt.parse::<f64>().unwrap_or(0.0);
-                    tracing::info!("  Active Position: {} = {}", p.symbol, p.position_amt);
-                }
-            }
+            apply_position(&state, &symbol, &positions);
         }
         Err(e) => {
             tracing::warn!("Failed to sync positions: {}", e);
@@ -171,15 +535,21 @@ async fn run_engine(
     }
 
     // 10. Setup Ring Buffers
-    let (producer, consumer) = rtrb::RingBuffer::<common::MarketEvent>::new(4096);
+    let market_capacity = config.trading.market_buffer_capacity.unwrap_or(4096);
+    let signal_capacity = config.trading.signal_buffer_capacity.unwrap_or(4096);
+    tracing::info!(
+        "Ring buffers: market={} signal={}",
+        market_capacity,
+        signal_capacity
+    );
+    let (producer, consumer) = rtrb::RingBuffer::<common::MarketEvent>::new(market_capacity);
     let (signal_producer, mut signal_consumer) =
-        rtrb::RingBuffer::<common::TradeInstruction>::new(4096);
+        rtrb::RingBuffer::<common::ExecSignal>::new(signal_capacity);
 
     // 11. Shutdown Signals
     let shutdown = Arc::new(AtomicBool::new(false));
     let shutdown_clone = shutdown.clone();
 
-    let (shutdown_tx, _) = tokio::sync::broadcast::channel(1);
     let mut shutdown_rx_execution = shutdown_tx.subscribe();
     let shutdown_tx_ctrlc = shutdown_tx.clone();
     let shutdown_signal = shutdown.clone();
@@ -188,20 +558,74 @@ async fn run_engine(
     let is_running_flag = state.is_running.clone();
     let dry_run_config = config.trading.dry_run;
     let active_strategy = state.active_strategy.clone();
-    let fee_maker = config.trading.fee_maker;
-    let fee_taker = config.trading.fee_taker;
-    let strategy_window = config.trading.strategy_window.unwrap_or(50);
-    let strategy_threshold = config.trading.strategy_threshold.unwrap_or(2.0);
-    let price_threshold = config.trading.price_threshold.unwrap_or(10.0);
-    let volume_multiplier = config.trading.volume_multiplier.unwrap_or(3.0);
+    let defaults = strategy::StrategyParams::default();
+    *state.strategy_params.lock() = strategy::StrategyParams {
+        fee_maker: config.trading.fee_maker,
+        fee_taker: config.trading.fee_taker,
+        window: config.trading.strategy_window.unwrap_or(defaults.window),
+        threshold: config
+            .trading
+            .strategy_threshold
+            .unwrap_or(defaults.threshold),
+        price_threshold: config
+            .trading
+            .price_threshold
+            .unwrap_or(defaults.price_threshold),
+        volume_multiplier: config
+            .trading
+            .volume_multiplier
+            .unwrap_or(defaults.volume_multiplier),
+        limit_offset_ticks: config
+            .trading
+            .limit_offset_ticks
+            .unwrap_or(defaults.limit_offset_ticks),
+        use_limit_orders: config
+            .trading
+            .use_limit_orders
+            .unwrap_or(defaults.use_limit_orders),
+        order_quantity: config
+            .trading
+            .order_quantity
+            .unwrap_or(defaults.order_quantity),
+        cooldown_ns: config.trading.cooldown_ns.unwrap_or(defaults.cooldown_ns),
+    };
+    let strategy_params = state.strategy_params.clone();
+    let latency_warn_ns = config.trading.latency_warn_ns.unwrap_or(1_000_000);
+    let stale_ticks = state.stale_ticks.clone();
+    let last_strategy_latency_ns = state.last_strategy_latency_ns.clone();
+    let engine_position = state.current_position.clone();
+    let strategy_reset = state.strategy_reset.clone();
+
+    // 13b. Seed the strategy window from the latest trades so it can trade on the first tick.
+    // Windows count trades, so seeding from trades (not candle closes) keeps thresholds such
+    // as Momentum's velocity on the same scale as live ticks.
+    let mut seed_prices = Vec::new();
+    if config.trading.seed_from_trades.unwrap_or(true) {
+        let window = state.strategy_params.lock().window;
+        match execution_client
+            .get_agg_trades(&symbol, window.min(u16::MAX as usize) as u16)
+            .await
+        {
+            Ok(trades) => seed_prices = trades.iter().map(|t| t.price).collect(),
+            Err(e) => tracing::warn!("Failed to fetch trades for warm-up, starting cold: {}", e),
+        }
+    }
 
+    let strategy_core = config.performance.strategy_core;
+    let wait_strategy = config.performance.wait_strategy;
     let strategy_handle = std::thread::spawn(move || {
-        // Pin to the last available core
-        if let Some(core_ids) = core_affinity::get_core_ids() {
-            if let Some(core_id) = core_ids.last() {
-                core_affinity::set_for_current(*core_id);
-                tracing::info!("Strategy thread pinned to core {:?}", core_id);
+        match strategy_core {
+            Some(core) => {
+                let core_id = core_affinity::get_core_ids()
+                    .and_then(|ids| ids.into_iter().find(|id| id.id == core));
+                match core_id {
+                    Some(core_id) if core_affinity::set_for_current(core_id) => {
+                        tracing::info!("Strategy thread pinned to core {}", core)
+                    }
+                    _ => tracing::warn!("Failed to pin strategy thread to core {}", core),
+                }
             }
+            None => tracing::info!("Strategy thread pinning disabled"),
         }
         strategy::run(
             consumer,
@@ -209,23 +633,39 @@ async fn run_engine(
             shutdown_clone,
             is_running_flag,
             active_strategy,
+            strategy_params,
             dry_run_config,
             false,
-            fee_maker,
-            fee_taker,
-            strategy_window,
-            strategy_threshold,
-            price_threshold,
-            volume_multiplier,
+            latency_warn_ns,
+            stale_ticks,
+            last_strategy_latency_ns,
+            engine_position,
+            strategy_reset,
+            seed_prices,
+            wait_strategy,
         );
     });
 
     // 14. Spawn Execution Task
+    // Confirmed fills count against the same realized-loss limit
+    let risk_fills = risk_engine.clone();
     let execution_client_task = execution_client.clone();
     let state_exec = state.clone();
     let db_exec = db.clone();
+    let fee_maker = config.trading.fee_maker;
     let fee_taker = config.trading.fee_taker;
 
+    let flatten_symbol = symbol.clone();
+    let order_strategies_exec = order_strategies.clone();
+    let paper_sim = config.trading.paper_trading.unwrap_or(false).then(|| {
+        PaperFillSimulator::new(
+            config.trading.paper_slippage_bps.unwrap_or(1.0),
+            std::time::Duration::from_millis(config.trading.paper_latency_ms.unwrap_or(50)),
+        )
+    });
+    let mut failure_breaker =
+        risk_engine::FailureBreaker::new(config.risk.max_consecutive_failures.unwrap_or(5));
+
     let execution_handle = tokio::spawn(async move {
         tracing::info!("Execution task started");
         loop {
@@ -233,17 +673,142 @@ async fn run_engine(
                 break;
             }
 
+            // Emergency FLATTEN takes priority over strategy signals
+            if flatten_rx.try_recv().is_ok() {
+                let position = *state_exec.current_position.lock();
+                if position.abs() < 0.000001 {
+                    tracing::info!("FLATTEN: No open position");
+                    state_exec.add_log("FLATTEN: Already flat".to_string());
+                } else {
+                    let price = *state_exec.last_price.lock();
+                    let side = if position > 0.0 {
+                        common::Side::Sell
+                    } else {
+                        common::Side::Buy
+                    };
+                    let instruction = common::TradeInstruction {
+                        dry_run: state_exec.dry_run.load(Ordering::Relaxed),
+                        reduce_only: true,
+                        client_order_id: Some(execution::new_client_order_id("FLATTEN")),
+                        ..common::TradeInstruction::market(
+                            flatten_symbol.as_str(),
+                            side,
+                            price,
+                            position.abs(),
+                            common::now_nanos(),
+                        )
+                    };
+                    tracing::warn!("FLATTEN: Closing position {} @ ~{}", position, price);
+
+                    let latency = db::OrderLatency::measure(&instruction, common::now_nanos());
+                    let tracked_id = (fills_confirmed && !instruction.dry_run)
+                        .then(|| instruction.client_order_id.clone())
+                        .flatten();
+                    if let Some(id) = &tracked_id {
+                        order_strategies_exec
+                            .lock()
+                            .insert(id.clone(), ("FLATTEN".to_string(), latency));
+                    }
+                    let start = std::time::Instant::now();
+                    let result = execution_client_task.place_order(&instruction).await;
+                    if result.is_ok() {
+                        state_exec.record_order_rtt(start.elapsed().as_nanos() as u64);
+                    }
+                    let accepted = match &result {
+                        Ok(_) => true,
+                        Err(e) => execution::is_order_state_unknown(e),
+                    };
+                    if accepted {
+                        // The strategy still believes it is in the trade
+                        state_exec.strategy_reset.store(true, Ordering::Relaxed);
+                    }
+                    match result {
+                        Ok(response) if tracked_id.is_some() => {
+                            // Position and PnL are updated when the fill is confirmed
+                            state_exec.trade_count.fetch_add(1, Ordering::Relaxed);
+                            state_exec.add_log(format!(
+                                "FLATTEN: Close order {:?} sent for {}",
+                                response.order_id, position
+                            ));
+                        }
+                        Ok(response) => {
+                            // Market order: the simulator always fills it
+                            let price = match &paper_sim {
+                                Some(sim) if instruction.dry_run => {
+                                    sim.fill(&instruction, &state_exec).await.unwrap_or(price)
+                                }
+                                _ => price,
+                            };
+                            state_exec.trade_count.fetch_add(1, Ordering::Relaxed);
+                            let realized_pnl = book_fill(
+                                &state_exec,
+                                &db_exec,
+                                &execution_client_task,
+                                &risk_engine,
+                                &instruction,
+                                price,
+                                response,
+                                "FLATTEN".to_string(),
+                                latency,
+                                (fee_maker, fee_taker),
+                            )
+                            .await;
+                            state_exec.add_log(format!(
+                                "FLATTEN: Closed {} @ {} (PnL {:.2})",
+                                position, price, realized_pnl
+                            ));
+                        }
+                        Err(e) if execution::is_order_state_unknown(&e) => {
+                            tracing::error!("FLATTEN: {}", e);
+                            state_exec.add_log(format!("FLATTEN: {}", e));
+                            // A confirmed fill still arrives on the user stream
+                            if tracked_id.is_none() {
+                                resync_position(
+                                    &state_exec,
+                                    &execution_client_task,
+                                    &flatten_symbol,
+                                    "FLATTEN state unknown",
+                                )
+                                .await;
+                            }
+                        }
+                        Err(e) => {
+                            if let Some(id) = &tracked_id {
+                                order_strategies_exec.lock().remove(id);
+                            }
+                            tracing::error!("FLATTEN Failed: {}", e);
+                            state_exec.add_log(format!("FLATTEN Failed: {}", e));
+                        }
+                    }
+                }
+                continue;
+            }
+
             match signal_consumer.pop() {
-                Ok(instruction) => {
+                Ok(common::ExecSignal::Place(mut instruction)) => {
                     // Check if Engine is Running
                     if !state_exec.is_running.load(Ordering::Relaxed) {
                         continue;
                     }
 
+                    // Runtime dry-run switch overrides whatever the strategy set
+                    instruction.dry_run = state_exec.dry_run.load(Ordering::Relaxed);
+                    let strategy_name = state_exec.active_strategy.lock().clone();
+                    // Strategies that cancel their own orders pick the id themselves
+                    if instruction.client_order_id.is_none() {
+                        instruction.client_order_id =
+                            Some(execution::new_client_order_id(&strategy_name));
+                    }
+
                     tracing::info!("Received instruction: {:?}", instruction);
 
                     // Risk Check
-                    if let Err(e) = risk_engine.check(&instruction) {
+                    let current_position = *state_exec.current_position.lock();
+                    let market_price = *state_exec.last_price.lock();
+                    if let Err(e) = risk_engine
+                        .check(&instruction, current_position)
+                        .and_then(|_| risk_engine.check_slippage(&instruction, market_price))
+                    {
                         tracing::error!("Risk Rejection: {}", e);
                         state_exec.add_log(format!("Risk Reject: {}", e));
                         continue;
@@ -251,90 +816,156 @@ async fn run_engine(
 
                     // Measure RTT
                     let start = std::time::Instant::now();
+                    let latency = db::OrderLatency::measure(&instruction, common::now_nanos());
+                    let tracked_id = (fills_confirmed && !instruction.dry_run)
+                        .then(|| instruction.client_order_id.clone())
+                        .flatten();
+                    if let Some(id) = &tracked_id {
+                        order_strategies_exec
+                            .lock()
+                            .insert(id.clone(), (strategy_name.clone(), latency));
+                    }
 
                     match execution_client_task.place_order(&instruction).await {
                         Ok(response) => {
                             let rtt = start.elapsed().as_nanos() as u64;
-                            state_exec.last_order_rtt_ns.store(rtt, Ordering::Relaxed);
+                            state_exec.record_order_rtt(rtt);
+                            failure_breaker.record_success();
 
-                            tracing::info!("Order Placed: {}", response);
+                            tracing::info!("Order Placed: {:?}", response.order_id);
                             state_exec.trade_count.fetch_add(1, Ordering::Relaxed);
                             state_exec.add_log(format!(
                                 "Order Placed: {:?} {} @ {}",
                                 instruction.side, instruction.quantity, instruction.price
                             ));
 
-                            // Calculate PnL & Fee
-                            // Simple assumption: Market orders are Taker
-                            let fee_rate = fee_taker;
-                            let fee_amount = instruction.quantity * instruction.price * fee_rate;
-
-                            let signed_qty = match instruction.side {
-                                common::Side::Buy => instruction.quantity,
-                                common::Side::Sell => -instruction.quantity,
-                            };
-                            let realized_pnl = state_exec.update_from_trade(
-                                signed_qty,
-                                instruction.price,
-                                fee_amount,
-                            );
-
-                            // DB Insert
-                            db_exec
-                                .insert_trade(crate::db::TradeRecord {
-                                    exchange_ts_ms: std::time::SystemTime::now()
-                                        .duration_since(std::time::UNIX_EPOCH)
-                                        .unwrap()
-                                        .as_millis()
-                                        as i64,
-                                    monotonic_ns: common::now_nanos(),
-                                    symbol: instruction.symbol.to_string(),
-                                    side: format!("{:?}", instruction.side),
-                                    price: instruction.price,
-                                    quantity: instruction.quantity,
-                                    pnl: realized_pnl,
-                                    strategy: "PING_PONG".to_string(),
-                                    order_id: None, // Parse from response
-                                    exec_id: None,
-                                    fee: Some(fee_amount),
-                                    fee_currency: Some("USDT".to_string()), // Assuming USDT
-                                    raw: Some(response),
-                                })
-                                .await;
+                            if tracked_id.is_some() {
+                                // Position and PnL are updated when the fill is confirmed
+                                continue;
+                            }
+                            // A post-only quote rests on the book instead of filling now.
+                            // Without confirmed fills there is nothing to book it from.
+                            if instruction.post_only {
+                                tracing::debug!("Resting quote not booked: {:?}", instruction);
+                                continue;
+                            }
 
-                            // Auto-Stop Logic
-                            let pnl = *state_exec.current_pnl.lock();
-                            let max_loss = *state_exec.max_loss_limit.lock();
-                            let target_profit = *state_exec.target_profit.lock();
-
-                            // Update Balance After Trade
-                            if let Ok(balances) = execution_client_task.get_account_balance().await
-                            {
-                                for b in balances {
-                                    if b.asset == "USDT" {
-                                        if let Ok(available) = b.available_balance.parse::<f64>() {
-                                            *state_exec.available_balance.lock() = available;
-                                            tracing::debug!(
-                                                "Balance updated: Available = {:.2}",
-                                                available
-                                            );
+                            // Paper mode: simulated fill instead of the signal price
+                            let fill_price = match &paper_sim {
+                                Some(sim) if instruction.dry_run => {
+                                    match sim.fill(&instruction, &state_exec).await {
+                                        Some(price) => price,
+                                        None => {
+                                            state_exec.add_log(format!(
+                                                "Paper: {:?} limit @ {} not reached, not filled",
+                                                instruction.side, instruction.price
+                                            ));
+                                            continue;
                                         }
                                     }
                                 }
-                            }
+                                _ => instruction.price,
+                            };
 
-                            if pnl <= -max_loss {
-                                tracing::warn!("Max Loss Limit Hit! Stopping Engine.");
-                                state_exec.is_running.store(false, Ordering::SeqCst);
+                            book_fill(
+                                &state_exec,
+                                &db_exec,
+                                &execution_client_task,
+                                &risk_engine,
+                                &instruction,
+                                fill_price,
+                                response,
+                                strategy_name,
+                                latency,
+                                (fee_maker, fee_taker),
+                            )
+                            .await;
+                        }
+                        Err(e) if execution::is_post_only_reject(&e) => {
+                            if let Some(id) = &tracked_id {
+                                order_strategies_exec.lock().remove(id);
                             }
-                            if target_profit > 0.0 && pnl >= target_profit {
-                                tracing::info!("Target Profit Hit! Stopping Engine.");
-                                state_exec.is_running.store(false, Ordering::SeqCst);
+                            // Would have crossed the spread: nothing sent, nothing filled
+                            tracing::info!("Post-only order rejected (would take): {}", e);
+                            state_exec.add_log(format!(
+                                "Post-only reject: {:?} {} @ {}",
+                                instruction.side, instruction.quantity, instruction.price
+                            ));
+                        }
+                        // Accepted, but the fill isn't known: don't book one at the signal price
+                        Err(e) if execution::is_order_state_unknown(&e) => {
+                            tracing::error!("Order state unknown: {}", e);
+                            state_exec.add_log(format!("Order state unknown: {}", e));
+                            // A confirmed fill still arrives on the user stream
+                            if tracked_id.is_none() {
+                                resync_position(
+                                    &state_exec,
+                                    &execution_client_task,
+                                    instruction.symbol_str(),
+                                    "order state unknown",
+                                )
+                                .await;
                             }
                         }
                         Err(e) => {
+                            if let Some(id) = &tracked_id {
+                                order_strategies_exec.lock().remove(id);
+                            }
                             tracing::error!("Order Failed: {}", e);
                             state_exec.add_log(format!("Order Failed: {}", e));
+
+                            // Circuit breaker: stop retrying into a persistent failure
+                            if failure_breaker.record_failure() {
+                                state_exec.is_running.store(false, Ordering::SeqCst);
+                                risk_engine::disarm();
+                                tracing::error!(
+                                    "CRITICAL: Too many consecutive order failures (last: {}). Trading STOPPED and risk disarmed.",
+                                    e
+                                );
+                                state_exec.add_log(
+                                    "CRITICAL: Consecutive order failures - trading stopped"
+                                        .to_string(),
+                                );
+                            }
+                        }
+                    }
+                }
+                // Cancels go through even while stopped; dry-run orders never reached the exchange
+                Ok(common::ExecSignal::Cancel {
+                    symbol,
+                    client_order_id,
+                }) => {
+                    if state_exec.dry_run.load(Ordering::Relaxed) {
+                        continue;
+                    }
+                    match execution_client_task
+                        .cancel_order_by_client_id(symbol.as_str(), &client_order_id)
+                        .await
+                    {
+                        Ok(_) => {
+                            // No more fills can come for it
+                            order_strategies_exec.lock().remove(&client_order_id);
+                            tracing::info!("Order cancelled: {}", client_order_id);
+                        }
+                        // Usually already filled or cancelled (-2011)
+                        Err(e) => tracing::warn!("Cancel {} failed: {}", client_order_id, e),
+                    }
+                }
+                Ok(common::ExecSignal::CancelAll { symbol }) => {
+                    if state_exec.dry_run.load(Ordering::Relaxed) {
+                        continue;
+                    }
+                    match execution_client_task
+                        .cancel_all_orders(symbol.as_str())
+                        .await
+                    {
+                        Ok(()) => {
+                            tracing::info!("All {} orders cancelled", symbol);
+                            state_exec.add_log(format!("Cancelled all {} orders", symbol));
+                        }
+                        Err(e) => {
+                            tracing::error!("Cancel all {} failed: {}", symbol, e);
+                            state_exec.add_log(format!("Cancel all failed: {}", e));
                         }
                     }
                 }
@@ -346,15 +977,119 @@ async fn run_engine(
         tracing::info!("Execution task shutting down");
     });
 
+    // 14b. Spawn Fill Task (confirmed fills from the user-data stream)
+    let mut shutdown_rx_fills = shutdown_tx.subscribe();
+    let state_fills = state.clone();
+    let db_fills = db.clone();
+    let fill_symbol = symbol.clone();
+    let fill_handle = tokio::spawn(async move {
+        let Some(mut fill_rx) = fill_rx else {
+            return;
+        };
+        tracing::info!("Fill task started");
+        loop {
+            let fill = tokio::select! {
+                _ = shutdown_rx_fills.recv() => break,
+                fill = fill_rx.recv() => match fill {
+                    Some(fill) => fill,
+                    None => break,
+                },
+            };
+            if fill.symbol != fill_symbol {
+                continue;
+            }
+
+            let (strategy_name, latency) = {
+                let mut orders = order_strategies.lock();
+                let order = if fill.order_status == "FILLED" {
+                    orders.remove(&fill.client_order_id)
+                } else {
+                    orders.get(&fill.client_order_id).cloned()
+                };
+                // Orders placed outside the engine still move the real position
+                order.unwrap_or_else(|| ("EXTERNAL".to_string(), db::OrderLatency::default()))
+            };
+
+            let signed_qty = match fill.side {
+                common::Side::Buy => fill.quantity,
+                common::Side::Sell => -fill.quantity,
+            };
+            // Real commission, priced at the fill's maker/taker rate when not charged in USDT
+            let fee = state::fill_fee(&fill, fee_maker, fee_taker);
+            let realized_pnl = state_fills.update_from_trade(signed_qty, fill.price, fee);
+            risk_fills.record_realized_pnl(realized_pnl);
+            state_fills.add_log(format!(
+                "Fill: {:?} {} @ {} {} (order {}, PnL {:.2})",
+                fill.side,
+                fill.quantity,
+                fill.price,
+                if fill.is_maker { "maker" } else { "taker" },
+                fill.order_id,
+                realized_pnl
+            ));
+
+            db_fills
+                .insert_trade(db::TradeRecord {
+                    exchange_ts_ms: fill.trade_time,
+                    monotonic_ns: common::now_nanos(),
+                    symbol: fill.symbol,
+                    side: format!("{:?}", fill.side),
+                    price: fill.price,
+                    quantity: fill.quantity,
+                    pnl: realized_pnl,
+                    strategy: strategy_name,
+                    order_id: Some(fill.order_id.to_string()),
+                    exec_id: Some(fill.trade_id.to_string()),
+                    fee: Some(fill.commission),
+                    fee_currency: Some(fill.commission_asset),
+                    raw: Some(fill.raw),
+                    decision_latency_ns: latency.decision_ns,
+                    tick_to_order_ns: latency.tick_to_order_ns,
+                })
+                .await;
+
+            if let Some(reason) = state_fills.stop_if_pnl_limit_hit() {
+                tracing::warn!("{}! Stopping Engine.", reason);
+            }
+        }
+        tracing::info!("Fill task shutting down");
+    });
+
+    // 14c. Live Recording Tee (never blocks the feed: the recorder skips what it lags on)
+    let (live_tx, record_handle) = if config.trading.record_live.unwrap_or(false) {
+        let path = std::path::PathBuf::from(
+            config
+                .trading
+                .record_live_path
+                .clone()
+                .unwrap_or_else(|| "data/live_ticks.jsonl".to_string()),
+        );
+        tracing::info!("Recording live market events to {:?}", path);
+        let (tx, rx) = tokio::sync::broadcast::channel::<common::MarketEvent>(10_000);
+        let handle = tokio::spawn(async move {
+            match trading_engine::recording::record_events(rx, &path).await {
+                Ok(count) => tracing::info!("Live recording closed: {} events", count),
+                Err(e) => tracing::error!("Live recording failed: {:#}", e),
+            }
+        });
+        (Some(tx), Some(handle))
+    } else {
+        (None, None)
+    };
+
     // 15. Spawn Feed Task
     let mut shutdown_rx_feed = shutdown_tx.subscribe();
     let mut producer = producer; // Move producer into task
     let state_feed = state.clone();
+    let feed_symbol = symbol.clone();
+    let feed_ws_url = config.network.ws_url.clone();
+    let feed_exchange = config.network.exchange.clone();
+    let depth_levels = config.trading.depth_levels;
 
     let feed_handle = tokio::spawn(async move {
-        tracing::info!("Feed task started - Connecting to Binance...");
+        tracing::info!("Feed task started - Connecting to {}...", feed_exchange);
 
-        let mut rx = match feed_handler::connect("BTCUSDT", None).await {
+        let mut rx = match feed_source.connect(&feed_symbol).await {
             Ok(rx) => rx,
             Err(e) => {
                 tracing::error!("Failed to connect to feed: {}", e);
@@ -362,26 +1097,68 @@ async fn run_engine(
             }
         };
 
-        tracing::info!("Connected to Binance for btcusdt");
+        tracing::info!("Subscribed to {} via {}", feed_symbol, feed_ws_url);
+
+        // Optional top-of-book stream, merged into the same ring buffer
+        let mut depth_rx = match depth_levels {
+            Some(levels) => {
+                match feed_handler::connect_depth(&feed_ws_url, &feed_symbol, levels).await {
+                    Ok(rx) => Some(rx),
+                    Err(e) => {
+                        tracing::error!("Failed to connect to depth feed: {}", e);
+                        None
+                    }
+                }
+            }
+            None => None,
+        };
 
         loop {
             tokio::select! {
                 _ = shutdown_rx_feed.recv() => {
                     break;
                 }
+                Ok(_) = status_rx.changed() => {
+                    let status = *status_rx.borrow();
+                    *state_feed.feed_status.lock() = status;
+                    state_feed.add_log(format!("Feed status: {}", status));
+                }
                 Some(event) = rx.recv() => {
                     // Update Heartbeat
                     state_feed.last_tick_timestamp.store(event.exchange_timestamp as u64, Ordering::Relaxed);
                     state_feed.ticks_counter.fetch_add(1, Ordering::Relaxed);
                     *state_feed.last_price.lock() = event.price;
 
+                    if let Some(ref tee) = live_tx {
+                        let _ = tee.send(event.clone());
+                    }
+
                     // Push to RingBuffer
-                    if let Err(_e) = producer.push(event) {
-                        // tracing::warn!("Ring buffer full, dropping tick");
+                    if producer.push(event).is_err() {
+                        if let Some(dropped) = state_feed.record_dropped_tick() {
+                            tracing::warn!(
+                                "Ring buffer full, dropping tick ({} dropped so far; consider raising market_buffer_capacity)",
+                                dropped
+                            );
+                        }
+                    }
+                }
+                Some(book) = async {
+                    match depth_rx.as_mut() {
+                        Some(rx) => rx.recv().await,
+                        None => std::future::pending().await,
+                    }
+                } => {
+                    // Book updates don't count as ticks or move `last_price`
+                    if let Some(event) = book.to_market_event(common::now_nanos()) {
+                        if producer.push(event).is_err() {
+                            state_feed.record_dropped_tick();
+                        }
                     }
                 }
             }
         }
+        *state_feed.feed_status.lock() = feed_handler::FeedStatus::Disconnected;
         tracing::info!("Feed task shutting down");
     });
 
@@ -403,14 +1180,29 @@ async fn run_engine(
     state.shutting_down.store(true, Ordering::SeqCst);
     state.is_running.store(false, Ordering::SeqCst); // Stop Engine
 
-    // 2. Stop Feed & Execution
+    // 2. Stop Feed, Execution & Web Server
     let _ = shutdown_tx_ctrlc.send(());
 
     // 3. Drain Strategy
     shutdown_signal.store(true, Ordering::SeqCst);
 
-    // 4. Cancel Orders (TODO)
-    // tracing::warn!("Cancelling all open orders...");
+    // 4. Cancel Orders
+    // Hard timeout on top of the client's own retries so shutdown can't hang.
+    if config.trading.enabled {
+        tracing::warn!("Cancelling all open orders...");
+        match tokio::time::timeout(
+            std::time::Duration::from_secs(10),
+            execution_client.cancel_all_orders(&config.trading.symbol),
+        )
+        .await
+        {
+            Ok(Ok(())) => tracing::info!("Open orders cancelled"),
+            Ok(Err(e)) => tracing::error!("Failed to cancel open orders: {}", e),
+            Err(_) => tracing::error!("Timed out cancelling open orders"),
+        }
+    } else {
+        tracing::info!("Trading disabled, skipping order cancellation");
+    }
 
     // 5. Disarm Risk Engine
     // tracing::warn!("Disarming Risk Engine...");
@@ -418,7 +1210,9 @@ async fn run_engine(
 
     // 6. Flush DB
     tracing::warn!("Flushing Database...");
-    db.flush().await;
+    if let Err(e) = db.flush().await {
+        tracing::error!("Failed to flush database: {:#}", e);
+    }
 
     // 7. Wait for Strategy Thread
     if let Err(e) = strategy_handle.join() {
@@ -426,8 +1220,176 @@ async fn run_engine(
     }
 
     // 8. Wait for Tokio Tasks
-    let _ = tokio::join!(execution_handle, feed_handle);
+    time_sync_handle.abort();
+    if let Some(handle) = listen_key_handle {
+        handle.abort();
+    }
+    let _ = tokio::join!(execution_handle, feed_handle, fill_handle);
+    // Feed task dropped the tee sender; wait for the recording to flush
+    if let Some(handle) = record_handle {
+        let _ = handle.await;
+    }
 
     tracing::info!("Engine instance stopped.");
     Ok(is_reload)
 }
+
+/// Books an order the exchange reported done when there is no user-data stream to confirm
+/// the fill: position and PnL at `fill_price`, the risk engine's loss tally, the trade
+/// record, the balance and the PnL auto-stop. Returns the realized PnL.
+#[allow(clippy::too_many_arguments)]
+async fn book_fill(
+    state: &EngineState,
+    db: &db::TradeStorage,
+    client: &ExecutionClient,
+    risk_engine: &risk_engine::RiskEngine,
+    instruction: &common::TradeInstruction,
+    fill_price: f64,
+    response: execution::OrderResponse,
+    strategy: String,
+    latency: db::OrderLatency,
+    (fee_maker, fee_taker): (f64, f64),
+) -> f64 {
+    // Prefer the exchange-reported commission; otherwise estimate from config
+    let (fee_amount, fee_currency) = response.commission().unwrap_or((
+        state::estimate_fee(
+            instruction.order_type,
+            instruction.quantity,
+            fill_price,
+            fee_maker,
+            fee_taker,
+        ),
+        "USDT".to_string(),
+    ));
+
+    let signed_qty = match instruction.side {
+        common::Side::Buy => instruction.quantity,
+        common::Side::Sell => -instruction.quantity,
+    };
+    let realized_pnl = state.update_from_trade(signed_qty, fill_price, fee_amount);
+    risk_engine.record_realized_pnl(realized_pnl);
+
+    db.insert_trade(db::TradeRecord {
+        exchange_ts_ms: std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_millis() as i64,
+        monotonic_ns: common::now_nanos(),
+        symbol: instruction.symbol.to_string(),
+        side: format!("{:?}", instruction.side),
+        price: fill_price,
+        quantity: instruction.quantity,
+        pnl: realized_pnl,
+        strategy,
+        order_id: response.order_id.map(|id| id.to_string()),
+        exec_id: instruction.client_order_id.clone(),
+        fee: Some(fee_amount),
+        fee_currency: Some(fee_currency),
+        raw: Some(response.raw),
+        decision_latency_ns: latency.decision_ns,
+        tick_to_order_ns: latency.tick_to_order_ns,
+    })
+    .await;
+
+    // Update Balance After Trade
+    if let Ok(balances) = client.get_account_balance().await {
+        for b in balances {
+            if b.asset == "USDT" {
+                if let Ok(available) = b.available_balance.parse::<f64>() {
+                    *state.available_balance.lock() = available;
+                    tracing::debug!("Balance updated: Available = {:.2}", available);
+                }
+            }
+        }
+    }
+
+    // Auto-Stop Logic
+    if let Some(reason) = state.stop_if_pnl_limit_hit() {
+        tracing::warn!("{}! Stopping Engine.", reason);
+    }
+    realized_pnl
+}
+
+/// Logs why a watchdog is closing the position and queues a FLATTEN. The strategy is
+/// reset by the execution task once the close order goes through.
+fn request_flatten(state: &EngineState, flatten_tx: &mpsc::Sender<()>, reason: String) {
+    tracing::warn!("{}", reason);
+    state.add_log(reason);
+    // Full channel means a FLATTEN is already pending
+    let _ = flatten_tx.try_send(());
+}
+
+/// Re-reads `symbol`'s position from the exchange when local accounting can't be
+/// trusted; `reason` goes into the engine log.
+async fn resync_position(
+    state: &EngineState,
+    client: &ExecutionClient,
+    symbol: &str,
+    reason: &str,
+) {
+    match client.sync_positions(Some(symbol)).await {
+        Ok(positions) => {
+            apply_position(state, symbol, &positions);
+            state.add_log(format!("Position re-synced ({})", reason));
+        }
+        Err(e) => tracing::error!("Position re-sync ({}) failed: {}", reason, e),
+    }
+}
+
+/// Take `symbol`'s position from a `sync_positions` result.
+fn apply_position(state: &EngineState, symbol: &str, positions: &[execution::PositionRisk]) {
+    for p in positions.iter().filter(|p| p.symbol == symbol) {
+        *state.current_position.lock() = p.position_amt.parse::<f64>().unwrap_or(0.0);
+        // Restore entry/mark so unrealized PnL is correct before the first tick
+        *state.avg_entry_price.lock() = p.entry_price.parse::<f64>().unwrap_or(0.0);
+        *state.last_price.lock() = p.mark_price.parse::<f64>().unwrap_or(0.0);
+        tracing::info!(
+            "  Active Position: {} = {} (Entry: {}, Mark: {})",
+            p.symbol,
+            p.position_amt,
+            p.entry_price,
+            p.mark_price
+        );
+    }
+}
+
+/// Keeps the user-data listenKey alive (every 30 min; keys expire after 60 min) and
+/// replaces it when a keepalive fails or the stream reports a disconnect.
+async fn maintain_listen_key(
+    client: Arc<ExecutionClient>,
+    key_tx: tokio::sync::watch::Sender<String>,
+    key_invalid: Arc<tokio::sync::Notify>,
+) {
+    let mut interval = tokio::time::interval(std::time::Duration::from_secs(30 * 60));
+    interval.tick().await; // First tick fires immediately; key was just created
+    loop {
+        tokio::select! {
+            _ = interval.tick() => {
+                match client.keepalive_listen_key().await {
+                    Ok(()) => {
+                        tracing::debug!("listenKey kept alive");
+                        continue;
+                    }
+                    Err(e) => tracing::warn!("listenKey keepalive failed: {}. Re-creating", e),
+                }
+            }
+            _ = key_invalid.notified() => {
+                tracing::warn!("User-data stream disconnected, refreshing listenKey");
+            }
+        }
+
+        // POST returns the current key if it is still valid, otherwise a new one
+        match client.create_listen_key().await {
+            Ok(key) => {
+                key_tx.send_if_modified(|current| {
+                    if *current == key {
+                        return false;
+                    }
+                    *current = key;
+                    true
+                });
+            }
+            Err(e) => tracing::error!("Failed to re-create listenKey: {}", e),
+        }
+    }
+}
@@ -0,0 +1,117 @@
+use crate::state::EngineState;
+use common::{OrderType, Side, TradeInstruction};
+use std::time::Duration;
+
+/// Simulates fills for dry-run orders against the live market instead of assuming an
+/// instant fill at the signal price.
+///
+/// Market orders wait `latency`, then fill at the current `last_price` moved against the
+/// order by `slippage_bps`. Limit orders only fill if the market has reached them, at the
+/// better of `last_price` and the limit; there is no simulated book for them to rest on,
+/// so a limit away from the market is simply not filled.
+#[derive(Debug, Clone)]
+pub struct PaperFillSimulator {
+    slippage_bps: f64,
+    latency: Duration,
+}
+
+impl PaperFillSimulator {
+    pub fn new(slippage_bps: f64, latency: Duration) -> Self {
+        Self {
+            slippage_bps: slippage_bps.max(0.0),
+            latency,
+        }
+    }
+
+    /// Fill price for `instruction` given the prevailing market price, `None` for a limit
+    /// the market hasn't reached. Falls back to the signal price if no market price is
+    /// known yet.
+    pub fn fill_price(&self, instruction: &TradeInstruction, market_price: f64) -> Option<f64> {
+        let base = if market_price > 0.0 {
+            market_price
+        } else {
+            instruction.price
+        };
+        if instruction.order_type == OrderType::Limit {
+            return match instruction.side {
+                Side::Buy => (base <= instruction.price).then_some(base),
+                Side::Sell => (base >= instruction.price).then_some(base),
+            };
+        }
+        let slippage = base * self.slippage_bps / 10_000.0;
+        Some(match instruction.side {
+            Side::Buy => base + slippage,
+            Side::Sell => base - slippage,
+        })
+    }
+
+    /// Waits the simulated latency, then prices the fill off the engine's latest market price.
+    pub async fn fill(&self, instruction: &TradeInstruction, state: &EngineState) -> Option<f64> {
+        if instruction.order_type != OrderType::Limit && !self.latency.is_zero() {
+            tokio::time::sleep(self.latency).await;
+        }
+        let market_price = *state.last_price.lock();
+        self.fill_price(instruction, market_price)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn instruction(side: Side, order_type: OrderType) -> TradeInstruction {
+        TradeInstruction {
+            order_type,
+            dry_run: true,
+            ..TradeInstruction::market("BTCUSDT", side, 50_000.0, 0.01, 0)
+        }
+    }
+
+    #[test]
+    fn test_market_fill_applies_adverse_slippage() {
+        let sim = PaperFillSimulator::new(10.0, Duration::ZERO);
+
+        let buy = sim.fill_price(&instruction(Side::Buy, OrderType::Market), 50_100.0);
+        let sell = sim.fill_price(&instruction(Side::Sell, OrderType::Market), 50_100.0);
+        assert!((buy.unwrap() - 50_150.1).abs() < 1e-9);
+        assert!((sell.unwrap() - 50_049.9).abs() < 1e-9);
+
+        // No market price yet: signal price is the base
+        let cold = sim.fill_price(&instruction(Side::Buy, OrderType::Market), 0.0);
+        assert!((cold.unwrap() - 50_050.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_limit_fills_only_once_market_reaches_it() {
+        let sim = PaperFillSimulator::new(10.0, Duration::ZERO);
+
+        // Buy limit 50_000 below a 50_100 market: not filled
+        let buy = instruction(Side::Buy, OrderType::Limit);
+        assert_eq!(sim.fill_price(&buy, 50_100.0), None);
+        // Market at or through the limit: filled at market, no slippage
+        assert_eq!(sim.fill_price(&buy, 49_990.0), Some(49_990.0));
+        assert_eq!(sim.fill_price(&buy, 50_000.0), Some(50_000.0));
+
+        let sell = instruction(Side::Sell, OrderType::Limit);
+        assert_eq!(sim.fill_price(&sell, 49_900.0), None);
+        assert_eq!(sim.fill_price(&sell, 50_020.0), Some(50_020.0));
+    }
+
+    #[tokio::test]
+    async fn test_fill_reads_price_after_latency() {
+        let state = std::sync::Arc::new(EngineState::new());
+        *state.last_price.lock() = 50_000.0;
+        let sim = PaperFillSimulator::new(0.0, Duration::from_millis(50));
+
+        let mover = state.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+            *mover.last_price.lock() = 50_200.0;
+        });
+
+        let price = sim
+            .fill(&instruction(Side::Buy, OrderType::Market), &state)
+            .await;
+        assert_eq!(price, Some(50_200.0));
+    }
+}
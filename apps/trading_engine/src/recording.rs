@@ -0,0 +1,81 @@
+use anyhow::Context;
+use common::MarketEvent;
+use std::fs::{self, OpenOptions};
+use std::io::{BufWriter, Write};
+use std::path::Path;
+use tokio::sync::broadcast;
+
+/// Drains the live market-event tee to `path` as JSONL (one `MarketEvent` per line),
+/// appending to any existing file. Runs until every sender is dropped and returns the
+/// number of events written. Events the writer falls behind on are skipped, never
+/// back-pressuring the feed.
+pub async fn record_events(
+    mut rx: broadcast::Receiver<MarketEvent>,
+    path: &Path,
+) -> anyhow::Result<u64> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).context("Failed to create recording directory")?;
+    }
+    let file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .with_context(|| format!("Failed to open {:?}", path))?;
+    let mut writer = BufWriter::new(file);
+
+    let mut written = 0u64;
+    loop {
+        match rx.recv().await {
+            Ok(event) => {
+                serde_json::to_writer(&mut writer, &event)?;
+                writer.write_all(b"\n")?;
+                written += 1;
+            }
+            Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                tracing::warn!("Live recorder lagged, skipped {} events", skipped);
+            }
+            Err(broadcast::error::RecvError::Closed) => break,
+        }
+    }
+
+    writer.flush().context("Failed to flush recording")?;
+    Ok(written)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event(price: f64) -> MarketEvent {
+        MarketEvent {
+            exchange_timestamp: 1,
+            received_timestamp: 2,
+            ..MarketEvent::trade("BTCUSDT", price, 0.1)
+        }
+    }
+
+    #[tokio::test]
+    async fn test_record_events_writes_jsonl() {
+        let path =
+            std::env::temp_dir().join(format!("hft_live_recording_{}.jsonl", std::process::id()));
+        let _ = fs::remove_file(&path);
+
+        let (tx, rx) = broadcast::channel(16);
+        let task_path = path.clone();
+        let handle = tokio::spawn(async move { record_events(rx, &task_path).await });
+
+        for price in [100.0, 101.0, 102.0] {
+            tx.send(event(price)).unwrap();
+        }
+        drop(tx);
+
+        assert_eq!(handle.await.unwrap().unwrap(), 3);
+        let content = fs::read_to_string(&path).unwrap();
+        let prices: Vec<f64> = content
+            .lines()
+            .map(|l| serde_json::from_str::<MarketEvent>(l).unwrap().price)
+            .collect();
+        assert_eq!(prices, vec![100.0, 101.0, 102.0]);
+        let _ = fs::remove_file(&path);
+    }
+}
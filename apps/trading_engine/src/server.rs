@@ -1,8 +1,9 @@
-use crate::db::TradeStorage;
-use crate::state::EngineState;
+use crate::db::{TradeFilter, TradeStorage};
+use crate::state::{pnl_pct, EngineState, LatencySnapshot};
+use anyhow::Context;
 use axum::{
     extract::{Query, State},
-    http::StatusCode,
+    http::{header, StatusCode},
     response::{
         sse::{Event, Sse},
         IntoResponse, Json,
@@ -10,6 +11,8 @@ use axum::{
     routing::{get, get_service, post},
     Router,
 };
+use execution::ExecutionClient;
+use feed_handler::FeedStatus;
 use futures::stream::Stream;
 use serde::{Deserialize, Serialize};
 use std::convert::Infallible;
@@ -17,15 +20,20 @@ use std::net::SocketAddr;
 use std::sync::atomic::Ordering;
 use std::sync::Arc;
 use std::time::Duration;
+use tokio::sync::{broadcast, mpsc};
 
 use tower_http::cors::CorsLayer;
 use tower_http::services::ServeDir;
 
 #[derive(Serialize)]
 struct StatusResponse {
+    symbol: String,
     running: bool,
+    dry_run: bool,
     trade_count: usize,
     pnl: f64,
+    /// `pnl` as % of `initial_balance` (0 if the balance is unknown).
+    pnl_pct: f64,
     max_loss_limit: f64,
     target_profit: f64,
     initial_balance: f64,
@@ -33,16 +41,24 @@ struct StatusResponse {
     current_position: f64,
     last_tick_ts: u64,
     last_order_rtt_ns: u64,
+    order_rtt: LatencySnapshot,
+    last_strategy_latency_ns: u64,
+    stale_ticks: u64,
+    dropped_ticks: u64,
+    feed_dropped_ticks: u64,
     active_strategy: String,
     tps: usize,
     cps: usize,
+    feed_status: String,
 }
 
 // ... (ControlRequest, ConfigRequest, etc. skipped)
 
 async fn get_status(State(state): State<AppState>) -> impl IntoResponse {
     let engine = &state.engine;
+    let symbol = engine.symbol.lock().clone();
     let running = engine.is_running.load(Ordering::Relaxed);
+    let dry_run = engine.dry_run.load(Ordering::Relaxed);
     let trade_count = engine.trade_count.load(Ordering::Relaxed);
     let pnl = *engine.current_pnl.lock();
     let max_loss_limit = *engine.max_loss_limit.lock();
@@ -52,47 +68,115 @@ async fn get_status(State(state): State<AppState>) -> impl IntoResponse {
     let current_position = *engine.current_position.lock();
     let last_tick_ts = engine.last_tick_timestamp.load(Ordering::Relaxed);
     let last_order_rtt_ns = engine.last_order_rtt_ns.load(Ordering::Relaxed);
+    let order_rtt = engine.order_rtt_snapshot();
+    let last_strategy_latency_ns = engine.last_strategy_latency_ns.load(Ordering::Relaxed);
+    let stale_ticks = engine.stale_ticks.load(Ordering::Relaxed);
+    let dropped_ticks = engine.dropped_ticks.load(Ordering::Relaxed);
+    let feed_dropped_ticks = engine.feed_dropped_ticks.load(Ordering::Relaxed);
     let active_strategy = engine.active_strategy.lock().clone();
     let tps = engine.current_tps.load(Ordering::Relaxed);
     let cps = engine.current_cps.load(Ordering::Relaxed);
+    let feed_status = engine.feed_status.lock().to_string();
 
     Json(StatusResponse {
+        symbol,
         running,
+        dry_run,
         trade_count,
         pnl,
         max_loss_limit,
         target_profit,
+        pnl_pct: pnl_pct(pnl, initial_balance),
         initial_balance,
         available_balance,
         current_position,
         last_tick_ts,
         last_order_rtt_ns,
+        order_rtt,
+        last_strategy_latency_ns,
+        stale_ticks,
+        dropped_ticks,
+        feed_dropped_ticks,
         active_strategy,
         tps,
         cps,
+        feed_status,
     })
 }
 
+#[derive(Serialize)]
+struct ReadinessResponse {
+    ready: bool,
+    /// Human-readable description of each failed check.
+    failed: Vec<String>,
+}
+
 #[derive(Deserialize)]
 struct ControlRequest {
     command: String,
     confirm: Option<bool>,
 }
 
+#[derive(Deserialize)]
+struct DryRunRequest {
+    enabled: bool,
+    confirm: Option<bool>,
+}
+
 #[derive(Deserialize)]
 struct ConfigRequest {
     max_loss: f64,
     target_profit: f64,
 }
 
+/// Partial update of the live risk limits; omitted fields keep their value.
+#[derive(Deserialize)]
+struct RiskRequest {
+    max_order_size: Option<f64>,
+    max_position: Option<f64>,
+    max_drawdown: Option<f64>,
+}
+
 #[derive(Deserialize)]
 struct StrategyRequest {
     strategy: String,
 }
 
+/// Partial update of the live strategy tunables; omitted fields keep their value.
+#[derive(Deserialize)]
+struct StrategyParamsRequest {
+    window: Option<usize>,
+    threshold: Option<f64>,
+    price_threshold: Option<f64>,
+    volume_multiplier: Option<f64>,
+}
+
+#[derive(Deserialize)]
+struct OrdersQuery {
+    symbol: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct KlinesQuery {
+    /// Defaults to the traded symbol.
+    symbol: Option<String>,
+    /// Binance interval (`1m`, `5m`, `1h`, ...). Defaults to `1m`.
+    interval: Option<String>,
+    /// Defaults to 500, max 1500.
+    limit: Option<u16>,
+}
+
+/// Total matching trades on `/api/history`, regardless of `limit`/`offset`.
+const TOTAL_COUNT_HEADER: &str = "x-total-count";
+
 #[derive(Deserialize)]
 struct HistoryQuery {
     limit: Option<i64>,
+    /// Skip this many of the most recent matches (paging).
+    offset: Option<i64>,
+    symbol: Option<String>,
+    /// `buy` or `sell` (case-insensitive).
+    side: Option<String>,
 }
 
 #[derive(Serialize)]
@@ -105,34 +189,73 @@ struct ErrorResponse {
 pub struct AppState {
     engine: Arc<EngineState>,
     db: TradeStorage,
+    flatten_tx: mpsc::Sender<()>,
+    client: Arc<ExecutionClient>,
 }
 
-pub async fn run(state: Arc<EngineState>, db: TradeStorage) {
+pub async fn run(
+    state: Arc<EngineState>,
+    db: TradeStorage,
+    flatten_tx: mpsc::Sender<()>,
+    client: Arc<ExecutionClient>,
+    addr: SocketAddr,
+    mut shutdown_rx: broadcast::Receiver<()>,
+) -> anyhow::Result<()> {
     let serve_dir = ServeDir::new("dashboard");
-    let app_state = AppState { engine: state, db };
+    let app_state = AppState {
+        engine: state,
+        db,
+        flatten_tx,
+        client,
+    };
 
     let app = Router::new()
         .route("/api/status", get(get_status))
         .route("/api/control", post(control_engine))
         .route("/api/config", post(update_config))
+        .route("/api/risk", get(get_risk_limits).post(set_risk_limits))
+        .route("/api/dryrun", post(set_dry_run))
         .route("/api/strategy", post(set_strategy))
+        .route(
+            "/api/strategy/params",
+            get(get_strategy_params).post(set_strategy_params),
+        )
+        .route("/api/strategy/reset", post(reset_strategy))
         .route("/api/strategies", get(get_strategies))
         .route("/api/history", get(get_history).delete(clear_history))
         .route("/api/pnl_series", get(get_pnl_series))
+        .route("/api/pnl_daily", get(get_pnl_daily))
+        .route("/api/equity", get(get_equity))
+        .route("/api/stats", get(get_stats))
+        .route("/api/latency", get(get_latency).delete(reset_latency))
         .route("/api/logs", get(get_logs))
+        .route("/api/logs/stream", get(log_stream_handler))
+        .route("/api/positions", get(get_positions))
+        .route("/api/orders", get(get_orders))
+        .route("/api/klines", get(get_klines))
         .route("/api/sse", get(sse_handler))
+        .route("/metrics", get(get_metrics))
+        .route("/healthz", get(healthz))
+        .route("/readyz", get(readyz))
         .nest_service("/dashboard", serve_dir.clone())
         .route("/", get_service(serve_dir))
         .layer(CorsLayer::permissive())
         .with_state(app_state);
 
-    let addr = SocketAddr::from(([0, 0, 0, 0], 3000));
+    let server = axum::Server::try_bind(&addr)
+        .with_context(|| format!("Failed to bind web server to {}", addr))?;
     tracing::info!("Web Dashboard listening on http://{}", addr);
 
-    axum::Server::bind(&addr)
+    server
         .serve(app.into_make_service())
+        .with_graceful_shutdown(async move {
+            let _ = shutdown_rx.recv().await;
+            tracing::info!("Web server shutting down");
+        })
         .await
-        .unwrap();
+        .context("Web server error")?;
+
+    Ok(())
 }
 
 async fn control_engine(
@@ -182,14 +305,25 @@ async fn control_engine(
                     .into_response();
             }
 
-            // Enqueue Flatten Job (Mock implementation for now)
-            // In a real system, this would push to a high-priority channel consumed by Execution
+            // Enqueue Flatten Job on the high-priority channel consumed by Execution
             state
                 .engine
                 .add_log("FLATTEN command received. Queuing emergency close.".to_string());
             tracing::warn!("FLATTEN COMMAND RECEIVED");
 
-            // TODO: Implement actual flatten logic via ExecutionClient
+            match state.flatten_tx.try_send(()) {
+                // A pending request already covers the full position
+                Ok(_) | Err(mpsc::error::TrySendError::Full(_)) => {}
+                Err(mpsc::error::TrySendError::Closed(_)) => {
+                    return (
+                        StatusCode::SERVICE_UNAVAILABLE,
+                        Json(ErrorResponse {
+                            error: "Execution task not running".to_string(),
+                        }),
+                    )
+                        .into_response();
+                }
+            }
 
             (
                 StatusCode::ACCEPTED,
@@ -207,6 +341,52 @@ async fn control_engine(
     }
 }
 
+async fn set_dry_run(
+    State(state): State<AppState>,
+    Json(payload): Json<DryRunRequest>,
+) -> impl IntoResponse {
+    if !payload.enabled {
+        // Safety: Going live sends real orders
+        if payload.confirm != Some(true) {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(ErrorResponse {
+                    error: "Confirmation required to disable dry-run".to_string(),
+                }),
+            )
+                .into_response();
+        }
+
+        if state.client.is_mainnet() && !state.engine.allow_mainnet.load(Ordering::Relaxed) {
+            return (
+                StatusCode::FORBIDDEN,
+                Json(ErrorResponse {
+                    error: "Live trading on MAINNET requires `allow_mainnet = true`".to_string(),
+                }),
+            )
+                .into_response();
+        }
+    }
+
+    state
+        .engine
+        .dry_run
+        .store(payload.enabled, Ordering::SeqCst);
+    let msg = if payload.enabled {
+        "Dry-run ENABLED (paper trading)"
+    } else {
+        "Dry-run DISABLED (LIVE trading)"
+    };
+    tracing::warn!("{}", msg);
+    state.engine.add_log(msg.to_string());
+
+    (
+        StatusCode::OK,
+        Json(serde_json::json!({"dry_run": payload.enabled})),
+    )
+        .into_response()
+}
+
 async fn update_config(
     State(state): State<AppState>,
     Json(payload): Json<ConfigRequest>,
@@ -224,10 +404,60 @@ async fn update_config(
         .into_response()
 }
 
+async fn get_risk_limits(State(state): State<AppState>) -> impl IntoResponse {
+    Json(*state.engine.risk_limits.lock())
+}
+
+async fn set_risk_limits(
+    State(state): State<AppState>,
+    Json(payload): Json<RiskRequest>,
+) -> impl IntoResponse {
+    let mut limits = *state.engine.risk_limits.lock();
+    if let Some(max_order_size) = payload.max_order_size {
+        limits.max_order_size = max_order_size;
+    }
+    if let Some(max_position) = payload.max_position {
+        limits.max_position = max_position;
+    }
+    if let Some(max_drawdown) = payload.max_drawdown {
+        limits.max_daily_loss = max_drawdown;
+    }
+    if let Err(e) = limits.validate() {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(ErrorResponse {
+                error: e.to_string(),
+            }),
+        )
+            .into_response();
+    }
+
+    *state.engine.risk_limits.lock() = limits;
+    state.engine.add_log(format!(
+        "Risk limits updated: max_order_size={} max_position={} max_drawdown={}",
+        limits.max_order_size, limits.max_position, limits.max_daily_loss
+    ));
+    (StatusCode::OK, Json(limits)).into_response()
+}
+
 async fn set_strategy(
     State(state): State<AppState>,
     Json(payload): Json<StrategyRequest>,
 ) -> impl IntoResponse {
+    if !strategy::AVAILABLE_STRATEGIES.contains(&payload.strategy.as_str()) {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(ErrorResponse {
+                error: format!(
+                    "Unknown strategy '{}'. Valid strategies: {}",
+                    payload.strategy,
+                    strategy::AVAILABLE_STRATEGIES.join(", ")
+                ),
+            }),
+        )
+            .into_response();
+    }
+
     let current_pos = *state.engine.current_position.lock();
     if current_pos.abs() > 0.000001 {
         return (
@@ -250,17 +480,234 @@ async fn set_strategy(
         .into_response()
 }
 
+async fn get_strategy_params(State(state): State<AppState>) -> impl IntoResponse {
+    Json(state.engine.strategy_params.lock().clone()).into_response()
+}
+
+async fn set_strategy_params(
+    State(state): State<AppState>,
+    Json(payload): Json<StrategyParamsRequest>,
+) -> impl IntoResponse {
+    let invalid = payload.window == Some(0)
+        || [
+            payload.threshold,
+            payload.price_threshold,
+            payload.volume_multiplier,
+        ]
+        .iter()
+        .flatten()
+        .any(|v| !v.is_finite() || *v < 0.0);
+    if invalid {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(ErrorResponse {
+                error: "window must be > 0 and thresholds must be non-negative numbers".to_string(),
+            }),
+        )
+            .into_response();
+    }
+
+    let current_pos = *state.engine.current_position.lock();
+    if current_pos.abs() > 0.000001 {
+        return (
+            StatusCode::CONFLICT,
+            Json(ErrorResponse {
+                error: "Cannot change strategy params with open positions".to_string(),
+            }),
+        )
+            .into_response();
+    }
+
+    let updated = {
+        let mut params = state.engine.strategy_params.lock();
+        if let Some(window) = payload.window {
+            params.window = window;
+        }
+        if let Some(threshold) = payload.threshold {
+            params.threshold = threshold;
+        }
+        if let Some(price_threshold) = payload.price_threshold {
+            params.price_threshold = price_threshold;
+        }
+        if let Some(volume_multiplier) = payload.volume_multiplier {
+            params.volume_multiplier = volume_multiplier;
+        }
+        params.clone()
+    };
+
+    state.engine.add_log(format!(
+        "Strategy params updated: window={} threshold={} price_threshold={} volume_multiplier={}",
+        updated.window, updated.threshold, updated.price_threshold, updated.volume_multiplier
+    ));
+    (StatusCode::OK, Json(updated)).into_response()
+}
+
+/// Clears the running strategy's internal position and price history,
+/// e.g. after a position was closed directly on the exchange.
+async fn reset_strategy(State(state): State<AppState>) -> impl IntoResponse {
+    state.engine.strategy_reset.store(true, Ordering::Relaxed);
+    state
+        .engine
+        .add_log("Strategy state reset requested".to_string());
+    (
+        StatusCode::ACCEPTED,
+        Json(serde_json::json!({"status": "reset_queued"})),
+    )
+        .into_response()
+}
+
 async fn get_strategies() -> impl IntoResponse {
     Json(strategy::AVAILABLE_STRATEGIES).into_response()
 }
 
+/// Prometheus text exposition (format 0.0.4) of the engine's core gauges/counters.
+fn render_metrics(engine: &EngineState) -> String {
+    let feed_connected = *engine.feed_status.lock() == FeedStatus::Connected;
+    let metrics: [(&str, &str, &str, f64); 6] = [
+        (
+            "hft_trade_count",
+            "counter",
+            "Executed trades since start",
+            engine.trade_count.load(Ordering::Relaxed) as f64,
+        ),
+        (
+            "hft_pnl",
+            "gauge",
+            "Realized PnL net of fees",
+            *engine.current_pnl.lock(),
+        ),
+        (
+            "hft_tps",
+            "gauge",
+            "Market data ticks per second",
+            engine.current_tps.load(Ordering::Relaxed) as f64,
+        ),
+        (
+            "hft_position",
+            "gauge",
+            "Current position size (signed)",
+            *engine.current_position.lock(),
+        ),
+        (
+            "hft_order_rtt_ns",
+            "gauge",
+            "Last order round-trip time in nanoseconds",
+            engine.last_order_rtt_ns.load(Ordering::Relaxed) as f64,
+        ),
+        (
+            "hft_feed_connected",
+            "gauge",
+            "1 if the market data feed is connected",
+            if feed_connected { 1.0 } else { 0.0 },
+        ),
+    ];
+
+    let mut out = String::new();
+    for (name, kind, help, value) in metrics {
+        out.push_str(&format!(
+            "# HELP {name} {help}\n# TYPE {name} {kind}\n{name} {value}\n"
+        ));
+    }
+    out
+}
+
+/// Liveness: the process is up and serving requests.
+async fn healthz() -> impl IntoResponse {
+    Json(serde_json::json!({"status": "ok"}))
+}
+
+/// Readiness: feed connected, database reachable, and not shutting down.
+async fn readyz(State(state): State<AppState>) -> impl IntoResponse {
+    let mut failed = Vec::new();
+
+    if state.engine.shutting_down.load(Ordering::Relaxed) {
+        failed.push("engine: shutting down".to_string());
+    }
+
+    let feed_status = *state.engine.feed_status.lock();
+    if feed_status != FeedStatus::Connected {
+        failed.push(format!("feed: {}", feed_status));
+    }
+
+    match tokio::time::timeout(Duration::from_secs(1), state.db.ping()).await {
+        Ok(Ok(())) => {}
+        Ok(Err(e)) => failed.push(format!("database: {}", e)),
+        Err(_) => failed.push("database: ping timed out".to_string()),
+    }
+
+    let status = if failed.is_empty() {
+        StatusCode::OK
+    } else {
+        StatusCode::SERVICE_UNAVAILABLE
+    };
+    (
+        status,
+        Json(ReadinessResponse {
+            ready: failed.is_empty(),
+            failed,
+        }),
+    )
+        .into_response()
+}
+
+async fn get_metrics(State(state): State<AppState>) -> impl IntoResponse {
+    (
+        [(header::CONTENT_TYPE, "text/plain; version=0.0.4")],
+        render_metrics(&state.engine),
+    )
+}
+
+async fn get_latency(State(state): State<AppState>) -> impl IntoResponse {
+    Json(state.engine.order_rtt_snapshot()).into_response()
+}
+
+async fn reset_latency(State(state): State<AppState>) -> impl IntoResponse {
+    state.engine.reset_order_rtt();
+    state
+        .engine
+        .add_log("Order latency histogram reset by user".to_string());
+    (StatusCode::OK, Json(serde_json::json!({"status": "reset"}))).into_response()
+}
+
 async fn get_history(
     State(state): State<AppState>,
     Query(params): Query<HistoryQuery>,
 ) -> impl IntoResponse {
     let limit = params.limit.unwrap_or(50);
-    match state.db.get_recent_trades(limit).await {
-        Ok(trades) => Json(trades).into_response(),
+    let side = match params
+        .side
+        .as_deref()
+        .map(str::to_ascii_lowercase)
+        .as_deref()
+    {
+        None => None,
+        Some("buy") => Some("Buy".to_string()),
+        Some("sell") => Some("Sell".to_string()),
+        Some(other) => {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(ErrorResponse {
+                    error: format!("Invalid side: {} (expected buy or sell)", other),
+                }),
+            )
+                .into_response();
+        }
+    };
+    let filter = TradeFilter {
+        symbol: params.symbol.map(|s| s.to_uppercase()),
+        side,
+    };
+    let offset = params.offset.unwrap_or(0).max(0);
+    let result = async {
+        let total = state.db.count_trades(&filter).await?;
+        let trades = state.db.query_trades(&filter, limit, offset).await?;
+        anyhow::Ok((total, trades))
+    };
+    match result.await {
+        // Body stays a plain array; the total for page controls goes in a header
+        Ok((total, trades)) => {
+            ([(TOTAL_COUNT_HEADER, total.to_string())], Json(trades)).into_response()
+        }
         Err(e) => (
             StatusCode::INTERNAL_SERVER_ERROR,
             Json(ErrorResponse {
@@ -296,6 +743,9 @@ async fn clear_history(State(state): State<AppState>) -> impl IntoResponse {
 #[derive(Deserialize)]
 struct PnlQuery {
     mode: Option<String>,
+    /// Optional time range (epoch ms) for historical mode.
+    start_ms: Option<i64>,
+    end_ms: Option<i64>,
 }
 
 async fn get_pnl_series(
@@ -304,7 +754,18 @@ async fn get_pnl_series(
 ) -> impl IntoResponse {
     if let Some(mode) = &params.mode {
         if mode == "historical" {
-            match state.db.get_all_trades_asc().await {
+            let trades = if params.start_ms.is_some() || params.end_ms.is_some() {
+                state
+                    .db
+                    .get_trades_between(
+                        params.start_ms.unwrap_or(0),
+                        params.end_ms.unwrap_or(i64::MAX),
+                    )
+                    .await
+            } else {
+                state.db.get_all_trades_asc().await
+            };
+            match trades {
                 Ok(trades) => {
                     let mut history = std::collections::VecDeque::new();
                     let mut cumulative_pnl = 0.0;
@@ -332,11 +793,126 @@ async fn get_pnl_series(
     Json(history).into_response()
 }
 
+/// Equity curve `[(ts_ms, equity)]` over all stored trades, anchored at the startup
+/// `initial_balance`. Historical points are realized-only (unrealized PnL isn't stored);
+/// the last point is live: the latest realized equity plus the open position's
+/// unrealized PnL.
+async fn get_equity(State(state): State<AppState>) -> impl IntoResponse {
+    let trades = match state.db.get_all_trades_asc().await {
+        Ok(trades) => trades,
+        Err(e) => {
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ErrorResponse {
+                    error: e.to_string(),
+                }),
+            )
+                .into_response();
+        }
+    };
+
+    let initial_balance = *state.engine.initial_balance.lock();
+    let mut curve = crate::db::equity_curve(&trades, initial_balance);
+
+    let position = *state.engine.current_position.lock();
+    let avg_entry = *state.engine.avg_entry_price.lock();
+    let last_price = *state.engine.last_price.lock();
+    let unrealized_pnl = (last_price - avg_entry) * position;
+    let realized_equity = curve.last().map_or(initial_balance, |&(_, equity)| equity);
+    // Wall clock, like the trades' exchange_ts_ms
+    let now_ms = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis() as u64;
+    curve.push((now_ms, realized_equity + unrealized_pnl));
+
+    Json(curve).into_response()
+}
+
+async fn get_pnl_daily(State(state): State<AppState>) -> impl IntoResponse {
+    match state.db.get_daily_pnl().await {
+        Ok(days) => Json(days).into_response(),
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse {
+                error: e.to_string(),
+            }),
+        )
+            .into_response(),
+    }
+}
+
+async fn get_stats(State(state): State<AppState>) -> impl IntoResponse {
+    match state.db.get_stats().await {
+        Ok(stats) => Json(stats).into_response(),
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse {
+                error: e.to_string(),
+            }),
+        )
+            .into_response(),
+    }
+}
+
 async fn get_logs(State(state): State<AppState>) -> impl IntoResponse {
     let logs = state.engine.recent_logs.lock().clone();
     Json(logs).into_response()
 }
 
+async fn get_positions(State(state): State<AppState>) -> impl IntoResponse {
+    match state.client.sync_positions(None).await {
+        Ok(positions) => Json(positions).into_response(),
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse {
+                error: e.to_string(),
+            }),
+        )
+            .into_response(),
+    }
+}
+
+async fn get_orders(
+    State(state): State<AppState>,
+    Query(params): Query<OrdersQuery>,
+) -> impl IntoResponse {
+    match state.client.get_open_orders(params.symbol.as_deref()).await {
+        Ok(orders) => Json(orders).into_response(),
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse {
+                error: e.to_string(),
+            }),
+        )
+            .into_response(),
+    }
+}
+
+async fn get_klines(
+    State(state): State<AppState>,
+    Query(params): Query<KlinesQuery>,
+) -> impl IntoResponse {
+    let symbol = params
+        .symbol
+        .unwrap_or_else(|| state.engine.symbol.lock().clone());
+    let interval = params.interval.as_deref().unwrap_or("1m");
+    match state
+        .client
+        .get_klines(&symbol, interval, params.limit.unwrap_or(500))
+        .await
+    {
+        Ok(klines) => Json(klines).into_response(),
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse {
+                error: e.to_string(),
+            }),
+        )
+            .into_response(),
+    }
+}
+
 // SSE Handler
 async fn sse_handler(
     State(state): State<AppState>,
@@ -354,17 +930,21 @@ async fn sse_handler(
             let avg_entry = *state.engine.avg_entry_price.lock();
             let last_price = *state.engine.last_price.lock();
             let available_balance = *state.engine.available_balance.lock();
+            let initial_balance = *state.engine.initial_balance.lock();
+            let strategy_latency_ns = state.engine.last_strategy_latency_ns.load(Ordering::Relaxed);
 
             let unrealized_pnl = (last_price - avg_entry) * position;
 
             let data = serde_json::json!({
                 "pnl": pnl,
+                "pnl_pct": pnl_pct(pnl, initial_balance),
                 "unrealized_pnl": unrealized_pnl,
                 "last_tick": last_tick,
                 "tps": tps,
                 "trade_count": trade_count,
                 "position": position,
                 "available_balance": available_balance,
+                "strategy_latency_ns": strategy_latency_ns,
                 "ts": common::now_nanos() / 1_000_000 // ms
             });
 
@@ -374,3 +954,49 @@ async fn sse_handler(
 
     Sse::new(stream).keep_alive(axum::response::sse::KeepAlive::default())
 }
+
+/// Live log tail: one `{ts_ms, level, message}` event per line from now on.
+/// A client that falls behind gets a `... N lines dropped` marker instead of the
+/// missed lines.
+async fn log_stream_handler(
+    State(state): State<AppState>,
+) -> Sse<impl Stream<Item = Result<Event, Infallible>>> {
+    let mut rx = state.engine.log_stream.subscribe();
+    let stream = async_stream::stream! {
+        loop {
+            let line = match rx.recv().await {
+                Ok(line) => line,
+                Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                    telemetry::LogLine::now("WARN", format!("... {} lines dropped", skipped))
+                }
+                Err(broadcast::error::RecvError::Closed) => break,
+            };
+            let data = serde_json::to_string(&line).unwrap_or_default();
+            yield Ok(Event::default().data(data));
+        }
+    };
+
+    Sse::new(stream).keep_alive(axum::response::sse::KeepAlive::default())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_metrics() {
+        let engine = EngineState::new();
+        engine.trade_count.store(3, Ordering::Relaxed);
+        *engine.current_pnl.lock() = -1.5;
+        *engine.current_position.lock() = 0.02;
+        *engine.feed_status.lock() = FeedStatus::Connected;
+
+        let body = render_metrics(&engine);
+
+        assert!(body.contains("# TYPE hft_trade_count counter\nhft_trade_count 3\n"));
+        assert!(body.contains("\nhft_pnl -1.5\n"));
+        assert!(body.contains("\nhft_position 0.02\n"));
+        assert!(body.contains("\nhft_order_rtt_ns 0\n"));
+        assert!(body.contains("\nhft_feed_connected 1\n"));
+    }
+}
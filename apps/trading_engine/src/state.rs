@@ -1,15 +1,106 @@
+use feed_handler::FeedStatus;
+use hdrhistogram::Histogram;
 use parking_lot::Mutex;
+use risk_engine::RiskLimits;
+use serde::Serialize;
 use std::collections::VecDeque;
 use std::sync::{
-    atomic::{AtomicBool, AtomicU64, AtomicUsize},
+    atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering},
     Arc,
 };
+use strategy::StrategyParams;
+use telemetry::{LogBuffer, LogLine};
+use tokio::sync::broadcast;
+
+/// Order RTT percentiles over the rolling [`RttWindow`].
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct LatencySnapshot {
+    pub count: u64,
+    pub p50_ns: u64,
+    pub p95_ns: u64,
+    pub p99_ns: u64,
+    pub max_ns: u64,
+}
+
+/// Span of one order RTT histogram; percentiles cover the last one to two of these.
+pub const ORDER_RTT_WINDOW_NS: u64 = 5 * 60 * 1_000_000_000;
+
+/// Order RTT distribution (1ns..60s, 3 significant figures) over a rolling window: two
+/// histograms, the older one dropped every `ORDER_RTT_WINDOW_NS`, so the percentiles
+/// follow current conditions rather than the whole session.
+pub struct RttWindow {
+    current: Histogram<u64>,
+    previous: Histogram<u64>,
+    started_ns: u64,
+}
+
+impl RttWindow {
+    pub fn new(now_ns: u64) -> Self {
+        let hist =
+            Histogram::new_with_bounds(1, 60_000_000_000, 3).expect("Valid histogram bounds");
+        Self {
+            current: hist.clone(),
+            previous: hist,
+            started_ns: now_ns,
+        }
+    }
+
+    fn rotate(&mut self, now_ns: u64) {
+        let elapsed = now_ns.saturating_sub(self.started_ns);
+        if elapsed < ORDER_RTT_WINDOW_NS {
+            return;
+        }
+        std::mem::swap(&mut self.current, &mut self.previous);
+        self.current.reset();
+        if elapsed >= 2 * ORDER_RTT_WINDOW_NS {
+            // Nothing recorded in the last full window either
+            self.previous.reset();
+        }
+        self.started_ns = now_ns;
+    }
+
+    pub fn record(&mut self, rtt_ns: u64, now_ns: u64) {
+        self.rotate(now_ns);
+        self.current.saturating_record(rtt_ns.max(1));
+    }
+
+    pub fn snapshot(&mut self, now_ns: u64) -> LatencySnapshot {
+        self.rotate(now_ns);
+        let mut hist = self.previous.clone();
+        hist.add(&self.current).expect("Same histogram bounds");
+        if hist.is_empty() {
+            return LatencySnapshot::default();
+        }
+        LatencySnapshot {
+            count: hist.len(),
+            p50_ns: hist.value_at_quantile(0.50),
+            p95_ns: hist.value_at_quantile(0.95),
+            p99_ns: hist.value_at_quantile(0.99),
+            max_ns: hist.max(),
+        }
+    }
+
+    pub fn reset(&mut self, now_ns: u64) {
+        self.current.reset();
+        self.previous.reset();
+        self.started_ns = now_ns;
+    }
+}
+
+/// Default `pnl_history` capacity (`trading.pnl_history_capacity`).
+pub const DEFAULT_PNL_HISTORY_CAPACITY: usize = 5000;
 
 pub struct EngineState {
+    /// Traded instrument (from `trading.symbol`).
+    pub symbol: Mutex<String>,
     /// Global Start/Stop switch.
     pub is_running: Arc<AtomicBool>,
     /// Exit guard.
     pub shutting_down: AtomicBool,
+    /// Paper-trading switch applied to every order at execution time.
+    pub dry_run: AtomicBool,
+    /// Whether live orders may be sent to a Mainnet endpoint (`trading.allow_mainnet`).
+    pub allow_mainnet: AtomicBool,
     /// Number of executed trades.
     pub trade_count: AtomicUsize,
 
@@ -26,14 +117,40 @@ pub struct EngineState {
     // --- Telemetry ---
     pub last_tick_timestamp: AtomicU64, // Epoch ms
     pub last_order_rtt_ns: AtomicU64,
-    pub current_position: Mutex<f64>,
+    /// Recent order RTT distribution.
+    pub order_rtt_hist: Mutex<RttWindow>,
+    /// Ticks that exceeded the tick-to-strategy latency budget (shared with the strategy thread).
+    pub stale_ticks: Arc<AtomicU64>,
+    /// Ticks dropped because the market ring buffer was full.
+    pub dropped_ticks: AtomicU64,
+    /// Ticks dropped by the feed handler (websocket -> channel backpressure).
+    pub feed_dropped_ticks: Arc<AtomicU64>,
+    /// Tick-to-strategy latency of the last processed event (internal pipeline, not network).
+    pub last_strategy_latency_ns: Arc<AtomicU64>,
+    /// When the current position was opened (epoch ms), 0 while flat. Drives `max_hold_secs`.
+    pub position_opened_ms: AtomicU64,
+    /// Shared with the strategy thread for position reconciliation.
+    pub current_position: Arc<Mutex<f64>>,
     pub avg_entry_price: Mutex<f64>,
     pub last_price: Mutex<f64>,
+    /// Latest websocket connection state reported by the feed.
+    pub feed_status: Mutex<FeedStatus>,
 
     // History (Capped)
     pub pnl_history: Mutex<VecDeque<(u64, f64)>>, // (ts_ms, pnl)
-    pub recent_logs: Mutex<VecDeque<String>>,
+    /// Max points in `pnl_history`; when full the history is downsampled, not truncated.
+    pub pnl_history_capacity: AtomicUsize,
+    /// Dashboard log lines; shared with telemetry, which appends WARN+ events.
+    pub recent_logs: LogBuffer,
+    /// Live tail of `recent_logs` for `/api/logs/stream`.
+    pub log_stream: broadcast::Sender<LogLine>,
     pub active_strategy: Arc<Mutex<String>>,
+    /// Live-tunable strategy parameters (watched by the strategy thread).
+    pub strategy_params: Arc<Mutex<StrategyParams>>,
+    /// One-shot request for the strategy thread to call `Strategy::reset()`.
+    pub strategy_reset: Arc<AtomicBool>,
+    /// Live risk limits (read by the execution task's `RiskEngine` on every check).
+    pub risk_limits: Arc<Mutex<RiskLimits>>,
 
     // Speed Meter
     pub ticks_counter: AtomicUsize,
@@ -42,11 +159,20 @@ pub struct EngineState {
     pub current_cps: AtomicUsize,
 }
 
+impl Default for EngineState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl EngineState {
     pub fn new() -> Self {
         Self {
+            symbol: Mutex::new("BTCUSDT".to_string()),
             is_running: Arc::new(AtomicBool::new(false)),
             shutting_down: AtomicBool::new(false),
+            dry_run: AtomicBool::new(true),
+            allow_mainnet: AtomicBool::new(false),
             trade_count: AtomicUsize::new(0),
             current_pnl: Mutex::new(0.0),
             max_loss_limit: Mutex::new(0.0),
@@ -56,13 +182,30 @@ impl EngineState {
 
             last_tick_timestamp: AtomicU64::new(0),
             last_order_rtt_ns: AtomicU64::new(0),
-            current_position: Mutex::new(0.0),
+            order_rtt_hist: Mutex::new(RttWindow::new(common::now_nanos())),
+            stale_ticks: Arc::new(AtomicU64::new(0)),
+            dropped_ticks: AtomicU64::new(0),
+            feed_dropped_ticks: Arc::new(AtomicU64::new(0)),
+            last_strategy_latency_ns: Arc::new(AtomicU64::new(0)),
+            position_opened_ms: AtomicU64::new(0),
+            current_position: Arc::new(Mutex::new(0.0)),
             avg_entry_price: Mutex::new(0.0),
             last_price: Mutex::new(0.0),
+            feed_status: Mutex::new(FeedStatus::Disconnected),
 
-            pnl_history: Mutex::new(VecDeque::with_capacity(5000)),
-            recent_logs: Mutex::new(VecDeque::with_capacity(200)),
+            pnl_history: Mutex::new(VecDeque::with_capacity(DEFAULT_PNL_HISTORY_CAPACITY)),
+            pnl_history_capacity: AtomicUsize::new(DEFAULT_PNL_HISTORY_CAPACITY),
+            recent_logs: telemetry::new_log_buffer(),
+            log_stream: telemetry::new_log_stream(),
             active_strategy: Arc::new(Mutex::new("PING_PONG".to_string())),
+            strategy_params: Arc::new(Mutex::new(StrategyParams::default())),
+            strategy_reset: Arc::new(AtomicBool::new(false)),
+            // Set from config at startup
+            risk_limits: Arc::new(Mutex::new(RiskLimits {
+                max_order_size: 0.0,
+                max_daily_loss: 0.0,
+                max_position: 0.0,
+            })),
 
             ticks_counter: AtomicUsize::new(0),
             cycles_counter: AtomicUsize::new(0),
@@ -71,14 +214,113 @@ impl EngineState {
         }
     }
 
+    /// State whose `recent_logs`/`log_stream` are the ones registered with
+    /// `telemetry::init`, so the dashboard shows tracing warnings/errors alongside
+    /// `add_log` lines.
+    pub fn with_logs(recent_logs: LogBuffer, log_stream: broadcast::Sender<LogLine>) -> Self {
+        Self {
+            recent_logs,
+            log_stream,
+            ..Self::new()
+        }
+    }
+
     pub fn add_log(&self, msg: String) {
-        let mut logs = self.recent_logs.lock();
-        if logs.len() >= 200 {
-            logs.pop_front();
+        telemetry::push_log(&self.recent_logs, msg.clone());
+        let _ = self.log_stream.send(LogLine::now("INFO", msg));
+    }
+
+    /// Stops the engine if it is running and no tick has arrived for `timeout_ms`.
+    /// Returns true if it tripped. Never trips before the first tick.
+    pub fn stop_if_feed_stale(&self, now_ms: u64, timeout_ms: u64) -> bool {
+        let last_tick = self.last_tick_timestamp.load(Ordering::Relaxed);
+        if last_tick == 0 || now_ms.saturating_sub(last_tick) < timeout_ms {
+            return false;
+        }
+        // Only trip once per stall: a stopped engine stays stopped until restarted via the API
+        self.is_running.swap(false, Ordering::SeqCst)
+    }
+
+    /// True once the open position has been held for `max_hold_ms`, i.e. it must be force-closed.
+    /// The clock then restarts, so a close still in flight isn't re-triggered on every check.
+    /// A position without an open time (e.g. synced at startup) starts its clock now.
+    pub fn take_expired_position(&self, now_ms: u64, max_hold_ms: u64) -> bool {
+        if self.current_position.lock().abs() < 0.000001 {
+            self.position_opened_ms.store(0, Ordering::Relaxed);
+            return false;
+        }
+        let opened_ms = self.position_opened_ms.load(Ordering::Relaxed);
+        if opened_ms == 0 {
+            self.position_opened_ms.store(now_ms, Ordering::Relaxed);
+            return false;
+        }
+        if now_ms.saturating_sub(opened_ms) < max_hold_ms {
+            return false;
+        }
+        self.position_opened_ms.store(now_ms, Ordering::Relaxed);
+        true
+    }
+
+    /// Stops the engine if PnL breached the max-loss limit or reached the profit target.
+    /// Returns which limit tripped.
+    pub fn stop_if_pnl_limit_hit(&self) -> Option<&'static str> {
+        let pnl = *self.current_pnl.lock();
+        let max_loss = *self.max_loss_limit.lock();
+        let target_profit = *self.target_profit.lock();
+
+        let reason = if pnl <= -max_loss {
+            "Max Loss Limit Hit"
+        } else if target_profit > 0.0 && pnl >= target_profit {
+            "Target Profit Hit"
+        } else {
+            return None;
+        };
+        self.is_running.store(false, Ordering::SeqCst);
+        Some(reason)
+    }
+
+    /// Append to `pnl_history`. At capacity, every other point is dropped first (keeping
+    /// the first and latest), so the chart keeps the whole session at half the resolution.
+    pub fn record_pnl_point(&self, ts_ms: u64, pnl: f64) {
+        let capacity = self.pnl_history_capacity.load(Ordering::Relaxed).max(2);
+        let mut history = self.pnl_history.lock();
+        if history.len() >= capacity {
+            let last = history.len() - 1;
+            let mut i = 0;
+            history.retain(|_| {
+                let keep = i == 0 || (last - i).is_multiple_of(2);
+                i += 1;
+                keep
+            });
         }
-        logs.push_back(msg);
+        history.push_back((ts_ms, pnl));
+    }
+
+    /// Counts a tick dropped on a full ring buffer.
+    /// Returns the running total when a throttled warning is due (first drop, then every 1000th).
+    pub fn record_dropped_tick(&self) -> Option<u64> {
+        let dropped = self.dropped_ticks.fetch_add(1, Ordering::Relaxed) + 1;
+        (dropped - 1).is_multiple_of(1000).then_some(dropped)
+    }
+
+    /// Records an order round-trip time (last value + rolling histogram).
+    pub fn record_order_rtt(&self, rtt_ns: u64) {
+        self.last_order_rtt_ns.store(rtt_ns, Ordering::Relaxed);
+        self.order_rtt_hist
+            .lock()
+            .record(rtt_ns, common::now_nanos());
     }
 
+    pub fn order_rtt_snapshot(&self) -> LatencySnapshot {
+        self.order_rtt_hist.lock().snapshot(common::now_nanos())
+    }
+
+    pub fn reset_order_rtt(&self) {
+        self.order_rtt_hist.lock().reset(common::now_nanos());
+    }
+
+    /// Applies a fill to position/avg-entry and returns the realized PnL net of `fee`.
+    /// `qty` is signed (+ buy, - sell); `fee` is the absolute fee paid in quote currency.
     pub fn update_from_trade(&self, qty: f64, price: f64, fee: f64) -> f64 {
         let mut pos = self.current_position.lock();
         let mut avg_entry = self.avg_entry_price.lock();
@@ -124,20 +366,299 @@ impl EngineState {
 
         *pos = new_pos;
 
+        // Holding-time clock: restarts on open or flip, clears when flat
+        if new_pos == 0.0 {
+            self.position_opened_ms.store(0, Ordering::Relaxed);
+        } else if old_pos == 0.0 || old_pos.signum() != new_pos.signum() {
+            let now_ms = std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_millis() as u64;
+            self.position_opened_ms.store(now_ms, Ordering::Relaxed);
+        }
+
         // Update Global PnL
         // We update PnL if there is realized PnL OR if there is a fee (even on open)
         if realized_pnl != 0.0 || fee > 0.0 {
             let mut pnl_lock = self.current_pnl.lock();
             *pnl_lock += realized_pnl;
 
-            // Add to history
-            let mut history = self.pnl_history.lock();
-            if history.len() >= 5000 {
-                history.pop_front();
-            }
-            history.push_back((common::now_nanos() / 1_000_000, *pnl_lock));
+            self.record_pnl_point(common::now_nanos() / 1_000_000, *pnl_lock);
         }
 
         realized_pnl
     }
 }
+
+/// PnL as a percentage of the starting balance; 0 until the balance is known.
+pub fn pnl_pct(pnl: f64, initial_balance: f64) -> f64 {
+    if initial_balance > 0.0 {
+        pnl / initial_balance * 100.0
+    } else {
+        0.0
+    }
+}
+
+/// Estimated fee for a fill when the exchange doesn't report one.
+/// Resting limit orders pay the maker rate; everything else is assumed Taker.
+pub fn estimate_fee(
+    order_type: common::OrderType,
+    quantity: f64,
+    price: f64,
+    fee_maker: f64,
+    fee_taker: f64,
+) -> f64 {
+    let rate = match order_type {
+        common::OrderType::Limit => fee_maker,
+        common::OrderType::Market | common::OrderType::StopMarket => fee_taker,
+    };
+    quantity * price * rate
+}
+
+/// Fee in quote currency (USDT) for a confirmed fill.
+/// Uses the exchange-reported commission when it is charged in USDT; otherwise
+/// (e.g. BNB fee discount) prices the fill at the maker or taker rate it actually paid.
+pub fn fill_fee(fill: &execution::FillEvent, fee_maker: f64, fee_taker: f64) -> f64 {
+    if fill.commission_asset == "USDT" {
+        return fill.commission;
+    }
+    let rate = if fill.is_maker { fee_maker } else { fee_taker };
+    fill.quantity * fill.price * rate
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_add_log_buffers_and_streams() {
+        let state = EngineState::new();
+        let mut rx = state.log_stream.subscribe();
+
+        state.add_log("System Started".to_string());
+
+        assert_eq!(
+            state.recent_logs.lock().back().map(String::as_str),
+            Some("System Started")
+        );
+        let line = rx.try_recv().expect("Line not streamed");
+        assert_eq!(line.level, "INFO");
+        assert_eq!(line.message, "System Started");
+    }
+
+    #[test]
+    fn test_open_only_charges_fee() {
+        let state = EngineState::new();
+        let fee = estimate_fee(common::OrderType::Market, 0.01, 50_000.0, 0.0002, 0.0005);
+
+        let realized = state.update_from_trade(0.01, 50_000.0, fee);
+
+        assert!((realized + 0.25).abs() < 1e-9);
+        assert!((*state.current_pnl.lock() + 0.25).abs() < 1e-9);
+        assert_eq!(*state.current_position.lock(), 0.01);
+    }
+
+    fn fill(is_maker: bool, commission: f64, commission_asset: &str) -> execution::FillEvent {
+        execution::FillEvent {
+            symbol: "BTCUSDT".to_string(),
+            order_id: 1,
+            client_order_id: String::new(),
+            side: common::Side::Buy,
+            quantity: 0.01,
+            price: 50_000.0,
+            commission,
+            commission_asset: commission_asset.to_string(),
+            is_maker,
+            trade_id: 1,
+            trade_time: 0,
+            order_status: "FILLED".to_string(),
+            raw: String::new(),
+        }
+    }
+
+    #[test]
+    fn test_fill_fee_uses_fill_liquidity() {
+        // Reported USDT commission is used as-is
+        assert_eq!(fill_fee(&fill(true, 0.07, "USDT"), 0.0002, 0.0005), 0.07);
+
+        // Non-USDT commission: priced at the rate matching maker/taker
+        let maker = fill_fee(&fill(true, 0.0001, "BNB"), 0.0002, 0.0005);
+        let taker = fill_fee(&fill(false, 0.0001, "BNB"), 0.0002, 0.0005);
+        assert!((maker - 0.1).abs() < 1e-9);
+        assert!((taker - 0.25).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_round_trip_nets_price_move_minus_fees() {
+        let state = EngineState::new();
+        let entry_fee = estimate_fee(common::OrderType::Market, 0.01, 50_000.0, 0.0002, 0.0005);
+        let exit_fee = estimate_fee(common::OrderType::Limit, 0.01, 50_100.0, 0.0002, 0.0005);
+
+        state.update_from_trade(0.01, 50_000.0, entry_fee);
+        state.update_from_trade(-0.01, 50_100.0, exit_fee);
+
+        let expected = (50_100.0 - 50_000.0) * 0.01 - entry_fee - exit_fee;
+        assert!((*state.current_pnl.lock() - expected).abs() < 1e-9);
+        assert_eq!(*state.current_position.lock(), 0.0);
+        assert_eq!(*state.avg_entry_price.lock(), 0.0);
+    }
+
+    #[test]
+    fn test_feed_watchdog_stops_when_ticks_stall() {
+        let state = EngineState::new();
+        state.is_running.store(true, Ordering::SeqCst);
+
+        // No tick received yet
+        assert!(!state.stop_if_feed_stale(1_000_000, 5_000));
+
+        // Ticks advancing
+        state
+            .last_tick_timestamp
+            .store(1_000_000, Ordering::Relaxed);
+        assert!(!state.stop_if_feed_stale(1_001_000, 5_000));
+        state
+            .last_tick_timestamp
+            .store(1_002_000, Ordering::Relaxed);
+        assert!(!state.stop_if_feed_stale(1_004_000, 5_000));
+
+        // Timestamp stops advancing
+        assert!(state.stop_if_feed_stale(1_007_000, 5_000));
+        assert!(!state.is_running.load(Ordering::SeqCst));
+
+        // Already stopped: no repeated alert
+        assert!(!state.stop_if_feed_stale(1_010_000, 5_000));
+    }
+
+    #[test]
+    fn test_max_hold_expires_open_position() {
+        let state = EngineState::new();
+
+        // Flat: nothing to expire
+        assert!(!state.take_expired_position(1_000_000, 60_000));
+
+        state.update_from_trade(0.01, 50_000.0, 0.0);
+        assert!(state.position_opened_ms.load(Ordering::Relaxed) > 0);
+        state.position_opened_ms.store(1_000_000, Ordering::Relaxed);
+
+        assert!(!state.take_expired_position(1_059_999, 60_000));
+        assert!(state.take_expired_position(1_060_000, 60_000));
+        // Timer restarted: no repeat while the close is in flight
+        assert!(!state.take_expired_position(1_061_000, 60_000));
+
+        // Back to flat resets the timer
+        state.update_from_trade(-0.01, 50_000.0, 0.0);
+        assert_eq!(state.position_opened_ms.load(Ordering::Relaxed), 0);
+        assert!(!state.take_expired_position(2_000_000, 60_000));
+
+        // Position without a recorded open time (synced at startup) starts its clock
+        *state.current_position.lock() = -0.02;
+        assert!(!state.take_expired_position(3_000_000, 60_000));
+        assert!(state.take_expired_position(3_060_000, 60_000));
+    }
+
+    #[test]
+    fn test_pnl_pct_of_initial_balance() {
+        assert!((pnl_pct(25.0, 1000.0) - 2.5).abs() < 1e-9);
+        assert!((pnl_pct(-50.0, 1000.0) + 5.0).abs() < 1e-9);
+        // Balance unknown (no keys / not synced yet)
+        assert_eq!(pnl_pct(25.0, 0.0), 0.0);
+    }
+
+    #[test]
+    fn test_pnl_history_downsamples_at_capacity() {
+        let state = EngineState::new();
+        state.pnl_history_capacity.store(8, Ordering::Relaxed);
+
+        for ts in 1..=100u64 {
+            state.record_pnl_point(ts, ts as f64);
+        }
+
+        let history = state.pnl_history.lock();
+        assert!(history.len() <= 8);
+        // Whole span retained, in time order
+        assert_eq!(history.front().unwrap().0, 1);
+        assert_eq!(history.back().unwrap().0, 100);
+        assert!(history
+            .iter()
+            .zip(history.iter().skip(1))
+            .all(|(a, b)| a.0 < b.0));
+    }
+
+    #[test]
+    fn test_pnl_limits_stop_engine() {
+        let state = EngineState::new();
+        *state.max_loss_limit.lock() = 10.0;
+        *state.target_profit.lock() = 20.0;
+        state.is_running.store(true, Ordering::SeqCst);
+
+        *state.current_pnl.lock() = 5.0;
+        assert_eq!(state.stop_if_pnl_limit_hit(), None);
+        assert!(state.is_running.load(Ordering::SeqCst));
+
+        *state.current_pnl.lock() = -10.0;
+        assert_eq!(state.stop_if_pnl_limit_hit(), Some("Max Loss Limit Hit"));
+        assert!(!state.is_running.load(Ordering::SeqCst));
+
+        state.is_running.store(true, Ordering::SeqCst);
+        *state.current_pnl.lock() = 25.0;
+        assert_eq!(state.stop_if_pnl_limit_hit(), Some("Target Profit Hit"));
+
+        // A zero target disables take-profit
+        *state.target_profit.lock() = 0.0;
+        assert_eq!(state.stop_if_pnl_limit_hit(), None);
+    }
+
+    #[test]
+    fn test_dropped_ticks_warning_is_throttled() {
+        let state = EngineState::new();
+        assert_eq!(state.record_dropped_tick(), Some(1));
+        for _ in 2..=1000 {
+            assert_eq!(state.record_dropped_tick(), None);
+        }
+        assert_eq!(state.record_dropped_tick(), Some(1001));
+        assert_eq!(state.dropped_ticks.load(Ordering::Relaxed), 1001);
+    }
+
+    #[test]
+    fn test_order_rtt_percentiles_and_reset() {
+        let state = EngineState::new();
+        assert_eq!(state.order_rtt_snapshot(), LatencySnapshot::default());
+
+        for rtt_ms in 1..=100u64 {
+            state.record_order_rtt(rtt_ms * 1_000_000);
+        }
+
+        let snapshot = state.order_rtt_snapshot();
+        assert_eq!(snapshot.count, 100);
+        assert_eq!(state.last_order_rtt_ns.load(Ordering::Relaxed), 100_000_000);
+        // 3 significant figures -> within 0.1%
+        assert!((snapshot.p50_ns as f64 - 50e6).abs() / 50e6 < 1e-3);
+        assert!((snapshot.p99_ns as f64 - 99e6).abs() / 99e6 < 1e-3);
+        assert!(snapshot.p95_ns <= snapshot.p99_ns && snapshot.p99_ns <= snapshot.max_ns);
+
+        state.reset_order_rtt();
+        assert_eq!(state.order_rtt_snapshot().count, 0);
+    }
+
+    #[test]
+    fn test_rtt_window_drops_old_samples() {
+        let mut window = RttWindow::new(0);
+        window.record(10_000_000, 0);
+        window.record(20_000_000, ORDER_RTT_WINDOW_NS / 2);
+
+        // Rotated once: the first window's samples are still included
+        window.record(1_000_000, ORDER_RTT_WINDOW_NS);
+        assert_eq!(window.snapshot(ORDER_RTT_WINDOW_NS).count, 3);
+
+        // Rotated again: only the second window remains
+        let snapshot = window.snapshot(2 * ORDER_RTT_WINDOW_NS);
+        assert_eq!(snapshot.count, 1);
+        assert!((snapshot.max_ns as f64 - 1e6).abs() / 1e6 < 1e-3);
+
+        // Idle for two full windows: empty
+        assert_eq!(
+            window.snapshot(5 * ORDER_RTT_WINDOW_NS),
+            LatencySnapshot::default()
+        );
+    }
+}
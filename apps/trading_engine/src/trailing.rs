@@ -0,0 +1,112 @@
+/// Trailing stop over the engine's net position.
+///
+/// Tracks the best price since the position was opened (highest for a long, lowest for a
+/// short, starting from the entry price) and fires once the market retraces `trailing_pct`
+/// percent from it. Going flat or flipping sides starts a new trail.
+#[derive(Debug, Clone)]
+pub struct TrailingStop {
+    trailing_pct: f64,
+    /// Best price so far and the side (+1 long / -1 short) it belongs to.
+    peak: Option<(f64, f64)>,
+}
+
+impl TrailingStop {
+    /// `trailing_pct <= 0` disables the stop.
+    pub fn new(trailing_pct: f64) -> Self {
+        Self {
+            trailing_pct,
+            peak: None,
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.trailing_pct > 0.0
+    }
+
+    /// Feed the current position, entry and market price. Returns true when the
+    /// position should be closed. The trail then restarts from `last_price`, so a close
+    /// still in flight only fires again after another full retrace.
+    pub fn update(&mut self, position: f64, avg_entry: f64, last_price: f64) -> bool {
+        if !self.is_enabled() || position.abs() < 0.000001 || last_price <= 0.0 {
+            self.peak = None;
+            return false;
+        }
+
+        let side = position.signum();
+        let peak = match self.peak {
+            Some((peak, peak_side)) if peak_side == side => peak,
+            // New position (or flipped): trail from the entry
+            _ if avg_entry > 0.0 => avg_entry,
+            _ => last_price,
+        };
+        let peak = if side > 0.0 {
+            peak.max(last_price)
+        } else {
+            peak.min(last_price)
+        };
+
+        let retrace_pct = (peak - last_price) * side / peak * 100.0;
+        if retrace_pct >= self.trailing_pct {
+            self.peak = Some((last_price, side));
+            return true;
+        }
+        self.peak = Some((peak, side));
+        false
+    }
+
+    /// Best price reached by the current position, if any.
+    pub fn peak(&self) -> Option<f64> {
+        self.peak.map(|(peak, _)| peak)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_long_trails_the_high() {
+        let mut stop = TrailingStop::new(1.0);
+
+        assert!(!stop.update(0.01, 100.0, 100.0));
+        assert!(!stop.update(0.01, 100.0, 110.0));
+        assert_eq!(stop.peak(), Some(110.0));
+
+        // 0.9% off the high: hold
+        assert!(!stop.update(0.01, 100.0, 109.01));
+        // >1% off the high: exit, even though still above entry
+        assert!(stop.update(0.01, 100.0, 108.8));
+
+        // Flat clears the trail
+        assert!(!stop.update(0.0, 0.0, 108.8));
+        assert_eq!(stop.peak(), None);
+    }
+
+    #[test]
+    fn test_short_trails_the_low() {
+        let mut stop = TrailingStop::new(1.0);
+
+        assert!(!stop.update(-0.01, 100.0, 100.0));
+        assert!(!stop.update(-0.01, 100.0, 90.0));
+        assert_eq!(stop.peak(), Some(90.0));
+
+        assert!(!stop.update(-0.01, 100.0, 90.8));
+        assert!(stop.update(-0.01, 100.0, 91.0));
+    }
+
+    #[test]
+    fn test_entry_is_the_initial_peak_and_flip_restarts() {
+        let mut stop = TrailingStop::new(2.0);
+
+        // Long entered at 100, first observed price already >2% lower
+        assert!(stop.update(0.01, 100.0, 97.9));
+
+        // Flipped short at 98: the old long high no longer applies
+        assert!(!stop.update(-0.01, 98.0, 99.0));
+        assert!(stop.update(-0.01, 98.0, 100.0));
+
+        // Disabled
+        let mut off = TrailingStop::new(0.0);
+        assert!(!off.update(0.01, 100.0, 50.0));
+    }
+}
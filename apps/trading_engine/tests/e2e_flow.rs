@@ -1,9 +1,10 @@
 use common::{MarketEvent, Side};
 use std::sync::{
-    atomic::{AtomicBool, Ordering},
+    atomic::{AtomicBool, AtomicU64, Ordering},
     Arc,
 };
 use std::time::{Duration, Instant};
+use strategy::{StrategyParams, WaitStrategy};
 
 #[test]
 fn test_end_to_end_pipeline() {
@@ -12,7 +13,7 @@ fn test_end_to_end_pipeline() {
 
     // Create Queues
     let (mut market_prod, market_cons) = rtrb::RingBuffer::<MarketEvent>::new(32);
-    let (trade_prod, mut trade_cons) = rtrb::RingBuffer::<common::TradeInstruction>::new(32);
+    let (trade_prod, mut trade_cons) = rtrb::RingBuffer::<common::ExecSignal>::new(32);
 
     // 2. Spawn Strategy
     let s_flag = shutdown.clone();
@@ -27,14 +28,16 @@ fn test_end_to_end_pipeline() {
             s_flag,
             r_flag,
             active_strategy,
-            true,   // dry_run
-            false,  // disable_throttle
-            0.0002, // fee_maker
-            0.0005, // fee_taker
-            50,     // strategy_window
-            2.0,    // strategy_threshold
-            10.0,   // price_threshold
-            3.0,    // volume_multiplier
+            Arc::new(parking_lot::Mutex::new(StrategyParams::default())),
+            true,                                   // dry_run
+            false,                                  // disable_throttle
+            1_000_000,                              // latency_warn_ns
+            Arc::new(AtomicU64::new(0)),            // stale_ticks
+            Arc::new(AtomicU64::new(0)),            // last_latency_ns
+            Arc::new(parking_lot::Mutex::new(0.0)), // engine_position
+            Arc::new(AtomicBool::new(false)),       // force_reset
+            Vec::new(),                             // seed_prices
+            WaitStrategy::Yield,                    // wait
         );
     });
 
@@ -42,29 +45,25 @@ fn test_end_to_end_pipeline() {
 
     // Event A: Should NOT trigger (Price <= 50,000)
     let event_a = MarketEvent {
-        symbol: "BTCUSDT".into(),
-        price: 49_000.0,
-        quantity: 1.0,
         exchange_timestamp: 1000,
         received_timestamp: common::now_nanos(),
+        ..MarketEvent::trade("BTCUSDT", 49_000.0, 1.0)
     };
     market_prod.push(event_a).expect("Failed to push event A");
 
     // Event B: Should TRIGGER (Price > 50,000)
     let event_b = MarketEvent {
-        symbol: "BTCUSDT".into(),
-        price: 50_001.0,
-        quantity: 1.0,
         exchange_timestamp: 2000,
         received_timestamp: common::now_nanos(),
+        ..MarketEvent::trade("BTCUSDT", 50_001.0, 1.0)
     };
     market_prod.push(event_b).expect("Failed to push event B");
 
     // 4. Poll for Result (with timeout)
     let start = Instant::now();
     let result = loop {
-        if let Ok(instr) = trade_cons.pop() {
-            break Some(instr);
+        if let Ok(signal) = trade_cons.pop() {
+            break signal.into_place();
         }
         if start.elapsed() > Duration::from_millis(200) {
             break None;
@@ -77,6 +76,7 @@ fn test_end_to_end_pipeline() {
     assert_eq!(instr.symbol, "BTCUSDT");
     assert_eq!(instr.side, Side::Buy);
     assert_eq!(instr.price, 50_001.0);
+    assert_eq!(instr.order_type, common::OrderType::Market);
     assert_eq!(instr.quantity, 0.01);
     assert!(!instr.dry_run); // Strategies hardcode dry_run to false
     assert!(instr.timestamp > 0);
@@ -84,4 +84,8 @@ fn test_end_to_end_pipeline() {
     // 6. Shutdown
     shutdown.store(true, Ordering::Relaxed);
     strategy_handle.join().expect("Strategy thread panicked");
+    assert!(
+        trade_cons.pop().is_err(),
+        "Expected exactly one trade instruction"
+    );
 }
@@ -1,7 +1,10 @@
 pub mod error;
+pub mod symbol;
 pub mod time;
 pub mod types;
+pub mod ws;
 
 pub use error::EngineError;
-pub use time::now_nanos;
+pub use symbol::SymbolId;
+pub use time::{now_nanos, Clock, MockClock, SystemClock};
 pub use types::*;
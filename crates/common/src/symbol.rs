@@ -0,0 +1,169 @@
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::sync::RwLock;
+
+/// An interned, uppercase instrument symbol (`BTCUSDT`).
+///
+/// Events and instructions carry this instead of a string so the hot path never allocates
+/// or compares symbol text: interning an already-seen symbol is a hash lookup in a
+/// per-thread cache, comparisons are of a `u32`, and the name travels with the id so
+/// reading it takes no lock. Names are kept for the life of the process, which is fine
+/// for the handful of instruments a feed subscribes to. Serializes as the name.
+#[derive(Clone, Copy)]
+pub struct SymbolId {
+    id: u32,
+    name: &'static str,
+}
+
+#[derive(Default)]
+struct Interner {
+    ids: HashMap<&'static str, SymbolId>,
+}
+
+static INTERNER: Lazy<RwLock<Interner>> = Lazy::new(Default::default);
+
+thread_local! {
+    /// Symbols this thread has already interned, so repeat lookups skip `INTERNER`'s lock.
+    static CACHE: RefCell<HashMap<&'static str, SymbolId>> = RefCell::new(HashMap::new());
+}
+
+impl SymbolId {
+    /// Id for `name`, uppercased (exchanges disagree on case).
+    pub fn intern(name: &str) -> Self {
+        if name.bytes().any(|b| b.is_ascii_lowercase()) {
+            return Self::intern_exact(&name.to_ascii_uppercase());
+        }
+        Self::intern_exact(name)
+    }
+
+    fn intern_exact(name: &str) -> Self {
+        if let Some(id) = CACHE.with(|cache| cache.borrow().get(name).copied()) {
+            return id;
+        }
+        let id = Self::intern_shared(name);
+        CACHE.with(|cache| cache.borrow_mut().insert(id.name, id));
+        id
+    }
+
+    fn intern_shared(name: &str) -> Self {
+        if let Some(&id) = INTERNER
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .ids
+            .get(name)
+        {
+            return id;
+        }
+
+        let mut interner = INTERNER.write().unwrap_or_else(|e| e.into_inner());
+        // Another thread may have interned it between the two locks
+        if let Some(&id) = interner.ids.get(name) {
+            return id;
+        }
+        let name: &'static str = Box::leak(name.to_owned().into_boxed_str());
+        let id = SymbolId {
+            id: interner.ids.len() as u32,
+            name,
+        };
+        interner.ids.insert(name, id);
+        id
+    }
+
+    pub fn as_str(self) -> &'static str {
+        self.name
+    }
+}
+
+// The name is determined by the id, so only the id is compared and hashed
+impl PartialEq for SymbolId {
+    fn eq(&self, other: &Self) -> bool {
+        self.id == other.id
+    }
+}
+
+impl Eq for SymbolId {}
+
+impl Hash for SymbolId {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.id.hash(state);
+    }
+}
+
+impl From<&str> for SymbolId {
+    fn from(name: &str) -> Self {
+        SymbolId::intern(name)
+    }
+}
+
+impl PartialEq<str> for SymbolId {
+    fn eq(&self, other: &str) -> bool {
+        self.as_str().eq_ignore_ascii_case(other)
+    }
+}
+
+impl PartialEq<&str> for SymbolId {
+    fn eq(&self, other: &&str) -> bool {
+        *self == **other
+    }
+}
+
+impl fmt::Display for SymbolId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl fmt::Debug for SymbolId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(self.as_str(), f)
+    }
+}
+
+impl Serialize for SymbolId {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for SymbolId {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let name = String::deserialize(deserializer)?;
+        Ok(SymbolId::intern(&name))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_interning_is_stable_and_case_insensitive() {
+        let a = SymbolId::intern("SOLUSDT");
+        assert_eq!(SymbolId::intern("solusdt"), a);
+        assert_ne!(SymbolId::intern("ADAUSDT"), a);
+        assert_eq!(a.as_str(), "SOLUSDT");
+        assert_eq!(a, "SolUsdt");
+    }
+
+    #[test]
+    fn test_interning_agrees_across_threads() {
+        let here = SymbolId::intern("XRPUSDT");
+        let there = std::thread::spawn(|| SymbolId::intern("xrpusdt"))
+            .join()
+            .unwrap();
+        assert_eq!(here, there);
+        assert_eq!(there.as_str(), "XRPUSDT");
+    }
+
+    #[test]
+    fn test_symbol_serializes_as_name() {
+        let id = SymbolId::intern("BTCUSDT");
+        assert_eq!(serde_json::to_string(&id).unwrap(), "\"BTCUSDT\"");
+        let back: SymbolId = serde_json::from_str("\"btcusdt\"").unwrap();
+        assert_eq!(back, id);
+    }
+}
@@ -1,5 +1,7 @@
 use once_cell::sync::Lazy;
-use std::time::Instant;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 
 /// Global monotonic start time for the application.
 /// Used to calculate relative timestamps for latency measurements.
@@ -9,3 +11,65 @@ pub static MONOTONIC_START: Lazy<Instant> = Lazy::new(Instant::now);
 pub fn now_nanos() -> u64 {
     MONOTONIC_START.elapsed().as_nanos() as u64
 }
+
+/// Source of `now_nanos()` timestamps, so time-dependent logic (cooldowns,
+/// throttles) can be driven by a `MockClock` in tests.
+pub trait Clock: Send + Sync {
+    fn now_nanos(&self) -> u64;
+}
+
+/// The production clock: the process-wide monotonic `now_nanos()`.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now_nanos(&self) -> u64 {
+        now_nanos()
+    }
+}
+
+/// Manually advanced clock for tests. Clones share the same time.
+#[derive(Debug, Clone, Default)]
+pub struct MockClock {
+    nanos: Arc<AtomicU64>,
+}
+
+impl MockClock {
+    pub fn new(start_nanos: u64) -> Self {
+        Self {
+            nanos: Arc::new(AtomicU64::new(start_nanos)),
+        }
+    }
+
+    pub fn advance(&self, by: Duration) {
+        self.nanos
+            .fetch_add(by.as_nanos() as u64, Ordering::Relaxed);
+    }
+
+    pub fn set(&self, nanos: u64) {
+        self.nanos.store(nanos, Ordering::Relaxed);
+    }
+}
+
+impl Clock for MockClock {
+    fn now_nanos(&self) -> u64 {
+        self.nanos.load(Ordering::Relaxed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mock_clock_clones_share_time() {
+        let clock = MockClock::new(5);
+        let shared = clock.clone();
+
+        clock.advance(Duration::from_secs(1));
+        assert_eq!(shared.now_nanos(), 1_000_000_005);
+
+        shared.set(42);
+        assert_eq!(clock.now_nanos(), 42);
+    }
+}
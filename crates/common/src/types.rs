@@ -1,5 +1,5 @@
+use crate::SymbolId;
 use serde::{Deserialize, Serialize};
-use smartstring::alias::String as SmartString;
 
 /// Side of the order (Buy or Sell)
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
@@ -9,36 +9,212 @@ pub enum Side {
     Sell,
 }
 
-/// Type of the order (Limit or Market)
+/// Type of the order (Limit, Market or StopMarket)
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum OrderType {
     Limit,
     Market,
+    /// Market order triggered once `stop_price` is touched.
+    StopMarket,
+}
+
+/// What a `MarketEvent` represents.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum EventKind {
+    /// An executed trade; `price`/`quantity` are the trade's.
+    #[default]
+    Trade,
+    /// A top-of-book update; `price` is the mid and `quantity` is 0.
+    BookTop { bid: f64, ask: f64 },
 }
 
 /// Represents a market event (e.g., a trade or quote update)
+///
+/// Migration note: `kind` was added so trades and book updates can share the
+/// same ring buffer. `price`/`quantity` keep their meaning for trades, and
+/// events serialized before `kind` existed deserialize as `EventKind::Trade`.
+/// Strategies that only understand trades should skip events where
+/// `!event.is_trade()`.
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct MarketEvent {
-    pub symbol: SmartString,
+    pub symbol: SymbolId,
     pub price: f64,
     pub quantity: f64,
     /// Raw Unix timestamp in milliseconds, as provided by the exchange.
     pub exchange_timestamp: i64,
     /// Local monotonic timestamp in nanoseconds, suitable for latency measurement.
     pub received_timestamp: u64,
+    /// True if the buyer was the maker, i.e. the trade was seller-initiated.
+    #[serde(default)]
+    pub is_buyer_maker: bool,
+    #[serde(default)]
+    pub kind: EventKind,
+}
+
+impl MarketEvent {
+    /// A trade print with zero timestamps; set other fields with struct-update syntax.
+    pub fn trade(symbol: impl Into<SymbolId>, price: f64, quantity: f64) -> Self {
+        Self {
+            symbol: symbol.into(),
+            price,
+            quantity,
+            exchange_timestamp: 0,
+            received_timestamp: 0,
+            is_buyer_maker: false,
+            kind: EventKind::Trade,
+        }
+    }
+
+    pub fn is_trade(&self) -> bool {
+        self.kind == EventKind::Trade
+    }
+
+    pub fn symbol_str(&self) -> &'static str {
+        self.symbol.as_str()
+    }
+}
+
+/// Top-N order book snapshot. Levels are `(price, quantity)`, best first.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct BookEvent {
+    pub symbol: SymbolId,
+    pub bids: Vec<(f64, f64)>,
+    pub asks: Vec<(f64, f64)>,
+    /// Raw Unix timestamp in milliseconds, as provided by the exchange.
+    pub ts: i64,
+}
+
+impl BookEvent {
+    /// Collapse to a `MarketEvent` carrying the top of book, or `None` if a side is empty.
+    pub fn to_market_event(&self, received_timestamp: u64) -> Option<MarketEvent> {
+        let (bid, _) = self.best_bid()?;
+        let (ask, _) = self.best_ask()?;
+        Some(MarketEvent {
+            symbol: self.symbol,
+            price: (bid + ask) / 2.0,
+            quantity: 0.0,
+            exchange_timestamp: self.ts,
+            received_timestamp,
+            is_buyer_maker: false,
+            kind: EventKind::BookTop { bid, ask },
+        })
+    }
+
+    pub fn best_bid(&self) -> Option<(f64, f64)> {
+        self.bids.first().copied()
+    }
+
+    pub fn best_ask(&self) -> Option<(f64, f64)> {
+        self.asks.first().copied()
+    }
 }
 
 /// Represents an instruction to execute a trade
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TradeInstruction {
-    pub symbol: SmartString,
+    pub symbol: SymbolId,
     pub side: Side,
     pub order_type: OrderType,
     pub price: f64,
     pub quantity: f64,
     pub timestamp: u64,
     pub dry_run: bool,
+    /// Trigger price, required for `OrderType::StopMarket`.
+    #[serde(default)]
+    pub stop_price: Option<f64>,
+    /// Closing order: the exchange rejects it if it would increase the position.
+    #[serde(default)]
+    pub reduce_only: bool,
+    /// Limit orders only: post-only (`GTX`), cancelled instead of taking liquidity.
+    #[serde(default)]
+    pub post_only: bool,
+    /// `newClientOrderId`, assigned by execution unless the strategy set one (to cancel
+    /// the order later). Identifies the order if the response is lost (e.g. on a
+    /// timeout), so it can be looked up instead of resent.
+    #[serde(default)]
+    pub client_order_id: Option<String>,
+    /// `received_timestamp` of the tick that triggered it (0 if not tick-driven),
+    /// stamped by the strategy loop for tick-to-order latency.
+    #[serde(default)]
+    pub tick_timestamp: u64,
+}
+
+/// A request from the strategy to execution, carried by the signal ring buffer.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum ExecSignal {
+    Place(TradeInstruction),
+    /// Cancel one resting order by the `client_order_id` it was placed with.
+    Cancel {
+        symbol: SymbolId,
+        client_order_id: String,
+    },
+    /// Cancel every open order on `symbol`.
+    CancelAll {
+        symbol: SymbolId,
+    },
+}
+
+impl TradeInstruction {
+    /// A plain market order: no stop, not reduce-only, no client id. Set the fields an
+    /// order actually varies with struct-update syntax.
+    pub fn market(
+        symbol: impl Into<SymbolId>,
+        side: Side,
+        price: f64,
+        quantity: f64,
+        timestamp: u64,
+    ) -> Self {
+        Self {
+            symbol: symbol.into(),
+            side,
+            order_type: OrderType::Market,
+            price,
+            quantity,
+            timestamp,
+            dry_run: false,
+            stop_price: None,
+            reduce_only: false,
+            post_only: false,
+            client_order_id: None,
+            tick_timestamp: 0,
+        }
+    }
+
+    /// A plain (GTC, not post-only) limit order at `price`.
+    pub fn limit(
+        symbol: impl Into<SymbolId>,
+        side: Side,
+        price: f64,
+        quantity: f64,
+        timestamp: u64,
+    ) -> Self {
+        Self {
+            order_type: OrderType::Limit,
+            ..Self::market(symbol, side, price, quantity, timestamp)
+        }
+    }
+
+    pub fn symbol_str(&self) -> &'static str {
+        self.symbol.as_str()
+    }
+}
+
+impl ExecSignal {
+    /// The instruction of a `Place`, `None` for cancels.
+    pub fn into_place(self) -> Option<TradeInstruction> {
+        match self {
+            ExecSignal::Place(instruction) => Some(instruction),
+            _ => None,
+        }
+    }
+}
+
+impl From<TradeInstruction> for ExecSignal {
+    fn from(instruction: TradeInstruction) -> Self {
+        ExecSignal::Place(instruction)
+    }
 }
 
 #[cfg(test)]
@@ -48,11 +224,10 @@ mod tests {
     #[test]
     fn test_market_event_serialization() {
         let event = MarketEvent {
-            symbol: SmartString::from("BTCUSDT"),
-            price: 50000.0,
-            quantity: 1.5,
             exchange_timestamp: 1630000000000,
             received_timestamp: 123456789,
+            is_buyer_maker: true,
+            ..MarketEvent::trade("BTCUSDT", 50000.0, 1.5)
         };
 
         let serialized = serde_json::to_string(&event).expect("Failed to serialize");
@@ -61,4 +236,47 @@ mod tests {
 
         assert_eq!(event, deserialized);
     }
+
+    #[test]
+    fn test_market_event_kind_defaults_to_trade() {
+        // Events recorded before `kind` existed
+        let raw = r#"{"symbol":"BTCUSDT","price":50000.0,"quantity":1.0,"exchange_timestamp":1,"received_timestamp":2}"#;
+        let event: MarketEvent = serde_json::from_str(raw).expect("Failed to deserialize");
+        assert_eq!(event.kind, EventKind::Trade);
+        assert!(event.is_trade());
+    }
+
+    #[test]
+    fn test_book_top_round_trip() {
+        let book = BookEvent {
+            symbol: SymbolId::from("BTCUSDT"),
+            bids: vec![(99.0, 1.0)],
+            asks: vec![(101.0, 2.0)],
+            ts: 1700000000000,
+        };
+        let event = book.to_market_event(42).expect("Expected top of book");
+        assert_eq!(event.price, 100.0);
+        assert!(!event.is_trade());
+
+        let serialized = serde_json::to_string(&event).expect("Failed to serialize");
+        let deserialized: MarketEvent =
+            serde_json::from_str(&serialized).expect("Failed to deserialize");
+        assert_eq!(event, deserialized);
+    }
+
+    #[test]
+    fn test_order_type_serialization() {
+        assert_eq!(
+            serde_json::to_string(&OrderType::Market).unwrap(),
+            "\"MARKET\""
+        );
+        assert_eq!(
+            serde_json::to_string(&OrderType::Limit).unwrap(),
+            "\"LIMIT\""
+        );
+        assert_eq!(
+            serde_json::to_string(&OrderType::StopMarket).unwrap(),
+            "\"STOP_MARKET\""
+        );
+    }
 }
@@ -0,0 +1,12 @@
+/// Raw-stream URL `<base>/ws[/<path>]`. Accepts the base with or without the
+/// trailing `/ws` (as in `config.network.ws_url`), so market and user-data streams
+/// can share one setting.
+pub fn stream_url(ws_base_url: &str, path: &str) -> String {
+    let base = ws_base_url.trim_end_matches('/');
+    let base = base.strip_suffix("/ws").unwrap_or(base);
+    if path.is_empty() {
+        format!("{}/ws", base)
+    } else {
+        format!("{}/ws/{}", base, path)
+    }
+}
@@ -1,20 +1,116 @@
+use crate::filters::SymbolFilters;
 use crate::signer::BinanceSigner;
 use common::{EngineError, OrderType, TradeInstruction};
 use governor::{DefaultDirectRateLimiter, Quota};
 use nonzero_ext::nonzero;
 use reqwest::Client;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, AtomicI64, AtomicU64, Ordering};
+use std::sync::RwLock;
 use std::time::Duration;
 
+/// Exchange environment the client is pointed at.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Network {
+    Testnet,
+    Mainnet,
+}
+
+impl Network {
+    /// Classify a REST base URL. Anything that isn't a known testnet host is treated as Mainnet.
+    pub fn from_url(base_url: &str) -> Self {
+        if base_url.to_ascii_lowercase().contains("testnet") {
+            Network::Testnet
+        } else {
+            Network::Mainnet
+        }
+    }
+}
+
+/// True if the exchange rejected a post-only order because it would have taken
+/// liquidity (-5022). Expected in post-only mode, not a failure.
+pub fn is_post_only_reject(error: &EngineError) -> bool {
+    matches!(error, EngineError::ExchangeError(text) if text.contains("-5022"))
+}
+
+/// Map a failed response body to an error, tagging credential failures `AUTH_ERROR`:
+/// rejected key (-2014, -2015) or bad signature, i.e. wrong secret (-1022).
+fn exchange_error(text: String) -> EngineError {
+    if text.contains("-2014")
+        || text.contains("-2015")
+        || text.contains("-1022")
+        || text.contains("API-key format invalid")
+    {
+        return EngineError::ExchangeError(format!("AUTH_ERROR: {}", text));
+    }
+    EngineError::ExchangeError(text)
+}
+
+/// Transport error without the request URL: signed GETs carry the signature in it.
+fn request_error(e: reqwest::Error) -> EngineError {
+    EngineError::ExchangeError(e.without_url().to_string())
+}
+
+/// True for errors tagged by `exchange_error` as a credential failure.
+pub fn is_auth_error(error: &EngineError) -> bool {
+    matches!(error, EngineError::ExchangeError(text) if text.starts_with("AUTH_ERROR"))
+}
+
+/// True if `place_order` knows the exchange accepted the order but not what became of
+/// it (fills, order id). The position must be re-read from the exchange.
+pub fn is_order_state_unknown(error: &EngineError) -> bool {
+    matches!(error, EngineError::ExchangeError(text) if text.starts_with("ORDER_STATE_UNKNOWN"))
+}
+
+/// Last timestamp handed out by `new_client_order_id`, keeps ids strictly increasing.
+static LAST_CLIENT_ORDER_TS: AtomicU64 = AtomicU64::new(0);
+
+/// Generate a unique `newClientOrderId`: `<prefix>-<unix nanos>`.
+/// The prefix (e.g. the strategy name) is reduced to the characters Binance accepts
+/// and capped so the id stays within the 36 character limit.
+pub fn new_client_order_id(prefix: &str) -> String {
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_nanos() as u64;
+    // Two ids within the same nanosecond (or after a clock step back) still differ
+    let next = |last: u64| now.max(last + 1);
+    let last = LAST_CLIENT_ORDER_TS
+        .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |last| {
+            Some(next(last))
+        })
+        .unwrap_or_else(|last| last);
+    let ts = next(last);
+    let prefix: String = prefix
+        .chars()
+        .filter(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.' | ':' | '/'))
+        .take(12)
+        .collect();
+    format!("{}-{}", prefix, ts)
+}
+
+/// Default `recvWindow` (ms) for signed requests.
+pub const DEFAULT_RECV_WINDOW_MS: u64 = 5000;
+
 pub struct ExecutionClient {
     http_client: Client,
     signer: BinanceSigner,
     base_url: String,
+    network: Network,
+    /// Set once the first live Mainnet order has been sent (for the one-time warning).
+    mainnet_warned: AtomicBool,
+    /// Exchange clock minus local clock (ms), applied to signed request timestamps.
+    time_offset_ms: AtomicI64,
+    /// `recvWindow` (ms) sent with every signed request.
+    recv_window_ms: u64,
+    /// Precision filters per symbol, from `load_symbol_filters`.
+    symbol_filters: RwLock<HashMap<String, SymbolFilters>>,
     // Rate Limiting: 10 requests per second, burst 10
     rate_limiter: DefaultDirectRateLimiter,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Serialize)]
 pub struct PositionRisk {
     pub symbol: String,
     #[serde(rename = "positionAmt")]
@@ -25,6 +121,174 @@ pub struct PositionRisk {
     pub mark_price: String,
 }
 
+/// A resting order from `GET /fapi/v1/openOrders`.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct OpenOrder {
+    pub symbol: String,
+    #[serde(rename = "orderId")]
+    pub order_id: i64,
+    pub side: String,
+    #[serde(rename = "type")]
+    pub order_type: String,
+    pub price: String,
+    #[serde(rename = "origQty")]
+    pub orig_qty: String,
+    pub status: String,
+}
+
+/// A single fill reported in an order response.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct OrderFill {
+    pub price: String,
+    pub qty: String,
+    pub commission: String,
+    #[serde(rename = "commissionAsset")]
+    pub commission_asset: String,
+}
+
+/// Parsed response of `POST /fapi/v1/order`.
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct OrderResponse {
+    #[serde(rename = "orderId", default)]
+    pub order_id: Option<i64>,
+    #[serde(rename = "clientOrderId", default)]
+    pub client_order_id: Option<String>,
+    #[serde(default)]
+    pub status: Option<String>,
+    #[serde(rename = "avgPrice", default)]
+    pub avg_price: Option<String>,
+    #[serde(rename = "executedQty", default)]
+    pub executed_qty: Option<String>,
+    #[serde(default)]
+    pub fills: Vec<OrderFill>,
+    /// Raw response body, kept for persistence/debugging.
+    #[serde(skip)]
+    pub raw: String,
+}
+
+impl OrderResponse {
+    fn dry_run() -> Self {
+        Self {
+            raw: "DRY_RUN_SUCCESS".to_string(),
+            ..Default::default()
+        }
+    }
+
+    /// Total commission across fills and its asset, if the exchange reported any.
+    pub fn commission(&self) -> Option<(f64, String)> {
+        let first = self.fills.first()?;
+        let total = self
+            .fills
+            .iter()
+            .filter_map(|f| f.commission.parse::<f64>().ok())
+            .sum();
+        Some((total, first.commission_asset.clone()))
+    }
+}
+
+/// One candle from `GET /fapi/v1/klines`.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct Kline {
+    /// Candle open time, unix ms.
+    pub open_time: u64,
+    pub open: f64,
+    pub high: f64,
+    pub low: f64,
+    pub close: f64,
+    pub volume: f64,
+}
+
+impl Kline {
+    /// Parse the klines response: an array of
+    /// `[openTime, "open", "high", "low", "close", "volume", closeTime, ...]` arrays.
+    pub fn parse_list(text: &str) -> Result<Vec<Kline>, EngineError> {
+        let rows: Vec<Vec<serde_json::Value>> = serde_json::from_str(text)
+            .map_err(|e| EngineError::ParseError(format!("Failed to parse klines: {}", e)))?;
+
+        rows.iter()
+            .map(|row| {
+                let field = |i: usize| -> Result<f64, EngineError> {
+                    row.get(i)
+                        .and_then(|v| v.as_str())
+                        .and_then(|s| s.parse().ok())
+                        .ok_or_else(|| {
+                            EngineError::ParseError(format!("Invalid kline field {}: {:?}", i, row))
+                        })
+                };
+                Ok(Kline {
+                    open_time: row.first().and_then(|v| v.as_u64()).ok_or_else(|| {
+                        EngineError::ParseError(format!("Invalid kline open time: {:?}", row))
+                    })?,
+                    open: field(1)?,
+                    high: field(2)?,
+                    low: field(3)?,
+                    close: field(4)?,
+                    volume: field(5)?,
+                })
+            })
+            .collect()
+    }
+}
+
+/// One aggregated trade from `GET /fapi/v1/aggTrades`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AggTrade {
+    pub price: f64,
+    pub quantity: f64,
+    /// Trade time, unix ms.
+    pub time: u64,
+    pub is_buyer_maker: bool,
+}
+
+#[derive(Debug, Deserialize)]
+struct RawAggTrade {
+    #[serde(rename = "p")]
+    price: String,
+    #[serde(rename = "q")]
+    quantity: String,
+    #[serde(rename = "T")]
+    time: u64,
+    #[serde(rename = "m")]
+    is_buyer_maker: bool,
+}
+
+impl AggTrade {
+    /// Parse the aggTrades response: an array of
+    /// `{"a": id, "p": "price", "q": "qty", "f": .., "l": .., "T": timeMs, "m": bool}`.
+    pub fn parse_list(text: &str) -> Result<Vec<AggTrade>, EngineError> {
+        let rows: Vec<RawAggTrade> = serde_json::from_str(text)
+            .map_err(|e| EngineError::ParseError(format!("Failed to parse aggTrades: {}", e)))?;
+
+        rows.into_iter()
+            .map(|row| {
+                let parse = |s: &str| -> Result<f64, EngineError> {
+                    s.parse().map_err(|_| {
+                        EngineError::ParseError(format!("Invalid aggTrade field: {:?}", s))
+                    })
+                };
+                Ok(AggTrade {
+                    price: parse(&row.price)?,
+                    quantity: parse(&row.quantity)?,
+                    time: row.time,
+                    is_buyer_maker: row.is_buyer_maker,
+                })
+            })
+            .collect()
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct ServerTime {
+    #[serde(rename = "serverTime")]
+    server_time: i64,
+}
+
+#[derive(Debug, Deserialize)]
+struct ListenKey {
+    #[serde(rename = "listenKey")]
+    listen_key: String,
+}
+
 #[derive(Debug, Deserialize)]
 pub struct AccountBalance {
     pub asset: String,
@@ -34,7 +298,13 @@ pub struct AccountBalance {
 }
 
 impl ExecutionClient {
-    pub fn new(api_key: String, secret_key: String, base_url: String) -> Self {
+    /// `recv_window_ms` falls back to [`DEFAULT_RECV_WINDOW_MS`] when `None`.
+    pub fn new(
+        api_key: String,
+        secret_key: String,
+        base_url: String,
+        recv_window_ms: Option<u64>,
+    ) -> Self {
         let http_client = Client::builder()
             .timeout(Duration::from_secs(10))
             .build()
@@ -48,17 +318,307 @@ impl ExecutionClient {
         Self {
             http_client,
             signer: BinanceSigner::new(api_key, secret_key),
+            network: Network::from_url(&base_url),
             base_url,
+            mainnet_warned: AtomicBool::new(false),
+            time_offset_ms: AtomicI64::new(0),
+            recv_window_ms: recv_window_ms.unwrap_or(DEFAULT_RECV_WINDOW_MS),
+            symbol_filters: RwLock::new(HashMap::new()),
             rate_limiter,
         }
     }
 
+    /// Replace the default HMAC signer (e.g. for an Ed25519 key).
+    pub fn with_signer(mut self, signer: BinanceSigner) -> Self {
+        self.signer = signer;
+        self
+    }
+
+    /// Override the per-request HTTP timeout (default 10s).
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.http_client = Client::builder()
+            .timeout(timeout)
+            .build()
+            .expect("Failed to build HTTP client");
+        self
+    }
+
+    pub fn network(&self) -> Network {
+        self.network
+    }
+
+    pub fn is_mainnet(&self) -> bool {
+        self.network == Network::Mainnet
+    }
+
+    /// Local wall-clock time adjusted by the last measured exchange offset.
+    fn timestamp_ms(&self) -> i64 {
+        chrono::Utc::now().timestamp_millis() + self.time_offset_ms.load(Ordering::Relaxed)
+    }
+
+    pub fn time_offset_ms(&self) -> i64 {
+        self.time_offset_ms.load(Ordering::Relaxed)
+    }
+
+    /// Offset between the exchange clock and the midpoint of the local request window.
+    fn compute_offset(server_time: i64, sent_at: i64, received_at: i64) -> i64 {
+        server_time - (sent_at + received_at) / 2
+    }
+
+    /// Measure the exchange clock via `/fapi/v1/time` and store the offset used for
+    /// every signed request's `timestamp`. Avoids -1021 rejections on skewed clocks.
+    pub async fn sync_time(&self) -> Result<i64, EngineError> {
+        self.await_rate_limit().await;
+
+        let url = format!("{}/fapi/v1/time", self.base_url);
+        let sent_at = chrono::Utc::now().timestamp_millis();
+        let resp = self
+            .http_client
+            .get(&url)
+            .send()
+            .await
+            .map_err(request_error)?;
+        let received_at = chrono::Utc::now().timestamp_millis();
+
+        let text = resp.text().await.map_err(request_error)?;
+        let server: ServerTime = serde_json::from_str(&text).map_err(|e| {
+            EngineError::ExchangeError(format!("Failed to parse server time: {}", e))
+        })?;
+
+        let offset = Self::compute_offset(server.server_time, sent_at, received_at);
+        self.time_offset_ms.store(offset, Ordering::Relaxed);
+        Ok(offset)
+    }
+
+    /// Fetch and cache `symbol`'s tick/step size and minQty from `GET /fapi/v1/exchangeInfo`.
+    /// Once cached, `place_order` rounds orders for that symbol to the exchange's precision.
+    pub async fn load_symbol_filters(&self, symbol: &str) -> Result<SymbolFilters, EngineError> {
+        self.await_rate_limit().await;
+
+        let url = format!("{}/fapi/v1/exchangeInfo", self.base_url);
+        let resp = self
+            .http_client
+            .get(&url)
+            .send()
+            .await
+            .map_err(request_error)?;
+
+        let status = resp.status();
+        let text = resp.text().await.map_err(request_error)?;
+        if !status.is_success() {
+            return Err(EngineError::ExchangeError(text));
+        }
+
+        let filters = SymbolFilters::from_exchange_info(&text, symbol)?;
+        self.symbol_filters
+            .write()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(symbol.to_uppercase(), filters);
+        Ok(filters)
+    }
+
+    /// Recent candles for `symbol` from `GET /fapi/v1/klines` (unsigned), oldest first.
+    /// `interval` is a Binance interval such as `1m` or `1h`; `limit` is capped at 1500.
+    pub async fn get_klines(
+        &self,
+        symbol: &str,
+        interval: &str,
+        limit: u16,
+    ) -> Result<Vec<Kline>, EngineError> {
+        self.await_rate_limit().await;
+
+        let url = format!(
+            "{}/fapi/v1/klines?symbol={}&interval={}&limit={}",
+            self.base_url,
+            symbol.to_uppercase(),
+            interval,
+            limit.min(1500)
+        );
+        let resp = self
+            .http_client
+            .get(&url)
+            .send()
+            .await
+            .map_err(request_error)?;
+
+        let status = resp.status();
+        let text = resp.text().await.map_err(request_error)?;
+        if !status.is_success() {
+            return Err(exchange_error(text));
+        }
+
+        Kline::parse_list(&text)
+    }
+
+    /// Most recent aggregated trades for `symbol` from `GET /fapi/v1/aggTrades` (unsigned),
+    /// oldest first. `limit` is capped at 1000.
+    pub async fn get_agg_trades(
+        &self,
+        symbol: &str,
+        limit: u16,
+    ) -> Result<Vec<AggTrade>, EngineError> {
+        self.await_rate_limit().await;
+
+        let url = format!(
+            "{}/fapi/v1/aggTrades?symbol={}&limit={}",
+            self.base_url,
+            symbol.to_uppercase(),
+            limit.min(1000)
+        );
+        let resp = self
+            .http_client
+            .get(&url)
+            .send()
+            .await
+            .map_err(request_error)?;
+
+        let status = resp.status();
+        let text = resp.text().await.map_err(request_error)?;
+        if !status.is_success() {
+            return Err(exchange_error(text));
+        }
+
+        AggTrade::parse_list(&text)
+    }
+
+    /// Cached precision filters for `symbol`, if loaded.
+    pub fn symbol_filters(&self, symbol: &str) -> Option<SymbolFilters> {
+        self.symbol_filters
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .get(&symbol.to_uppercase())
+            .copied()
+    }
+
+    /// Obtain a user-data stream key via `POST /fapi/v1/listenKey`.
+    /// Calling it again while a key is active returns the same key and extends it.
+    pub async fn create_listen_key(&self) -> Result<String, EngineError> {
+        let text = self.listen_key_request(reqwest::Method::POST).await?;
+        let key: ListenKey = serde_json::from_str(&text)
+            .map_err(|e| EngineError::ExchangeError(format!("Failed to parse listenKey: {}", e)))?;
+        Ok(key.listen_key)
+    }
+
+    /// Extend the active listenKey by 60 minutes (`PUT /fapi/v1/listenKey`).
+    /// Binance expires keys that are not kept alive; an error here usually means the key
+    /// already expired and a new one must be created.
+    pub async fn keepalive_listen_key(&self) -> Result<(), EngineError> {
+        self.listen_key_request(reqwest::Method::PUT).await?;
+        Ok(())
+    }
+
+    /// listenKey endpoints are API-key authenticated only (no signature).
+    async fn listen_key_request(&self, method: reqwest::Method) -> Result<String, EngineError> {
+        self.await_rate_limit().await;
+
+        let url = format!("{}/fapi/v1/listenKey", self.base_url);
+        let resp = self
+            .http_client
+            .request(method, &url)
+            .headers(self.signer.get_headers())
+            .send()
+            .await
+            .map_err(request_error)?;
+
+        let status = resp.status();
+        let text = resp
+            .text()
+            .await
+            .unwrap_or_else(|_| format!("Status: {}", status));
+        if status.is_success() {
+            Ok(text)
+        } else {
+            Err(EngineError::ExchangeError(text))
+        }
+    }
+
+    /// Set the initial leverage for `symbol` (`POST /fapi/v1/leverage`, 1-125).
+    pub async fn set_leverage(&self, symbol: &str, leverage: u8) -> Result<(), EngineError> {
+        if !(1..=125).contains(&leverage) {
+            return Err(EngineError::ParseError(format!(
+                "Leverage must be 1-125, got {}",
+                leverage
+            )));
+        }
+        let params = format!("symbol={}&leverage={}", symbol.to_uppercase(), leverage);
+        self.account_setting_request("/fapi/v1/leverage", &params)
+            .await?;
+        Ok(())
+    }
+
+    /// Switch `symbol` between ISOLATED and CROSSED margin (`POST /fapi/v1/marginType`).
+    /// Already being in the requested mode (-4046) counts as success.
+    pub async fn set_margin_type(&self, symbol: &str, isolated: bool) -> Result<(), EngineError> {
+        let params = format!(
+            "symbol={}&marginType={}",
+            symbol.to_uppercase(),
+            if isolated { "ISOLATED" } else { "CROSSED" }
+        );
+        match self
+            .account_setting_request("/fapi/v1/marginType", &params)
+            .await
+        {
+            Ok(_) => Ok(()),
+            Err(EngineError::ExchangeError(text))
+                if text.contains("-4046") || text.contains("No need to change margin type") =>
+            {
+                Ok(())
+            }
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Signed POST of per-symbol account settings; returns the response body.
+    async fn account_setting_request(
+        &self,
+        path: &str,
+        params: &str,
+    ) -> Result<String, EngineError> {
+        self.await_rate_limit().await;
+
+        let query = format!(
+            "{}&recvWindow={}&timestamp={}",
+            params,
+            self.recv_window_ms,
+            self.timestamp_ms()
+        );
+        let signature = self.signer.sign(&query);
+        let signed_body = format!("{}&signature={}", query, signature);
+
+        let url = format!("{}{}", self.base_url, path);
+        let resp = self
+            .http_client
+            .post(&url)
+            .headers(self.signer.get_headers())
+            .body(signed_body)
+            .send()
+            .await
+            .map_err(request_error)?;
+
+        let status = resp.status();
+        let text = resp
+            .text()
+            .await
+            .unwrap_or_else(|_| format!("Status: {}", status));
+        if status.is_success() {
+            return Ok(text);
+        }
+
+        Err(exchange_error(text))
+    }
+
+    /// Signed round trip to check the API key and secret before trading, since a wrong
+    /// secret still produces well-formed signatures. Needs the clock synced first.
+    pub async fn verify_credentials(&self) -> Result<(), EngineError> {
+        self.get_account_balance().await.map(|_| ())
+    }
+
     /// Fetch account balance.
     pub async fn get_account_balance(&self) -> Result<Vec<AccountBalance>, EngineError> {
         self.await_rate_limit().await;
 
-        let timestamp = chrono::Utc::now().timestamp_millis();
-        let query = format!("recvWindow=5000&timestamp={}", timestamp);
+        let timestamp = self.timestamp_ms();
+        let query = format!("recvWindow={}&timestamp={}", self.recv_window_ms, timestamp);
         let signature = self.signer.sign(&query);
         let signed_query = format!("{}&signature={}", query, signature);
 
@@ -71,13 +631,10 @@ impl ExecutionClient {
             .headers(headers)
             .send()
             .await
-            .map_err(|e| EngineError::ExchangeError(e.to_string()))?;
+            .map_err(request_error)?;
 
         if resp.status().is_success() {
-            let text = resp
-                .text()
-                .await
-                .map_err(|e| EngineError::ExchangeError(e.to_string()))?;
+            let text = resp.text().await.map_err(request_error)?;
             let balances: Vec<AccountBalance> = serde_json::from_str(&text).map_err(|e| {
                 EngineError::ExchangeError(format!("Failed to parse balances: {}", e))
             })?;
@@ -89,15 +646,7 @@ impl ExecutionClient {
                 .await
                 .unwrap_or_else(|_| format!("Status: {}", status));
 
-            // Check for Auth errors
-            if text.contains("-2014")
-                || text.contains("-2015")
-                || text.contains("API-key format invalid")
-            {
-                return Err(EngineError::ExchangeError(format!("AUTH_ERROR: {}", text)));
-            }
-
-            Err(EngineError::ExchangeError(text))
+            Err(exchange_error(text))
         }
     }
 
@@ -114,57 +663,216 @@ impl ExecutionClient {
         self.rate_limiter.until_ready().await;
     }
 
-    /// Place an order. If instruction.dry_run == true, return Ok("DRY_RUN_SUCCESS").
-    pub async fn place_order(&self, instruction: &TradeInstruction) -> Result<String, EngineError> {
-        if instruction.dry_run {
-            return Ok("DRY_RUN_SUCCESS".to_string());
+    /// Binance wire name for an order type.
+    fn order_type_str(order_type: OrderType) -> &'static str {
+        match order_type {
+            OrderType::Limit => "LIMIT",
+            OrderType::Market => "MARKET",
+            OrderType::StopMarket => "STOP_MARKET",
         }
+    }
 
-        self.await_rate_limit().await;
-
-        // 1. Build Canonical Query String
-        // Order: symbol, side, type, quantity, timeInForce (if Limit), price (if Limit), recvWindow, timestamp
+    /// Build the (unsigned) order query string.
+    /// Order: symbol, side, type, quantity, timeInForce (GTX if post-only) + price (if Limit),
+    /// stopPrice (if StopMarket), reduceOnly (if set), newClientOrderId (if set),
+    /// recvWindow, timestamp
+    fn order_query(
+        instruction: &TradeInstruction,
+        recv_window_ms: u64,
+        timestamp: i64,
+    ) -> Result<String, EngineError> {
         let mut query = format!(
             "symbol={}&side={}&type={}&quantity={}",
-            instruction.symbol.to_uppercase(),
+            instruction.symbol_str(),
             format!("{:?}", instruction.side).to_uppercase(),
-            format!("{:?}", instruction.order_type).to_uppercase(),
+            Self::order_type_str(instruction.order_type),
             Self::fmt_decimal(instruction.quantity)
         );
 
-        if instruction.order_type == OrderType::Limit {
-            query.push_str("&timeInForce=GTC");
-            query.push_str(&format!("&price={}", Self::fmt_decimal(instruction.price)));
+        match instruction.order_type {
+            OrderType::Limit => {
+                query.push_str(if instruction.post_only {
+                    "&timeInForce=GTX"
+                } else {
+                    "&timeInForce=GTC"
+                });
+                query.push_str(&format!("&price={}", Self::fmt_decimal(instruction.price)));
+            }
+            OrderType::StopMarket => {
+                let stop_price = instruction.stop_price.ok_or_else(|| {
+                    EngineError::ParseError("STOP_MARKET order requires stop_price".to_string())
+                })?;
+                query.push_str(&format!("&stopPrice={}", Self::fmt_decimal(stop_price)));
+            }
+            OrderType::Market => {}
+        }
+
+        if instruction.reduce_only {
+            query.push_str("&reduceOnly=true");
+        }
+        if let Some(client_order_id) = &instruction.client_order_id {
+            query.push_str(&format!("&newClientOrderId={}", client_order_id));
         }
 
         // Add recvWindow and timestamp
-        let timestamp = chrono::Utc::now().timestamp_millis();
-        query.push_str(&format!("&recvWindow=5000&timestamp={}", timestamp));
+        query.push_str(&format!(
+            "&recvWindow={}&timestamp={}",
+            recv_window_ms, timestamp
+        ));
+        Ok(query)
+    }
+
+    /// Place an order. If instruction.dry_run == true, returns a response with raw "DRY_RUN_SUCCESS".
+    pub async fn place_order(
+        &self,
+        instruction: &TradeInstruction,
+    ) -> Result<OrderResponse, EngineError> {
+        if instruction.dry_run {
+            return Ok(OrderResponse::dry_run());
+        }
+
+        // Conform to tickSize/stepSize; sub-minQty orders never reach the exchange
+        let rounded = match self.symbol_filters(instruction.symbol_str()) {
+            Some(filters) => Some(filters.apply(instruction)?),
+            None => None,
+        };
+        let instruction = rounded.as_ref().unwrap_or(instruction);
 
-        // 2. Sign
+        if self.is_mainnet() && !self.mainnet_warned.swap(true, Ordering::Relaxed) {
+            tracing::warn!(
+                "!!! LIVE MAINNET ORDER !!! Sending real order to {} - real funds at risk",
+                self.base_url
+            );
+        }
+
+        // Validate upfront (e.g. STOP_MARKET without stop_price)
+        Self::order_query(instruction, self.recv_window_ms, 0)?;
+
+        // 1-3. Build, sign and send. A timed-out order may still have landed: resend it
+        // once under the same clientOrderId so the exchange dedupes instead of filling twice.
+        let mut retried = false;
+        let resp = match self.send_order(instruction).await {
+            Err(e) if e.is_timeout() && instruction.client_order_id.is_some() => {
+                tracing::warn!(
+                    "Order {:?} timed out, retrying with the same clientOrderId",
+                    instruction.client_order_id
+                );
+                retried = true;
+                self.send_order(instruction).await
+            }
+            other => other,
+        }
+        .map_err(request_error)?;
+
+        // 4. Handle Response
+        if resp.status().is_success() {
+            let text = resp.text().await.map_err(request_error)?;
+            let mut order: OrderResponse = serde_json::from_str(&text).map_err(|e| {
+                EngineError::ExchangeError(format!("Failed to parse order response: {}", e))
+            })?;
+            order.raw = text;
+            Ok(order)
+        } else {
+            let status = resp.status();
+            let text = resp
+                .text()
+                .await
+                .unwrap_or_else(|_| format!("Status: {}", status));
+
+            // Duplicate clientOrderId on the retry: the timed-out attempt went through.
+            // Look it up for the real orderId and fills; if that fails too, say so rather
+            // than report an order whose outcome is unknown as a success.
+            if retried && Self::is_duplicate_client_order_id(&text) {
+                let client_order_id = instruction.client_order_id.as_deref().unwrap_or_default();
+                tracing::warn!(
+                    "Order {} already accepted before the timeout",
+                    client_order_id
+                );
+                return self
+                    .get_order_by_client_id(instruction.symbol_str(), client_order_id)
+                    .await
+                    .map_err(|e| {
+                        EngineError::ExchangeError(format!(
+                            "ORDER_STATE_UNKNOWN: order {} accepted, lookup failed: {}",
+                            client_order_id, e
+                        ))
+                    });
+            }
+
+            Err(exchange_error(text))
+        }
+    }
+
+    /// Sign and send one `POST /fapi/v1/order` attempt with a fresh timestamp.
+    async fn send_order(
+        &self,
+        instruction: &TradeInstruction,
+    ) -> Result<reqwest::Response, reqwest::Error> {
+        self.await_rate_limit().await;
+
+        let timestamp = self.timestamp_ms();
+        let query = Self::order_query(instruction, self.recv_window_ms, timestamp)
+            .expect("Order already validated");
         let signature = self.signer.sign(&query);
         let signed_body = format!("{}&signature={}", query, signature);
 
-        // 3. Send Request
         let url = format!("{}/fapi/v1/order", self.base_url);
+        self.http_client
+            .post(&url)
+            .headers(self.signer.get_headers())
+            .body(signed_body)
+            .send()
+            .await
+    }
+
+    /// -4116: an order with this clientOrderId already exists.
+    fn is_duplicate_client_order_id(text: &str) -> bool {
+        text.contains("-4116") || text.contains("ClientOrderId is duplicated")
+    }
+
+    /// Build the (unsigned) query string for `positionRisk` / `openOrders`.
+    fn positions_query(symbol: Option<&str>, recv_window_ms: u64, timestamp: i64) -> String {
+        let mut query = String::new();
+        if let Some(symbol) = symbol {
+            query.push_str(&format!("symbol={}&", symbol.to_uppercase()));
+        }
+        query.push_str(&format!(
+            "recvWindow={}&timestamp={}",
+            recv_window_ms, timestamp
+        ));
+        query
+    }
+
+    /// Fetch current position risk (positions).
+    /// When `symbol` is set, the exchange only returns that symbol's position.
+    pub async fn sync_positions(
+        &self,
+        symbol: Option<&str>,
+    ) -> Result<Vec<PositionRisk>, EngineError> {
+        self.await_rate_limit().await;
+
+        let timestamp = self.timestamp_ms();
+        let query = Self::positions_query(symbol, self.recv_window_ms, timestamp);
+        let signature = self.signer.sign(&query);
+        let signed_query = format!("{}&signature={}", query, signature);
+
+        let url = format!("{}/fapi/v2/positionRisk?{}", self.base_url, signed_query);
         let headers = self.signer.get_headers();
 
         let resp = self
             .http_client
-            .post(&url)
+            .get(&url)
             .headers(headers)
-            .body(signed_body)
             .send()
             .await
-            .map_err(|e| EngineError::ExchangeError(e.to_string()))?;
+            .map_err(request_error)?;
 
-        // 4. Handle Response
         if resp.status().is_success() {
-            let text = resp
-                .text()
-                .await
-                .map_err(|e| EngineError::ExchangeError(e.to_string()))?;
-            Ok(text)
+            let text = resp.text().await.map_err(request_error)?;
+            let positions: Vec<PositionRisk> = serde_json::from_str(&text).map_err(|e| {
+                EngineError::ExchangeError(format!("Failed to parse positions: {}", e))
+            })?;
+            Ok(positions)
         } else {
             let status = resp.status();
             let text = resp
@@ -172,28 +880,23 @@ impl ExecutionClient {
                 .await
                 .unwrap_or_else(|_| format!("Status: {}", status));
 
-            // Check for Auth errors (-2014, -2015, etc.)
-            if text.contains("-2014")
-                || text.contains("-2015")
-                || text.contains("API-key format invalid")
-            {
-                return Err(EngineError::ExchangeError(format!("AUTH_ERROR: {}", text)));
-            }
-
-            Err(EngineError::ExchangeError(text))
+            Err(exchange_error(text))
         }
     }
 
-    /// Fetch current position risk (positions).
-    pub async fn sync_positions(&self) -> Result<Vec<PositionRisk>, EngineError> {
+    /// Fetch resting orders, for one symbol or (when `None`) all symbols.
+    pub async fn get_open_orders(
+        &self,
+        symbol: Option<&str>,
+    ) -> Result<Vec<OpenOrder>, EngineError> {
         self.await_rate_limit().await;
 
-        let timestamp = chrono::Utc::now().timestamp_millis();
-        let query = format!("recvWindow=5000&timestamp={}", timestamp);
+        let timestamp = self.timestamp_ms();
+        let query = Self::positions_query(symbol, self.recv_window_ms, timestamp);
         let signature = self.signer.sign(&query);
         let signed_query = format!("{}&signature={}", query, signature);
 
-        let url = format!("{}/fapi/v2/positionRisk?{}", self.base_url, signed_query);
+        let url = format!("{}/fapi/v1/openOrders?{}", self.base_url, signed_query);
         let headers = self.signer.get_headers();
 
         let resp = self
@@ -202,17 +905,66 @@ impl ExecutionClient {
             .headers(headers)
             .send()
             .await
-            .map_err(|e| EngineError::ExchangeError(e.to_string()))?;
+            .map_err(request_error)?;
 
         if resp.status().is_success() {
+            let text = resp.text().await.map_err(request_error)?;
+            let orders: Vec<OpenOrder> = serde_json::from_str(&text).map_err(|e| {
+                EngineError::ExchangeError(format!("Failed to parse open orders: {}", e))
+            })?;
+            Ok(orders)
+        } else {
+            let status = resp.status();
             let text = resp
                 .text()
                 .await
-                .map_err(|e| EngineError::ExchangeError(e.to_string()))?;
-            let positions: Vec<PositionRisk> = serde_json::from_str(&text).map_err(|e| {
-                EngineError::ExchangeError(format!("Failed to parse positions: {}", e))
-            })?;
-            Ok(positions)
+                .unwrap_or_else(|_| format!("Status: {}", status));
+
+            Err(exchange_error(text))
+        }
+    }
+
+    /// Build the signed query string addressing a single order (query or cancel),
+    /// identified by `order_param` (`orderId=<id>` or `origClientOrderId=<id>`).
+    fn signed_order_query(&self, symbol: &str, order_param: &str) -> String {
+        let query = format!(
+            "symbol={}&{}&recvWindow={}&timestamp={}",
+            symbol.to_uppercase(),
+            order_param,
+            self.recv_window_ms,
+            self.timestamp_ms()
+        );
+        let signature = self.signer.sign(&query);
+        format!("{}&signature={}", query, signature)
+    }
+
+    /// Look up an order (any status) by the `newClientOrderId` it was placed with.
+    pub async fn get_order_by_client_id(
+        &self,
+        symbol: &str,
+        client_order_id: &str,
+    ) -> Result<OrderResponse, EngineError> {
+        self.await_rate_limit().await;
+
+        let signed_query =
+            self.signed_order_query(symbol, &format!("origClientOrderId={}", client_order_id));
+        let url = format!("{}/fapi/v1/order?{}", self.base_url, signed_query);
+        let headers = self.signer.get_headers();
+
+        let resp = self
+            .http_client
+            .get(&url)
+            .headers(headers)
+            .send()
+            .await
+            .map_err(request_error)?;
+
+        if resp.status().is_success() {
+            let text = resp.text().await.map_err(request_error)?;
+            let mut order: OrderResponse = serde_json::from_str(&text)
+                .map_err(|e| EngineError::ExchangeError(format!("Failed to parse order: {}", e)))?;
+            order.raw = text;
+            Ok(order)
         } else {
             let status = resp.status();
             let text = resp
@@ -220,15 +972,61 @@ impl ExecutionClient {
                 .await
                 .unwrap_or_else(|_| format!("Status: {}", status));
 
-            // Check for Auth errors
-            if text.contains("-2014")
-                || text.contains("-2015")
-                || text.contains("API-key format invalid")
-            {
-                return Err(EngineError::ExchangeError(format!("AUTH_ERROR: {}", text)));
-            }
+            Err(exchange_error(text))
+        }
+    }
 
-            Err(EngineError::ExchangeError(text))
+    /// Cancel a single open order by exchange order id.
+    /// Returns the cancelled order as reported by the exchange.
+    pub async fn cancel_order(
+        &self,
+        symbol: &str,
+        order_id: i64,
+    ) -> Result<OrderResponse, EngineError> {
+        self.cancel(symbol, &format!("orderId={}", order_id)).await
+    }
+
+    /// Cancel a single open order by the `newClientOrderId` it was placed with.
+    pub async fn cancel_order_by_client_id(
+        &self,
+        symbol: &str,
+        client_order_id: &str,
+    ) -> Result<OrderResponse, EngineError> {
+        self.cancel(symbol, &format!("origClientOrderId={}", client_order_id))
+            .await
+    }
+
+    async fn cancel(&self, symbol: &str, order_param: &str) -> Result<OrderResponse, EngineError> {
+        self.await_rate_limit().await;
+
+        let signed_body = self.signed_order_query(symbol, order_param);
+        let url = format!("{}/fapi/v1/order", self.base_url);
+        let headers = self.signer.get_headers();
+
+        let resp = self
+            .http_client
+            .delete(&url)
+            .headers(headers)
+            .body(signed_body)
+            .send()
+            .await
+            .map_err(request_error)?;
+
+        if resp.status().is_success() {
+            let text = resp.text().await.map_err(request_error)?;
+            let mut order: OrderResponse = serde_json::from_str(&text).map_err(|e| {
+                EngineError::ExchangeError(format!("Failed to parse cancel response: {}", e))
+            })?;
+            order.raw = text;
+            Ok(order)
+        } else {
+            let status = resp.status();
+            let text = resp
+                .text()
+                .await
+                .unwrap_or_else(|_| format!("Status: {}", status));
+
+            Err(exchange_error(text))
         }
     }
 
@@ -241,10 +1039,11 @@ impl ExecutionClient {
         for attempt in 1..=max_retries {
             self.await_rate_limit().await;
 
-            let timestamp = chrono::Utc::now().timestamp_millis();
+            let timestamp = self.timestamp_ms();
             let query = format!(
-                "symbol={}&recvWindow=5000&timestamp={}",
+                "symbol={}&recvWindow={}&timestamp={}",
                 symbol.to_uppercase(),
+                self.recv_window_ms,
                 timestamp
             );
             let signature = self.signer.sign(&query);
@@ -321,20 +1120,433 @@ mod tests {
             "dummy_key".to_string(),
             "dummy_secret".to_string(),
             "https://testnet.binancefuture.com".to_string(),
+            None,
         );
 
         let instr = TradeInstruction {
-            symbol: "BTCUSDT".to_string().into(),
-            side: Side::Buy,
-            order_type: OrderType::Market,
-            price: 50001.0,
-            quantity: 0.01,
-            timestamp: 123456789,
             dry_run: true,
+            ..TradeInstruction::market("BTCUSDT", Side::Buy, 50001.0, 0.01, 123456789)
         };
 
         let result = client.place_order(&instr).await;
-        assert_eq!(result.unwrap(), "DRY_RUN_SUCCESS");
+        assert_eq!(result.unwrap().raw, "DRY_RUN_SUCCESS");
+
+        let stop = TradeInstruction {
+            order_type: OrderType::StopMarket,
+            stop_price: Some(49000.5),
+            reduce_only: false,
+            post_only: false,
+            client_order_id: None,
+            tick_timestamp: 0,
+            ..instr
+        };
+        let result = client.place_order(&stop).await;
+        assert_eq!(result.unwrap().raw, "DRY_RUN_SUCCESS");
+    }
+
+    #[test]
+    fn test_parse_klines() {
+        let text = r#"[
+            [1499040000000, "0.01634790", "0.80000000", "0.01575800", "0.01577100",
+             "148976.11427815", 1499644799999, "2434.19055334", 308, "1756.87402397",
+             "28.46694368", "17928899.62484339"],
+            [1499040060000, "0.01577100", "0.01580000", "0.01570000", "0.01575000",
+             "10.5", 1499040119999, "0.16", 3, "5.0", "0.08", "0"]
+        ]"#;
+
+        let klines = Kline::parse_list(text).unwrap();
+        assert_eq!(klines.len(), 2);
+        assert_eq!(
+            klines[0],
+            Kline {
+                open_time: 1499040000000,
+                open: 0.0163479,
+                high: 0.8,
+                low: 0.015758,
+                close: 0.015771,
+                volume: 148976.11427815,
+            }
+        );
+        assert_eq!(klines[1].close, 0.01575);
+
+        assert!(Kline::parse_list("[[1499040000000, \"abc\"]]").is_err());
+        assert!(Kline::parse_list("{\"code\": -1121}").is_err());
+    }
+
+    #[test]
+    fn test_parse_agg_trades() {
+        let text = r#"[
+            {"a": 26129, "p": "0.01633102", "q": "4.70443515", "f": 27781, "l": 27781,
+             "T": 1498793709153, "m": true},
+            {"a": 26130, "p": "0.01634000", "q": "1.5", "f": 27782, "l": 27783,
+             "T": 1498793709160, "m": false}
+        ]"#;
+
+        let trades = AggTrade::parse_list(text).unwrap();
+        assert_eq!(
+            trades,
+            vec![
+                AggTrade {
+                    price: 0.01633102,
+                    quantity: 4.70443515,
+                    time: 1498793709153,
+                    is_buyer_maker: true,
+                },
+                AggTrade {
+                    price: 0.01634,
+                    quantity: 1.5,
+                    time: 1498793709160,
+                    is_buyer_maker: false,
+                },
+            ]
+        );
+
+        assert!(AggTrade::parse_list(r#"[{"p": "abc", "q": "1", "T": 1, "m": true}]"#).is_err());
+        assert!(AggTrade::parse_list("{\"code\": -1121}").is_err());
+    }
+
+    #[test]
+    fn test_parse_order_response() {
+        let raw = r#"{"orderId":4095723017,"symbol":"BTCUSDT","status":"FILLED","clientOrderId":"abc123","price":"0","avgPrice":"50001.10","origQty":"0.010","executedQty":"0.010","type":"MARKET","side":"BUY","fills":[{"price":"50001.10","qty":"0.006","commission":"0.15000330","commissionAsset":"USDT"},{"price":"50001.10","qty":"0.004","commission":"0.10000220","commissionAsset":"USDT"}]}"#;
+        let order: OrderResponse = serde_json::from_str(raw).expect("Failed to parse");
+
+        assert_eq!(order.order_id, Some(4095723017));
+        assert_eq!(order.client_order_id.as_deref(), Some("abc123"));
+        let (commission, asset) = order.commission().expect("Expected commission");
+        assert!((commission - 0.2500055).abs() < 1e-9);
+        assert_eq!(asset, "USDT");
+
+        // Futures responses usually omit fills entirely
+        let raw = r#"{"orderId":1,"status":"NEW","clientOrderId":"x"}"#;
+        let order: OrderResponse = serde_json::from_str(raw).expect("Failed to parse");
+        assert!(order.commission().is_none());
+    }
+
+    #[test]
+    fn test_parse_open_orders() {
+        let raw = r#"[{"avgPrice":"0.00000","clientOrderId":"abc","cumQuote":"0","executedQty":"0","orderId":1917641,"origQty":"0.40","origType":"LIMIT","price":"49000.10","reduceOnly":false,"side":"BUY","positionSide":"BOTH","status":"NEW","stopPrice":"0","closePosition":false,"symbol":"BTCUSDT","time":1579276756075,"timeInForce":"GTC","type":"LIMIT","activatePrice":"0","priceRate":"0","updateTime":1579276756075,"workingType":"CONTRACT_PRICE","priceProtect":false}]"#;
+        let orders: Vec<OpenOrder> = serde_json::from_str(raw).expect("Failed to parse");
+
+        assert_eq!(orders.len(), 1);
+        assert_eq!(orders[0].order_id, 1917641);
+        assert_eq!(orders[0].side, "BUY");
+        assert_eq!(orders[0].order_type, "LIMIT");
+        assert_eq!(orders[0].price, "49000.10");
+        assert_eq!(orders[0].orig_qty, "0.40");
+        assert_eq!(orders[0].status, "NEW");
+    }
+
+    #[test]
+    fn test_order_query() {
+        let mut instr = TradeInstruction::market("btcusdt", Side::Sell, 50001.0, 0.01, 123456789);
+
+        let query =
+            ExecutionClient::order_query(&instr, DEFAULT_RECV_WINDOW_MS, 1700000000000).unwrap();
+        assert_eq!(
+            query,
+            "symbol=BTCUSDT&side=SELL&type=MARKET&quantity=0.01&recvWindow=5000&timestamp=1700000000000"
+        );
+
+        instr.order_type = OrderType::Limit;
+        let query =
+            ExecutionClient::order_query(&instr, DEFAULT_RECV_WINDOW_MS, 1700000000000).unwrap();
+        assert_eq!(
+            query,
+            "symbol=BTCUSDT&side=SELL&type=LIMIT&quantity=0.01&timeInForce=GTC&price=50001&recvWindow=5000&timestamp=1700000000000"
+        );
+
+        instr.post_only = true;
+        let query =
+            ExecutionClient::order_query(&instr, DEFAULT_RECV_WINDOW_MS, 1700000000000).unwrap();
+        assert_eq!(
+            query,
+            "symbol=BTCUSDT&side=SELL&type=LIMIT&quantity=0.01&timeInForce=GTX&price=50001&recvWindow=5000&timestamp=1700000000000"
+        );
+        instr.post_only = false;
+
+        instr.order_type = OrderType::StopMarket;
+        assert!(
+            ExecutionClient::order_query(&instr, DEFAULT_RECV_WINDOW_MS, 1700000000000).is_err()
+        );
+
+        instr.stop_price = Some(49000.5);
+        let query =
+            ExecutionClient::order_query(&instr, DEFAULT_RECV_WINDOW_MS, 1700000000000).unwrap();
+        assert_eq!(
+            query,
+            "symbol=BTCUSDT&side=SELL&type=STOP_MARKET&quantity=0.01&stopPrice=49000.5&recvWindow=5000&timestamp=1700000000000"
+        );
+
+        instr.order_type = OrderType::Market;
+        instr.stop_price = None;
+        instr.reduce_only = true;
+        let query =
+            ExecutionClient::order_query(&instr, DEFAULT_RECV_WINDOW_MS, 1700000000000).unwrap();
+        assert_eq!(
+            query,
+            "symbol=BTCUSDT&side=SELL&type=MARKET&quantity=0.01&reduceOnly=true&recvWindow=5000&timestamp=1700000000000"
+        );
+    }
+
+    #[test]
+    fn test_client_order_id_in_query() {
+        let first = new_client_order_id("Momentum Strategy!");
+        let second = new_client_order_id("Momentum Strategy!");
+        assert!(first.starts_with("MomentumStra-"));
+        assert!(first.len() <= 36);
+        assert_ne!(first, second);
+
+        let instr = TradeInstruction {
+            client_order_id: Some(first.clone()),
+            ..TradeInstruction::market("BTCUSDT", Side::Buy, 50000.0, 0.01, 0)
+        };
+        let query =
+            ExecutionClient::order_query(&instr, DEFAULT_RECV_WINDOW_MS, 1700000000000).unwrap();
+        assert_eq!(
+            query,
+            format!(
+                "symbol=BTCUSDT&side=BUY&type=MARKET&quantity=0.01&newClientOrderId={}&recvWindow=5000&timestamp=1700000000000",
+                first
+            )
+        );
+    }
+
+    #[tokio::test]
+    async fn test_place_order_retries_timeout_and_accepts_duplicate() {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            // First attempt: request lands but the response never arrives in time
+            let (mut stalled, _) = listener.accept().await.unwrap();
+            let mut buf = [0u8; 4096];
+            let _ = stalled.read(&mut buf).await;
+
+            // Retry: exchange already has an order with this clientOrderId
+            let (mut retry, _) = listener.accept().await.unwrap();
+            let n = retry.read(&mut buf).await.unwrap();
+            assert!(String::from_utf8_lossy(&buf[..n]).contains("newClientOrderId=Test-1"));
+            let body = r#"{"code":-4116,"msg":"ClientOrderId is duplicated."}"#;
+            let response = format!(
+                "HTTP/1.1 400 Bad Request\r\nContent-Type: application/json\r\nConnection: close\r\nContent-Length: {}\r\n\r\n{}",
+                body.len(),
+                body
+            );
+            retry.write_all(response.as_bytes()).await.unwrap();
+            drop(retry);
+
+            // Lookup of the accepted order by its clientOrderId
+            let (mut lookup, _) = listener.accept().await.unwrap();
+            let n = lookup.read(&mut buf).await.unwrap();
+            let request = String::from_utf8_lossy(&buf[..n]);
+            assert!(request.starts_with("GET /fapi/v1/order?"));
+            assert!(request.contains("origClientOrderId=Test-1"));
+            let body = r#"{"orderId":4095723017,"clientOrderId":"Test-1","status":"FILLED","avgPrice":"50000.0","executedQty":"0.01"}"#;
+            let response = format!(
+                "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\r\n{}",
+                body.len(),
+                body
+            );
+            lookup.write_all(response.as_bytes()).await.unwrap();
+            tokio::time::sleep(Duration::from_secs(1)).await;
+            drop(stalled);
+        });
+
+        let client = ExecutionClient::new(
+            "dummy_key".to_string(),
+            "dummy_secret".to_string(),
+            format!("http://{}", addr),
+            None,
+        )
+        .with_timeout(Duration::from_millis(200));
+
+        let instr = TradeInstruction {
+            client_order_id: Some("Test-1".to_string()),
+            ..TradeInstruction::market("BTCUSDT", Side::Buy, 50000.0, 0.01, 0)
+        };
+
+        let response = client
+            .place_order(&instr)
+            .await
+            .expect("Duplicate is success");
+        assert_eq!(response.client_order_id.as_deref(), Some("Test-1"));
+        assert_eq!(response.order_id, Some(4095723017));
+    }
+
+    #[tokio::test]
+    async fn test_place_order_duplicate_with_failed_lookup_is_state_unknown() {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            let mut buf = [0u8; 4096];
+            let (mut stalled, _) = listener.accept().await.unwrap();
+            let _ = stalled.read(&mut buf).await;
+
+            // Retry is a duplicate, then the lookup fails
+            for body in [
+                r#"{"code":-4116,"msg":"ClientOrderId is duplicated."}"#,
+                r#"{"code":-1001,"msg":"Internal error; unable to process your request."}"#,
+            ] {
+                let (mut conn, _) = listener.accept().await.unwrap();
+                let _ = conn.read(&mut buf).await.unwrap();
+                let response = format!(
+                    "HTTP/1.1 400 Bad Request\r\nContent-Type: application/json\r\nConnection: close\r\nContent-Length: {}\r\n\r\n{}",
+                    body.len(),
+                    body
+                );
+                conn.write_all(response.as_bytes()).await.unwrap();
+            }
+            tokio::time::sleep(Duration::from_secs(1)).await;
+            drop(stalled);
+        });
+
+        let client = ExecutionClient::new(
+            "dummy_key".to_string(),
+            "dummy_secret".to_string(),
+            format!("http://{}", addr),
+            None,
+        )
+        .with_timeout(Duration::from_millis(200));
+
+        let instr = TradeInstruction {
+            client_order_id: Some("Test-2".to_string()),
+            ..TradeInstruction::market("BTCUSDT", Side::Buy, 50000.0, 0.01, 0)
+        };
+
+        let e = client
+            .place_order(&instr)
+            .await
+            .expect_err("Outcome is unknown");
+        assert!(is_order_state_unknown(&e), "{}", e);
+        assert!(!is_order_state_unknown(&EngineError::ExchangeError(
+            "Order timed out".to_string()
+        )));
+    }
+
+    #[test]
+    fn test_is_post_only_reject() {
+        let reject = EngineError::ExchangeError(
+            r#"{"code":-5022,"msg":"Due to the order could not be executed as maker, the Post Only order will be rejected. The order will not be recorded in the order history"}"#.to_string(),
+        );
+        assert!(is_post_only_reject(&reject));
+        assert!(!is_post_only_reject(&EngineError::ExchangeError(
+            r#"{"code":-2019,"msg":"Margin is insufficient."}"#.to_string()
+        )));
+    }
+
+    #[tokio::test]
+    async fn test_request_error_omits_signed_url() {
+        // Nothing listens on port 1
+        let e = reqwest::Client::new()
+            .get("http://127.0.0.1:1/fapi/v2/balance?timestamp=1&signature=deadbeefcafe")
+            .send()
+            .await
+            .expect_err("Connection should fail");
+        assert!(e.to_string().contains("deadbeefcafe"));
+
+        let EngineError::ExchangeError(text) = request_error(e) else {
+            panic!("Expected ExchangeError");
+        };
+        assert!(!text.contains("signature"), "{}", text);
+    }
+
+    #[test]
+    fn test_bad_signature_is_auth_error() {
+        let bad_secret = exchange_error(
+            r#"{"code":-1022,"msg":"Signature for this request is not valid."}"#.to_string(),
+        );
+        assert!(is_auth_error(&bad_secret));
+        assert!(is_auth_error(&exchange_error(
+            r#"{"code":-2015,"msg":"Invalid API-key, IP, or permissions for action."}"#.to_string()
+        )));
+        assert!(!is_auth_error(&exchange_error(
+            r#"{"code":-2019,"msg":"Margin is insufficient."}"#.to_string()
+        )));
+    }
+
+    #[test]
+    fn test_positions_query_symbol_filter() {
+        let query = ExecutionClient::positions_query(
+            Some("btcusdt"),
+            DEFAULT_RECV_WINDOW_MS,
+            1700000000000,
+        );
+        assert_eq!(
+            query,
+            "symbol=BTCUSDT&recvWindow=5000&timestamp=1700000000000"
+        );
+
+        let query = ExecutionClient::positions_query(None, DEFAULT_RECV_WINDOW_MS, 1700000000000);
+        assert_eq!(query, "recvWindow=5000&timestamp=1700000000000");
+
+        let query = ExecutionClient::positions_query(None, 15000, 1700000000000);
+        assert_eq!(query, "recvWindow=15000&timestamp=1700000000000");
+    }
+
+    #[test]
+    fn test_signed_order_query_includes_order_id() {
+        let client = ExecutionClient::new(
+            "dummy_key".to_string(),
+            "dummy_secret".to_string(),
+            "https://testnet.binancefuture.com".to_string(),
+            None,
+        );
+
+        let signed = client.signed_order_query("btcusdt", "orderId=4095723017");
+        let (query, signature) = signed.rsplit_once("&signature=").expect("Unsigned query");
+        assert!(query.starts_with("symbol=BTCUSDT&orderId=4095723017&recvWindow=5000&timestamp="));
+        assert_eq!(signature, client.signer.sign(query));
+
+        let signed = client.signed_order_query("BTCUSDT", "origClientOrderId=MM-1-2");
+        assert!(signed.starts_with("symbol=BTCUSDT&origClientOrderId=MM-1-2&recvWindow=5000&"));
+    }
+
+    #[tokio::test]
+    async fn test_set_leverage_rejects_out_of_range() {
+        let client = ExecutionClient::new(
+            "dummy_key".to_string(),
+            "dummy_secret".to_string(),
+            "https://testnet.binancefuture.com".to_string(),
+            None,
+        );
+
+        for leverage in [0, 126] {
+            assert!(matches!(
+                client.set_leverage("BTCUSDT", leverage).await,
+                Err(EngineError::ParseError(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn test_network_from_url() {
+        assert_eq!(
+            Network::from_url("https://testnet.binancefuture.com"),
+            Network::Testnet
+        );
+        assert_eq!(
+            Network::from_url("https://fapi.binance.com"),
+            Network::Mainnet
+        );
+
+        let client = ExecutionClient::new(
+            "dummy_key".to_string(),
+            "dummy_secret".to_string(),
+            "https://fapi.binance.com".to_string(),
+            None,
+        );
+        assert!(client.is_mainnet());
+    }
+
+    #[test]
+    fn test_compute_offset() {
+        // Server 1500ms ahead of the request midpoint
+        assert_eq!(ExecutionClient::compute_offset(3_000, 1_000, 2_000), 1_500);
+        // Server behind local clock
+        assert_eq!(ExecutionClient::compute_offset(1_000, 1_200, 1_400), -300);
     }
 
     #[test]
@@ -0,0 +1,255 @@
+use common::{EngineError, OrderType, Side, TradeInstruction};
+use serde::Deserialize;
+
+/// Guards against `0.013 / 0.001 = 12.999999999999998` style float error when
+/// counting whole steps.
+const STEP_EPSILON: f64 = 1e-9;
+
+/// Price/quantity precision rules for one symbol, from `exchangeInfo`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SymbolFilters {
+    /// `PRICE_FILTER.tickSize`: prices must be a multiple of this.
+    pub tick_size: f64,
+    /// `LOT_SIZE.stepSize`: quantities must be a multiple of this.
+    pub step_size: f64,
+    /// `LOT_SIZE.minQty`: smallest accepted order quantity.
+    pub min_qty: f64,
+    /// `MIN_NOTIONAL.notional`: smallest accepted `price * quantity` (0 if not filtered).
+    pub min_notional: f64,
+}
+
+#[derive(Debug, Deserialize)]
+struct ExchangeInfo {
+    symbols: Vec<SymbolInfo>,
+}
+
+#[derive(Debug, Deserialize)]
+struct SymbolInfo {
+    symbol: String,
+    filters: Vec<RawFilter>,
+}
+
+#[derive(Debug, Deserialize)]
+struct RawFilter {
+    #[serde(rename = "filterType")]
+    filter_type: String,
+    #[serde(rename = "tickSize")]
+    tick_size: Option<String>,
+    #[serde(rename = "stepSize")]
+    step_size: Option<String>,
+    #[serde(rename = "minQty")]
+    min_qty: Option<String>,
+    notional: Option<String>,
+}
+
+fn parse_field(name: &str, value: Option<String>) -> Result<f64, EngineError> {
+    let value = value.ok_or_else(|| EngineError::ParseError(format!("Missing {}", name)))?;
+    value
+        .parse()
+        .map_err(|_| EngineError::ParseError(format!("Invalid {}: {}", name, value)))
+}
+
+impl SymbolFilters {
+    /// Extract the filters for `symbol` from a `GET /fapi/v1/exchangeInfo` body.
+    pub fn from_exchange_info(text: &str, symbol: &str) -> Result<Self, EngineError> {
+        let info: ExchangeInfo =
+            serde_json::from_str(text).map_err(|e| EngineError::ParseError(e.to_string()))?;
+        let symbol_info = info
+            .symbols
+            .into_iter()
+            .find(|s| s.symbol.eq_ignore_ascii_case(symbol))
+            .ok_or_else(|| EngineError::ParseError(format!("Unknown symbol: {}", symbol)))?;
+
+        let mut tick_size = None;
+        let mut lot_size = None;
+        let mut min_notional = 0.0;
+        for filter in symbol_info.filters {
+            match filter.filter_type.as_str() {
+                "PRICE_FILTER" => tick_size = Some(parse_field("tickSize", filter.tick_size)?),
+                "LOT_SIZE" => {
+                    lot_size = Some((
+                        parse_field("stepSize", filter.step_size)?,
+                        parse_field("minQty", filter.min_qty)?,
+                    ))
+                }
+                "MIN_NOTIONAL" => min_notional = parse_field("notional", filter.notional)?,
+                _ => {}
+            }
+        }
+
+        let tick_size =
+            tick_size.ok_or_else(|| EngineError::ParseError("Missing PRICE_FILTER".into()))?;
+        let (step_size, min_qty) =
+            lot_size.ok_or_else(|| EngineError::ParseError("Missing LOT_SIZE".into()))?;
+        Ok(Self {
+            tick_size,
+            step_size,
+            min_qty,
+            min_notional,
+        })
+    }
+
+    /// Round a price to a tick, never in the order's disfavour: buys round down and
+    /// sells up, so a post-only bid can't be pushed onto the ask.
+    pub fn round_price(&self, price: f64, side: Side) -> f64 {
+        if self.tick_size <= 0.0 {
+            return price;
+        }
+        let ticks = price / self.tick_size;
+        let ticks = match side {
+            Side::Buy => (ticks + STEP_EPSILON).floor(),
+            Side::Sell => (ticks - STEP_EPSILON).ceil(),
+        };
+        ticks * self.tick_size
+    }
+
+    /// Round a quantity down to a whole number of steps (never sends more than asked).
+    pub fn round_qty(&self, quantity: f64) -> f64 {
+        if self.step_size <= 0.0 {
+            return quantity;
+        }
+        (quantity / self.step_size + STEP_EPSILON).floor() * self.step_size
+    }
+
+    /// Conform an order to the symbol's precision. Orders that round below `minQty` or
+    /// `min_notional` (-4164) are rejected here instead of by the exchange.
+    /// Market orders are valued at the signal price. Reduce-only orders are exempt from
+    /// `min_notional`, as on the exchange, so small positions can still be closed.
+    pub fn apply(&self, instruction: &TradeInstruction) -> Result<TradeInstruction, EngineError> {
+        let quantity = self.round_qty(instruction.quantity);
+        if quantity < self.min_qty - STEP_EPSILON || quantity <= 0.0 {
+            return Err(EngineError::RiskViolation(format!(
+                "Quantity {} below minQty {} for {}",
+                instruction.quantity, self.min_qty, instruction.symbol
+            )));
+        }
+
+        let mut rounded = instruction.clone();
+        rounded.quantity = quantity;
+        if instruction.order_type == OrderType::Limit {
+            rounded.price = self.round_price(instruction.price, instruction.side);
+        }
+        rounded.stop_price = instruction
+            .stop_price
+            .map(|p| self.round_price(p, instruction.side));
+
+        let notional = rounded.price * rounded.quantity;
+        if !instruction.reduce_only && notional < self.min_notional {
+            return Err(EngineError::RiskViolation(format!(
+                "Notional {:.2} below minimum {} for {}",
+                notional, self.min_notional, instruction.symbol
+            )));
+        }
+        Ok(rounded)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BTC: SymbolFilters = SymbolFilters {
+        tick_size: 0.1,
+        step_size: 0.001,
+        min_qty: 0.001,
+        min_notional: 100.0,
+    };
+
+    #[test]
+    fn test_from_exchange_info() {
+        let raw = r#"{"timezone":"UTC","serverTime":1565613908500,"symbols":[{"symbol":"ETHUSDT","filters":[{"filterType":"PRICE_FILTER","maxPrice":"100000","minPrice":"0.01","tickSize":"0.01"},{"filterType":"LOT_SIZE","maxQty":"10000","minQty":"0.001","stepSize":"0.001"}]},{"symbol":"BTCUSDT","pricePrecision":2,"quantityPrecision":3,"filters":[{"filterType":"PRICE_FILTER","maxPrice":"4529764","minPrice":"556.80","tickSize":"0.10"},{"filterType":"LOT_SIZE","maxQty":"1000","minQty":"0.001","stepSize":"0.001"},{"filterType":"MARKET_LOT_SIZE","maxQty":"120","minQty":"0.001","stepSize":"0.001"},{"filterType":"MIN_NOTIONAL","notional":"100"}]}]}"#;
+        let filters = SymbolFilters::from_exchange_info(raw, "btcusdt").expect("Failed to parse");
+        assert_eq!(filters, BTC);
+
+        assert!(SymbolFilters::from_exchange_info(raw, "XRPUSDT").is_err());
+    }
+
+    #[test]
+    fn test_round_qty_and_price() {
+        assert_eq!(format!("{:.8}", BTC.round_qty(0.013)), "0.01300000");
+        assert_eq!(format!("{:.8}", BTC.round_qty(0.0139)), "0.01300000");
+    }
+
+    #[test]
+    fn test_round_price_by_side() {
+        // Buys never pay more, sells never take less
+        assert_eq!(
+            format!("{:.8}", BTC.round_price(50000.06, Side::Buy)),
+            "50000.00000000"
+        );
+        assert_eq!(
+            format!("{:.8}", BTC.round_price(50000.04, Side::Sell)),
+            "50000.10000000"
+        );
+        // Already on a tick: unchanged either way despite float error
+        assert_eq!(
+            format!("{:.8}", BTC.round_price(50000.3, Side::Buy)),
+            "50000.30000000"
+        );
+        assert_eq!(
+            format!("{:.8}", BTC.round_price(50000.3, Side::Sell)),
+            "50000.30000000"
+        );
+    }
+
+    #[test]
+    fn test_apply_rejects_below_min_qty() {
+        let instruction = TradeInstruction::limit("BTCUSDT", Side::Buy, 50000.06, 0.013, 0);
+
+        let rounded = BTC.apply(&instruction).expect("Valid order");
+        assert!((rounded.quantity - 0.013).abs() < 1e-12);
+        assert!((rounded.price - 50000.0).abs() < 1e-9);
+
+        let sell = TradeInstruction {
+            side: Side::Sell,
+            stop_price: Some(49000.01),
+            ..instruction.clone()
+        };
+        let rounded = BTC.apply(&sell).expect("Valid order");
+        assert!((rounded.price - 50000.1).abs() < 1e-9);
+        assert!((rounded.stop_price.unwrap() - 49000.1).abs() < 1e-9);
+
+        let tiny = TradeInstruction {
+            quantity: 0.0009,
+            ..instruction
+        };
+        assert!(matches!(
+            BTC.apply(&tiny),
+            Err(EngineError::RiskViolation(_))
+        ));
+    }
+
+    #[test]
+    fn test_apply_rejects_below_min_notional() {
+        let instruction = TradeInstruction::market("BTCUSDT", Side::Buy, 9_000.0, 0.01, 0);
+        // 90 USDT < 100 USDT
+        assert!(matches!(
+            BTC.apply(&instruction),
+            Err(EngineError::RiskViolation(msg)) if msg.contains("Notional")
+        ));
+
+        let at_min = TradeInstruction {
+            price: 10_000.0,
+            ..instruction
+        };
+        assert!(BTC.apply(&at_min).is_ok());
+    }
+
+    #[test]
+    fn test_apply_allows_small_reduce_only_close() {
+        let close = TradeInstruction {
+            reduce_only: true,
+            ..TradeInstruction::market("BTCUSDT", Side::Sell, 9_000.0, 0.002, 0)
+        };
+        // 18 USDT < 100 USDT, but closing
+        let rounded = BTC.apply(&close).expect("Reduce-only close rejected");
+        assert!((rounded.quantity - 0.002).abs() < 1e-12);
+
+        // minQty still applies
+        let tiny = TradeInstruction {
+            quantity: 0.0009,
+            ..close
+        };
+        assert!(BTC.apply(&tiny).is_err());
+    }
+}
@@ -1,5 +1,12 @@
 pub mod client;
+pub mod filters;
 pub mod signer;
+pub mod user_stream;
 
-pub use client::ExecutionClient;
-pub use signer::BinanceSigner;
+pub use client::{
+    is_auth_error, is_order_state_unknown, is_post_only_reject, new_client_order_id, AggTrade,
+    ExecutionClient, Kline, Network, OpenOrder, OrderResponse, PositionRisk,
+};
+pub use filters::SymbolFilters;
+pub use signer::{BinanceSigner, SignScheme};
+pub use user_stream::{parse_user_event, start_user_stream, FillEvent};
@@ -0,0 +1,312 @@
+use common::{EngineError, Side};
+use futures_util::StreamExt;
+use serde::Deserialize;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::{mpsc, watch, Notify};
+use tokio_tungstenite::{connect_async, tungstenite::protocol::Message};
+use url::Url;
+
+/// A confirmed (partial) fill from an `ORDER_TRADE_UPDATE` event.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FillEvent {
+    pub symbol: String,
+    pub order_id: i64,
+    pub client_order_id: String,
+    pub side: Side,
+    /// Quantity of this fill (not the cumulative order quantity).
+    pub quantity: f64,
+    pub price: f64,
+    pub commission: f64,
+    pub commission_asset: String,
+    /// True if the fill added liquidity (maker), false if it took it (taker).
+    pub is_maker: bool,
+    pub trade_id: i64,
+    /// Exchange trade time (epoch ms).
+    pub trade_time: i64,
+    /// Order status after this fill (`PARTIALLY_FILLED` / `FILLED`).
+    pub order_status: String,
+    /// Raw event, kept for persistence/debugging.
+    pub raw: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct UserEvent {
+    #[serde(rename = "e")]
+    event_type: String,
+    #[serde(rename = "o")]
+    order: Option<OrderUpdate>,
+}
+
+#[derive(Debug, Deserialize)]
+#[allow(non_snake_case)]
+struct OrderUpdate {
+    s: String,
+    c: String,
+    S: String,
+    x: String,
+    X: String,
+    i: i64,
+    l: String,
+    L: String,
+    #[serde(default)]
+    N: Option<String>,
+    #[serde(default)]
+    n: Option<String>,
+    T: i64,
+    t: i64,
+    #[serde(default)]
+    m: bool,
+}
+
+fn parse_f64(field: &str, value: &str) -> Result<f64, EngineError> {
+    value
+        .parse()
+        .map_err(|_| EngineError::ParseError(format!("Invalid {}: {}", field, value)))
+}
+
+/// Parse a user-data stream message. Returns `Ok(None)` for anything that is not a
+/// trade execution (account updates, new/cancelled orders, listenKey expiry, ...).
+pub fn parse_user_event(text: &str) -> Result<Option<FillEvent>, EngineError> {
+    let event: UserEvent =
+        serde_json::from_str(text).map_err(|e| EngineError::ParseError(e.to_string()))?;
+
+    if event.event_type != "ORDER_TRADE_UPDATE" {
+        return Ok(None);
+    }
+    let order = event
+        .order
+        .ok_or_else(|| EngineError::ParseError("ORDER_TRADE_UPDATE without order".into()))?;
+    if order.x != "TRADE" {
+        return Ok(None);
+    }
+
+    let side = match order.S.as_str() {
+        "BUY" => Side::Buy,
+        "SELL" => Side::Sell,
+        other => return Err(EngineError::ParseError(format!("Invalid side: {}", other))),
+    };
+    let commission = match order.n.as_deref() {
+        Some(n) => parse_f64("commission", n)?,
+        None => 0.0,
+    };
+
+    Ok(Some(FillEvent {
+        symbol: order.s,
+        order_id: order.i,
+        client_order_id: order.c,
+        side,
+        quantity: parse_f64("last filled quantity", &order.l)?,
+        price: parse_f64("last filled price", &order.L)?,
+        commission,
+        commission_asset: order.N.unwrap_or_default(),
+        is_maker: order.m,
+        trade_id: order.t,
+        trade_time: order.T,
+        order_status: order.X,
+        raw: text.to_string(),
+    }))
+}
+
+fn stream_url(ws_url: &str, listen_key: &str) -> String {
+    common::ws::stream_url(ws_url, listen_key)
+}
+
+/// Open the futures user-data stream (`<ws_url>/ws/<listenKey>`, e.g.
+/// `wss://fstream.binance.com/ws/<listenKey>`; `ws_url` may include the `/ws`) and
+/// forward confirmed fills.
+///
+/// The key is read from `listen_key` on every (re)connect, and a new value forces an
+/// immediate reconnect. `key_invalid` is notified whenever the stream drops or the key
+/// expires, so the owner can obtain a fresh key (see `ExecutionClient::create_listen_key`).
+/// `reconnected` is notified on every connect after the first: fills made while the
+/// stream was down are never replayed, so the owner should re-sync its position.
+/// The stream stops once the fill receiver or the `listen_key` sender is dropped.
+pub async fn start_user_stream(
+    ws_url: &str,
+    listen_key: watch::Receiver<String>,
+    key_invalid: Arc<Notify>,
+    reconnected: Arc<Notify>,
+) -> Result<mpsc::Receiver<FillEvent>, EngineError> {
+    // Validate URL upfront
+    let url_str = stream_url(ws_url, &listen_key.borrow());
+    if Url::parse(&url_str).is_err() {
+        return Err(EngineError::ParseError(format!("Invalid URL: {}", url_str)));
+    }
+
+    let (tx, rx) = mpsc::channel::<FillEvent>(1_000);
+    tokio::spawn(run_user_stream(
+        ws_url.to_string(),
+        listen_key,
+        key_invalid,
+        reconnected,
+        tx,
+    ));
+
+    Ok(rx)
+}
+
+async fn run_user_stream(
+    ws_url: String,
+    mut listen_key: watch::Receiver<String>,
+    key_invalid: Arc<Notify>,
+    reconnected: Arc<Notify>,
+    tx: mpsc::Sender<FillEvent>,
+) {
+    let mut backoff = Duration::from_millis(100);
+    let max_backoff = Duration::from_secs(5);
+    let mut connected_before = false;
+
+    loop {
+        let url_str = stream_url(&ws_url, &listen_key.borrow_and_update());
+        let mut key_rotated = false;
+
+        match Url::parse(&url_str) {
+            Ok(url) => match connect_async(url).await {
+                Ok((ws_stream, _)) => {
+                    tracing::info!("Connected to user-data stream");
+                    backoff = Duration::from_millis(100); // Reset backoff
+                    if connected_before {
+                        tracing::warn!(
+                            "User stream reconnected: fills while disconnected were missed"
+                        );
+                        reconnected.notify_one();
+                    }
+                    connected_before = true;
+
+                    let (_, mut read) = ws_stream.split();
+
+                    loop {
+                        let msg = tokio::select! {
+                            msg = read.next() => match msg {
+                                Some(msg) => msg,
+                                None => break,
+                            },
+                            changed = listen_key.changed() => {
+                                if changed.is_err() {
+                                    tracing::info!("listenKey owner dropped, closing user stream");
+                                    return;
+                                }
+                                tracing::info!("listenKey rotated, reconnecting user stream");
+                                key_rotated = true;
+                                break;
+                            }
+                        };
+
+                        match msg {
+                            Ok(Message::Text(text)) => match parse_user_event(&text) {
+                                Ok(Some(fill)) => {
+                                    // Fills must never be dropped: wait for the consumer
+                                    if tx.send(fill).await.is_err() {
+                                        tracing::info!(
+                                            "Fill receiver dropped, closing user stream"
+                                        );
+                                        return;
+                                    }
+                                }
+                                Ok(None) => {
+                                    if text.contains("listenKeyExpired") {
+                                        tracing::error!("User-data listenKey expired");
+                                        break;
+                                    }
+                                }
+                                Err(e) => {
+                                    tracing::warn!("User stream parse error: {}", e);
+                                }
+                            },
+                            Ok(Message::Ping(_)) | Ok(Message::Pong(_)) => {}
+                            Ok(Message::Close(_)) => {
+                                tracing::warn!("User stream closed by server");
+                                break;
+                            }
+                            Err(e) => {
+                                tracing::error!("User stream error: {}", e);
+                                break;
+                            }
+                            _ => {}
+                        }
+                    }
+                }
+                Err(e) => {
+                    tracing::warn!(
+                        "User stream connection failed: {}. Retrying in {:?}",
+                        e,
+                        backoff
+                    );
+                }
+            },
+            // The URL embeds the listenKey, keep it out of the logs
+            Err(e) => tracing::error!("Invalid user stream URL: {}", e),
+        }
+
+        if tx.is_closed() {
+            return;
+        }
+        if key_rotated {
+            continue;
+        }
+
+        // The key may have expired while disconnected: ask the owner for a fresh one
+        key_invalid.notify_one();
+        tokio::time::sleep(backoff).await;
+        backoff = std::cmp::min(backoff * 2, max_backoff);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TRADE_UPDATE: &str = r#"{"e":"ORDER_TRADE_UPDATE","E":1568879465651,"T":1568879465650,"o":{"s":"BTCUSDT","c":"TEST","S":"SELL","o":"MARKET","f":"GTC","q":"0.002","p":"0","ap":"9000.5","sp":"0","x":"TRADE","X":"PARTIALLY_FILLED","i":8886774,"l":"0.001","z":"0.001","L":"9000.5","N":"USDT","n":"0.0045","T":1568879465651,"t":12345,"b":"0","a":"0","m":false,"R":false,"wt":"CONTRACT_PRICE","ot":"MARKET","ps":"BOTH","cp":false,"rp":"0"}}"#;
+
+    #[test]
+    fn test_parse_trade_update() {
+        let fill = parse_user_event(TRADE_UPDATE).unwrap().expect("fill");
+        assert_eq!(fill.symbol, "BTCUSDT");
+        assert_eq!(fill.order_id, 8886774);
+        assert_eq!(fill.side, Side::Sell);
+        assert_eq!(fill.quantity, 0.001);
+        assert_eq!(fill.price, 9000.5);
+        assert_eq!(fill.commission, 0.0045);
+        assert_eq!(fill.commission_asset, "USDT");
+        assert!(!fill.is_maker);
+        assert_eq!(fill.trade_id, 12345);
+
+        let maker = TRADE_UPDATE.replace(r#""m":false"#, r#""m":true"#);
+        assert!(parse_user_event(&maker).unwrap().expect("fill").is_maker);
+        assert_eq!(fill.order_status, "PARTIALLY_FILLED");
+    }
+
+    #[test]
+    fn test_non_fill_events_are_ignored() {
+        let new_order = TRADE_UPDATE
+            .replace(r#""x":"TRADE""#, r#""x":"NEW""#)
+            .replace(r#""n":"0.0045","#, "");
+        assert_eq!(parse_user_event(&new_order).unwrap(), None);
+
+        let account = r#"{"e":"ACCOUNT_UPDATE","E":1564745798939,"T":1564745798938,"a":{"m":"ORDER","B":[],"P":[]}}"#;
+        assert_eq!(parse_user_event(account).unwrap(), None);
+
+        let expired = r#"{"e":"listenKeyExpired","E":1576653824250}"#;
+        assert_eq!(parse_user_event(expired).unwrap(), None);
+
+        assert!(parse_user_event("not json").is_err());
+    }
+
+    #[test]
+    fn test_stream_url() {
+        assert_eq!(
+            stream_url("wss://fstream.binance.com/ws", "abc"),
+            "wss://fstream.binance.com/ws/abc"
+        );
+        assert_eq!(
+            stream_url("wss://stream.binancefuture.com/ws/", "abc"),
+            "wss://stream.binancefuture.com/ws/abc"
+        );
+        // Same base as the market feed accepts
+        assert_eq!(
+            stream_url("wss://fstream.binance.com", "abc"),
+            "wss://fstream.binance.com/ws/abc"
+        );
+    }
+}
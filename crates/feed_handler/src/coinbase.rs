@@ -0,0 +1,216 @@
+use crate::{run_stream, FeedSource, FeedStatus, Subscription};
+use async_trait::async_trait;
+use common::{EngineError, EventKind, MarketEvent, SymbolId};
+use serde::Deserialize;
+use std::sync::atomic::AtomicU64;
+use std::sync::Arc;
+use tokio::sync::{mpsc, watch};
+use url::Url;
+
+/// Coinbase Exchange public feed.
+pub const COINBASE_WS_URL: &str = "wss://ws-feed.exchange.coinbase.com";
+
+/// Coinbase Exchange `matches` channel feed.
+/// Engine symbols (`BTCUSDT`) are mapped to Coinbase products (`BTC-USD`) and back.
+#[derive(Clone)]
+pub struct CoinbaseFeed {
+    ws_url: String,
+    status_tx: Option<watch::Sender<FeedStatus>>,
+    drop_counter: Option<Arc<AtomicU64>>,
+}
+
+/// `match` message: one trade between a resting maker and a taker.
+#[derive(Deserialize)]
+struct CoinbaseMatch {
+    #[serde(rename = "type")]
+    kind: String,
+    #[serde(default)]
+    product_id: String,
+    #[serde(default)]
+    price: String,
+    #[serde(default)]
+    size: String,
+    #[serde(default)]
+    time: String,
+    /// Side of the maker order.
+    #[serde(default)]
+    side: String,
+    #[serde(default)]
+    message: Option<String>,
+}
+
+/// `BTCUSDT` -> `BTC-USD`. Coinbase quotes in USD where the engine uses USDT.
+pub fn to_product_id(symbol: &str) -> Result<String, EngineError> {
+    let symbol = symbol.to_ascii_uppercase();
+    let base = symbol
+        .strip_suffix("USDT")
+        .or_else(|| symbol.strip_suffix("USD"))
+        .filter(|base| !base.is_empty())
+        .ok_or_else(|| {
+            EngineError::ParseError(format!("No Coinbase USD product for {}", symbol))
+        })?;
+    Ok(format!("{}-USD", base))
+}
+
+/// `BTC-USD` -> `BTCUSDT`, other quotes are concatenated (`ETH-EUR` -> `ETHEUR`).
+pub fn from_product_id(product_id: &str) -> String {
+    match product_id.split_once('-') {
+        Some((base, "USD")) => format!("{}USDT", base),
+        Some((base, quote)) => format!("{}{}", base, quote),
+        None => product_id.to_string(),
+    }
+}
+
+/// ISO8601 / RFC3339 timestamp (`2014-11-07T08:19:27.028459Z`) to epoch ms.
+fn parse_time_ms(time: &str) -> Result<i64, EngineError> {
+    chrono::DateTime::parse_from_rfc3339(time)
+        .map(|t| t.timestamp_millis())
+        .map_err(|e| EngineError::ParseError(format!("Invalid time {:?}: {}", time, e)))
+}
+
+impl CoinbaseFeed {
+    pub fn new(ws_url: &str) -> Self {
+        Self {
+            ws_url: ws_url.to_string(),
+            status_tx: None,
+            drop_counter: None,
+        }
+    }
+
+    /// Publish connection state changes.
+    pub fn with_status(mut self, status_tx: watch::Sender<FeedStatus>) -> Self {
+        self.status_tx = Some(status_tx);
+        self
+    }
+
+    /// Count ticks dropped because the returned channel was full.
+    pub fn with_drop_counter(mut self, drop_counter: Arc<AtomicU64>) -> Self {
+        self.drop_counter = Some(drop_counter);
+        self
+    }
+
+    /// Parse a feed message. `match`/`last_match` become trades; subscription
+    /// confirmations and heartbeats are `Ok(None)`; `error` messages are errors.
+    pub fn parse(value: &str) -> Result<Option<MarketEvent>, EngineError> {
+        let msg: CoinbaseMatch =
+            serde_json::from_str(value).map_err(|e| EngineError::ParseError(e.to_string()))?;
+
+        match msg.kind.as_str() {
+            "match" | "last_match" => {}
+            "error" => {
+                return Err(EngineError::ExchangeError(
+                    msg.message.unwrap_or_else(|| value.to_string()),
+                ))
+            }
+            _ => return Ok(None),
+        }
+
+        let price = msg
+            .price
+            .parse::<f64>()
+            .map_err(|e| EngineError::ParseError(format!("Invalid price: {}", e)))?;
+        let quantity = msg
+            .size
+            .parse::<f64>()
+            .map_err(|e| EngineError::ParseError(format!("Invalid quantity: {}", e)))?;
+
+        Ok(Some(MarketEvent {
+            symbol: SymbolId::intern(&from_product_id(&msg.product_id)),
+            price,
+            quantity,
+            exchange_timestamp: parse_time_ms(&msg.time)?,
+            received_timestamp: common::time::MONOTONIC_START.elapsed().as_nanos() as u64,
+            // Maker on the buy side means the seller was the aggressor
+            is_buyer_maker: msg.side == "buy",
+            kind: EventKind::Trade,
+        }))
+    }
+}
+
+#[async_trait]
+impl FeedSource for CoinbaseFeed {
+    async fn connect(&self, symbol: &str) -> Result<mpsc::Receiver<MarketEvent>, EngineError> {
+        let product_id = to_product_id(symbol)?;
+
+        // Validate URL upfront
+        if Url::parse(&self.ws_url).is_err() {
+            return Err(EngineError::ParseError(format!(
+                "Invalid URL: {}",
+                self.ws_url
+            )));
+        }
+
+        let subscribe = serde_json::json!({
+            "type": "subscribe",
+            "product_ids": [product_id],
+            "channels": ["matches"],
+        })
+        .to_string();
+
+        let (tx, rx) = mpsc::channel::<MarketEvent>(10_000);
+        tokio::spawn(run_stream(
+            self.ws_url.clone(),
+            Subscription::Frames(vec![subscribe]),
+            CoinbaseFeed::parse,
+            tx,
+            None,
+            self.status_tx.clone(),
+            self.drop_counter.clone().unwrap_or_default(),
+        ));
+
+        Ok(rx)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_coinbase_match() {
+        let raw = r#"{"type":"match","trade_id":10,"sequence":50,"maker_order_id":"ac928c66-ca53-498f-9c13-a110027a60e8","taker_order_id":"132fb6ae-456b-4654-b4e0-d681ac05cea1","time":"2014-11-07T08:19:27.028459Z","product_id":"BTC-USD","size":"5.23512","price":"400.23","side":"sell"}"#;
+        let event = CoinbaseFeed::parse(raw)
+            .expect("Failed to parse")
+            .expect("match is a trade");
+
+        assert_eq!(event.symbol, "BTCUSDT");
+        assert_eq!(event.price, 400.23);
+        assert_eq!(event.quantity, 5.23512);
+        assert_eq!(event.exchange_timestamp, 1415348367028);
+        // Maker sold, so the buyer was the aggressor
+        assert!(!event.is_buyer_maker);
+    }
+
+    #[test]
+    fn test_parse_coinbase_control_messages() {
+        let subscriptions =
+            r#"{"type":"subscriptions","channels":[{"name":"matches","product_ids":["BTC-USD"]}]}"#;
+        assert_eq!(CoinbaseFeed::parse(subscriptions).unwrap(), None);
+
+        let heartbeat = r#"{"type":"heartbeat","sequence":90,"last_trade_id":20,"product_id":"BTC-USD","time":"2014-11-07T08:19:28.464459Z"}"#;
+        assert_eq!(CoinbaseFeed::parse(heartbeat).unwrap(), None);
+
+        let error = r#"{"type":"error","message":"Failed to subscribe","reason":"BTC-XYZ is not a valid product"}"#;
+        assert!(matches!(
+            CoinbaseFeed::parse(error),
+            Err(EngineError::ExchangeError(_))
+        ));
+    }
+
+    #[test]
+    fn test_parse_coinbase_invalid_time() {
+        let raw = r#"{"type":"match","time":"yesterday","product_id":"BTC-USD","size":"1","price":"1","side":"buy"}"#;
+        assert!(CoinbaseFeed::parse(raw).is_err());
+    }
+
+    #[test]
+    fn test_symbol_mapping() {
+        assert_eq!(to_product_id("btcusdt").unwrap(), "BTC-USD");
+        assert_eq!(to_product_id("ETHUSD").unwrap(), "ETH-USD");
+        assert!(to_product_id("USDT").is_err());
+        assert!(to_product_id("BTCEUR").is_err());
+
+        assert_eq!(from_product_id("BTC-USD"), "BTCUSDT");
+        assert_eq!(from_product_id("ETH-EUR"), "ETHEUR");
+    }
+}
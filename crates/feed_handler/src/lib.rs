@@ -1,82 +1,624 @@
 pub mod binance;
+pub mod coinbase;
 pub use binance::*;
+pub use coinbase::*;
 
-use common::{EngineError, MarketEvent};
-use futures_util::StreamExt;
+use async_trait::async_trait;
+use common::ws::stream_url;
+use common::{BookEvent, EngineError, MarketEvent};
+use futures_util::{SinkExt, StreamExt};
+use rand::Rng;
+use serde::Deserialize;
+use std::collections::{HashMap, VecDeque};
+use std::fmt;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
 use std::time::Duration;
-use tokio::sync::mpsc;
+use tokio::sync::{mpsc, watch};
 use tokio_tungstenite::{connect_async, tungstenite::protocol::Message};
 use url::Url;
 
+/// Backpressure warnings are logged on the first drop and then once per this many drops.
+const DROP_LOG_EVERY: u64 = 1000;
+
+/// Interval between client pings on an open connection.
+const PING_INTERVAL: Duration = Duration::from_secs(20);
+
+/// Reconnect if no frame (data, ack or pong) arrives for this long: the TCP connection
+/// may be half-open with no close frame ever coming.
+const IDLE_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Spacing of one-stream `SUBSCRIBE` retries after a batch is rejected. Binance
+/// futures disconnects clients sending more than 10 messages a second (pings included).
+const SUBSCRIBE_RETRY_INTERVAL: Duration = Duration::from_millis(200);
+
+/// USDT-M futures Mainnet. Testnet: `wss://stream.binancefuture.com`,
+/// spot: `wss://stream.binance.com:9443`.
+pub const FUTURES_WS_URL: &str = "wss://fstream.binance.com";
+
+/// Connection state of the websocket feed, published on the optional status channel.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FeedStatus {
+    Connected,
+    Reconnecting,
+    Disconnected,
+}
+
+impl fmt::Display for FeedStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = match self {
+            FeedStatus::Connected => "CONNECTED",
+            FeedStatus::Reconnecting => "RECONNECTING",
+            FeedStatus::Disconnected => "DISCONNECTED",
+        };
+        f.write_str(s)
+    }
+}
+
+/// An exchange market-data adapter producing normalized [`MarketEvent`]s.
+/// Implementations handle reconnects themselves; the returned channel stays open across them.
+#[async_trait]
+pub trait FeedSource: Send + Sync {
+    async fn connect(&self, symbol: &str) -> Result<mpsc::Receiver<MarketEvent>, EngineError>;
+}
+
+/// Build the trade feed for `exchange` (`binance` or `coinbase`, case-insensitive).
+pub fn feed_source(
+    exchange: &str,
+    ws_base_url: &str,
+    status_tx: Option<watch::Sender<FeedStatus>>,
+    drop_counter: Option<Arc<AtomicU64>>,
+) -> Result<Box<dyn FeedSource>, EngineError> {
+    match exchange.to_ascii_lowercase().as_str() {
+        "binance" => {
+            let mut feed = BinanceFeed::new(ws_base_url);
+            if let Some(status_tx) = status_tx {
+                feed = feed.with_status(status_tx);
+            }
+            if let Some(drop_counter) = drop_counter {
+                feed = feed.with_drop_counter(drop_counter);
+            }
+            Ok(Box::new(feed))
+        }
+        "coinbase" => {
+            let mut feed = CoinbaseFeed::new(ws_base_url);
+            if let Some(status_tx) = status_tx {
+                feed = feed.with_status(status_tx);
+            }
+            if let Some(drop_counter) = drop_counter {
+                feed = feed.with_drop_counter(drop_counter);
+            }
+            Ok(Box::new(feed))
+        }
+        other => Err(EngineError::ParseError(format!(
+            "Unsupported exchange: {}",
+            other
+        ))),
+    }
+}
+
+/// Subscribe to the Binance aggregated trade stream (`<ws_base_url>/ws/<symbol>@aggTrade`).
+/// `drop_counter`, if given, counts ticks dropped because the returned channel was full.
+/// Thin wrapper over [`BinanceFeed`], kept for existing callers.
 pub async fn connect(
+    ws_base_url: &str,
     symbol: &str,
     raw_tx: Option<mpsc::Sender<String>>,
+    status_tx: Option<watch::Sender<FeedStatus>>,
+    drop_counter: Option<Arc<AtomicU64>>,
 ) -> Result<mpsc::Receiver<MarketEvent>, EngineError> {
-    let (tx, rx) = mpsc::channel::<MarketEvent>(10_000);
-    let symbol_lower = symbol.to_lowercase();
-    let url_str = format!("wss://fstream.binance.com/ws/{}@aggTrade", symbol_lower);
+    let mut feed = BinanceFeed::new(ws_base_url);
+    if let Some(raw_tx) = raw_tx {
+        feed = feed.with_raw_tx(raw_tx);
+    }
+    if let Some(status_tx) = status_tx {
+        feed = feed.with_status(status_tx);
+    }
+    if let Some(drop_counter) = drop_counter {
+        feed = feed.with_drop_counter(drop_counter);
+    }
+    feed.connect(symbol).await
+}
+
+/// Subscribe to the partial order book stream (`<symbol>@depth<levels>@100ms`).
+/// `levels` must be one of 5, 10 or 20. Runs independently of the trade stream.
+pub async fn connect_depth(
+    ws_base_url: &str,
+    symbol: &str,
+    levels: usize,
+) -> Result<mpsc::Receiver<BookEvent>, EngineError> {
+    if ![5, 10, 20].contains(&levels) {
+        return Err(EngineError::ParseError(format!(
+            "Invalid depth levels: {} (expected 5, 10 or 20)",
+            levels
+        )));
+    }
+
+    let (tx, rx) = mpsc::channel::<BookEvent>(1_000);
+    let url_str = stream_url(
+        ws_base_url,
+        &format!("{}@depth{}@100ms", symbol.to_lowercase(), levels),
+    );
 
     // Validate URL upfront
     if Url::parse(&url_str).is_err() {
         return Err(EngineError::ParseError(format!("Invalid URL: {}", url_str)));
     }
 
-    tokio::spawn(async move {
-        let mut backoff = Duration::from_millis(100);
-        let max_backoff = Duration::from_secs(5);
+    tokio::spawn(run_stream(
+        url_str,
+        Subscription::None,
+        |text| parse_depth(text).map(Some),
+        tx,
+        None,
+        None,
+        Arc::default(),
+    ));
+
+    Ok(rx)
+}
+
+/// Subscribe to `<symbol>@aggTrade` for several symbols over one connection.
+/// Subscriptions are (re)sent after every connect; a symbol the server rejects is
+/// logged and dropped while the others keep streaming.
+pub async fn connect_multi(
+    ws_base_url: &str,
+    symbols: &[&str],
+    raw_tx: Option<mpsc::Sender<String>>,
+    status_tx: Option<watch::Sender<FeedStatus>>,
+    drop_counter: Option<Arc<AtomicU64>>,
+) -> Result<mpsc::Receiver<MarketEvent>, EngineError> {
+    if symbols.is_empty() {
+        return Err(EngineError::ParseError("No symbols to subscribe".into()));
+    }
+    if let Some(bad) = symbols
+        .iter()
+        .find(|s| s.is_empty() || !s.chars().all(|c| c.is_ascii_alphanumeric()))
+    {
+        return Err(EngineError::ParseError(format!(
+            "Invalid symbol: {:?}",
+            bad
+        )));
+    }
+
+    let (tx, rx) = mpsc::channel::<MarketEvent>(10_000);
+    let url_str = stream_url(ws_base_url, "");
+    if Url::parse(&url_str).is_err() {
+        return Err(EngineError::ParseError(format!("Invalid URL: {}", url_str)));
+    }
+    let streams = symbols
+        .iter()
+        .map(|s| format!("{}@aggTrade", s.to_lowercase()))
+        .collect();
+
+    let drops = drop_counter.unwrap_or_default();
+    tokio::spawn(run_stream(
+        url_str,
+        Subscription::Streams(streams),
+        |text| parse_trade(text).map(Some),
+        tx,
+        raw_tx,
+        status_tx,
+        drops,
+    ));
+
+    Ok(rx)
+}
+
+/// `SUBSCRIBE` request for `streams`. All streams go in one request so a reconnect
+/// stays under the message rate limit; an error ack names only the request, so a
+/// rejected batch is retried one stream at a time to find the bad one.
+fn subscribe_frame(streams: &[String], id: u64) -> String {
+    serde_json::json!({"method": "SUBSCRIBE", "params": streams, "id": id}).to_string()
+}
+
+/// Reply to a `SUBSCRIBE` request.
+#[derive(Debug, PartialEq)]
+enum SubscriptionAck {
+    Ok(u64),
+    Error { id: u64, msg: String },
+}
+
+#[derive(Deserialize)]
+struct RawAck {
+    id: u64,
+    #[serde(default)]
+    error: Option<RawAckError>,
+}
+
+#[derive(Deserialize)]
+struct RawAckError {
+    msg: String,
+}
+
+/// Returns the ack if `text` is a subscription reply rather than market data.
+fn parse_ack(text: &str) -> Option<SubscriptionAck> {
+    // Market data frames never carry an `id` field; skip the parse on the hot path
+    if !text.contains("\"id\"") {
+        return None;
+    }
+    let ack: RawAck = serde_json::from_str(text).ok()?;
+    Some(match ack.error {
+        None => SubscriptionAck::Ok(ack.id),
+        Some(err) => SubscriptionAck::Error {
+            id: ack.id,
+            msg: err.msg,
+        },
+    })
+}
+
+/// Full jitter: a uniformly random delay in `[0, backoff]`, so many clients
+/// reconnecting after the same outage don't retry in lockstep.
+fn jittered(backoff: Duration) -> Duration {
+    let max_ms = backoff.as_millis() as u64;
+    Duration::from_millis(rand::thread_rng().gen_range(0..=max_ms))
+}
+
+/// Counts a dropped event. Returns the running total when a throttled warning is due.
+fn record_drop(drops: &AtomicU64) -> Option<u64> {
+    let dropped = drops.fetch_add(1, Ordering::Relaxed) + 1;
+    (dropped - 1)
+        .is_multiple_of(DROP_LOG_EVERY)
+        .then_some(dropped)
+}
+
+/// What to send after each (re)connect; a fresh connection starts with no subscriptions.
+enum Subscription {
+    /// The URL already names the stream.
+    None,
+    /// Binance `SUBSCRIBE` request for all streams, with ack tracking.
+    Streams(Vec<String>),
+    /// Exchange-specific subscribe frames, sent as-is.
+    Frames(Vec<String>),
+}
+
+/// Reconnecting websocket read loop shared by all streams.
+/// Each text frame is parsed with `parse` and forwarded on `tx` (dropped and counted
+/// in `drops` under backpressure). `parse` returns `Ok(None)` for control messages.
+async fn run_stream<T>(
+    url_str: String,
+    mut subscription: Subscription,
+    parse: fn(&str) -> Result<Option<T>, EngineError>,
+    tx: mpsc::Sender<T>,
+    raw_tx: Option<mpsc::Sender<String>>,
+    status_tx: Option<watch::Sender<FeedStatus>>,
+    drops: Arc<AtomicU64>,
+) {
+    let mut backoff = Duration::from_millis(100);
+    let max_backoff = Duration::from_secs(5);
+
+    loop {
+        let url = Url::parse(&url_str).expect("URL already validated");
 
-        loop {
-            let url = Url::parse(&url_str).expect("URL already validated");
+        match connect_async(url).await {
+            Ok((ws_stream, _)) => {
+                tracing::info!("Connected to {}", url_str);
+                backoff = Duration::from_millis(100); // Reset backoff
+                if let Some(ref status) = status_tx {
+                    let _ = status.send(FeedStatus::Connected);
+                }
 
-            match connect_async(url).await {
-                Ok((ws_stream, _)) => {
-                    tracing::info!("Connected to Binance for {}", symbol_lower);
-                    backoff = Duration::from_millis(100); // Reset backoff
+                let (mut write, mut read) = ws_stream.split();
 
-                    let (_, mut read) = ws_stream.split();
+                // Resubscribe. A send error means a broken socket: the read loop below
+                // ends immediately and we reconnect.
+                // Request id -> streams it subscribes to
+                let mut pending: HashMap<u64, Vec<String>> = HashMap::new();
+                // Streams of a rejected batch, resubscribed one per SUBSCRIBE_RETRY_INTERVAL
+                let mut retry: VecDeque<String> = VecDeque::new();
+                let mut next_id = 1;
+                match &subscription {
+                    Subscription::None => {}
+                    Subscription::Streams(streams) if !streams.is_empty() => {
+                        let frame = Message::Text(subscribe_frame(streams, next_id));
+                        if let Err(e) = write.send(frame).await {
+                            tracing::error!("Failed to subscribe: {}", e);
+                        } else {
+                            pending.insert(next_id, streams.clone());
+                        }
+                        next_id += 1;
+                    }
+                    Subscription::Streams(_) => {}
+                    Subscription::Frames(frames) => {
+                        for frame in frames {
+                            if let Err(e) = write.send(Message::Text(frame.clone())).await {
+                                tracing::error!("Failed to send subscription: {}", e);
+                                break;
+                            }
+                        }
+                    }
+                }
 
-                    while let Some(msg) = read.next().await {
-                        match msg {
-                            Ok(Message::Text(text)) => {
-                                // If raw_tx is provided, send the raw message
-                                if let Some(ref raw_sender) = raw_tx {
-                                    let _ = raw_sender.try_send(text.clone());
+                let mut ping = tokio::time::interval(PING_INTERVAL);
+                ping.tick().await; // First tick fires immediately
+                let mut retry_pacer = tokio::time::interval(SUBSCRIBE_RETRY_INTERVAL);
+                let mut last_frame = tokio::time::Instant::now();
+
+                loop {
+                    let msg = tokio::select! {
+                        msg = read.next() => match msg {
+                            Some(msg) => msg,
+                            None => break,
+                        },
+                        _ = ping.tick() => {
+                            if let Err(e) = write.send(Message::Ping(Vec::new())).await {
+                                tracing::error!("Failed to send ping: {}", e);
+                                break;
+                            }
+                            continue;
+                        }
+                        _ = retry_pacer.tick(), if !retry.is_empty() => {
+                            let Some(stream) = retry.pop_front() else { continue };
+                            let streams = vec![stream];
+                            let frame = Message::Text(subscribe_frame(&streams, next_id));
+                            if let Err(e) = write.send(frame).await {
+                                tracing::error!("Failed to subscribe to {}: {}", streams[0], e);
+                                break;
+                            }
+                            pending.insert(next_id, streams);
+                            next_id += 1;
+                            continue;
+                        }
+                        _ = tokio::time::sleep_until(last_frame + IDLE_TIMEOUT) => {
+                            tracing::warn!(
+                                "No frames for {:?}, connection presumed dead. Reconnecting",
+                                IDLE_TIMEOUT
+                            );
+                            break;
+                        }
+                    };
+                    last_frame = tokio::time::Instant::now();
+
+                    let ack = match &msg {
+                        Ok(Message::Text(text)) if !pending.is_empty() => parse_ack(text),
+                        _ => None,
+                    };
+                    match (ack, msg) {
+                        (Some(SubscriptionAck::Ok(id)), _) => {
+                            if let Some(streams) = pending.remove(&id) {
+                                tracing::info!("Subscribed to {}", streams.join(", "));
+                            }
+                        }
+                        (Some(SubscriptionAck::Error { id, msg }), _) => {
+                            match pending.remove(&id) {
+                                Some(streams) if streams.len() > 1 => {
+                                    tracing::warn!(
+                                        "Subscription rejected: {}. Retrying streams one by one",
+                                        msg
+                                    );
+                                    retry.extend(streams);
+                                }
+                                Some(streams) => {
+                                    let stream = &streams[0];
+                                    tracing::error!(
+                                        "Subscription to {} rejected: {}. Continuing without it",
+                                        stream,
+                                        msg
+                                    );
+                                    if let Subscription::Streams(streams) = &mut subscription {
+                                        streams.retain(|s| s != stream);
+                                    }
                                 }
+                                None => {}
+                            }
+                        }
+                        (None, Ok(Message::Text(text))) => {
+                            // If raw_tx is provided, send the raw message
+                            if let Some(ref raw_sender) = raw_tx {
+                                let _ = raw_sender.try_send(text.clone());
+                            }
 
-                                match parse_trade(text.as_str()) {
-                                    Ok(event) => {
-                                        if let Err(_) = tx.try_send(event) {
-                                            tracing::warn!("dropping tick due to backpressure");
-                                            continue;
+                            match parse(text.as_str()) {
+                                Ok(None) => {}
+                                Ok(Some(event)) => {
+                                    if tx.try_send(event).is_err() {
+                                        if let Some(dropped) = record_drop(&drops) {
+                                            tracing::warn!(
+                                                "dropping tick due to backpressure ({} dropped so far)",
+                                                dropped
+                                            );
                                         }
-                                    }
-                                    Err(e) => {
-                                        tracing::warn!("Parse error: {}", e);
+                                        continue;
                                     }
                                 }
+                                Err(e) => {
+                                    tracing::warn!("Parse error: {}", e);
+                                }
                             }
-                            Ok(Message::Ping(_)) | Ok(Message::Pong(_)) => {}
-                            Ok(Message::Close(_)) => {
-                                tracing::warn!("WebSocket closed by server");
-                                break;
-                            }
-                            Err(e) => {
-                                tracing::error!("WebSocket error: {}", e);
+                        }
+                        (None, Ok(Message::Ping(payload))) => {
+                            // tungstenite already answers pings while reading; this reply is
+                            // defensive, so a missed pong on the split stream can't get us
+                            // dropped by Binance. A duplicate pong is harmless.
+                            if let Err(e) = write.send(Message::Pong(payload)).await {
+                                tracing::error!("Failed to send pong: {}", e);
                                 break;
                             }
-                            _ => {}
                         }
+                        (None, Ok(Message::Close(_))) => {
+                            tracing::warn!("WebSocket closed by server");
+                            break;
+                        }
+                        (None, Err(e)) => {
+                            tracing::error!("WebSocket error: {}", e);
+                            break;
+                        }
+                        _ => {}
                     }
                 }
-                Err(e) => {
-                    tracing::warn!("Connection failed: {}. Retrying in {:?}", e, backoff);
-                }
             }
+            Err(e) => {
+                tracing::warn!("Connection failed: {}. Retrying in up to {:?}", e, backoff);
+            }
+        }
 
-            tokio::time::sleep(backoff).await;
-            backoff = std::cmp::min(backoff * 2, max_backoff);
+        if let Some(ref status) = status_tx {
+            let _ = status.send(FeedStatus::Reconnecting);
         }
-    });
+        tokio::time::sleep(jittered(backoff)).await;
+        backoff = std::cmp::min(backoff * 2, max_backoff);
+    }
+}
 
-    Ok(rx)
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stream_url_from_base() {
+        assert_eq!(
+            stream_url(FUTURES_WS_URL, "btcusdt@aggTrade"),
+            "wss://fstream.binance.com/ws/btcusdt@aggTrade"
+        );
+        // Config-style base that already ends in /ws
+        assert_eq!(
+            stream_url("wss://stream.binancefuture.com/ws/", "btcusdt@aggTrade"),
+            "wss://stream.binancefuture.com/ws/btcusdt@aggTrade"
+        );
+        assert_eq!(
+            stream_url("wss://stream.binance.com:9443", ""),
+            "wss://stream.binance.com:9443/ws"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_connect_rejects_invalid_base_url() {
+        let result = connect("not a url", "btcusdt", None, None, None).await;
+        assert!(matches!(result, Err(EngineError::ParseError(_))));
+    }
+
+    #[test]
+    fn test_feed_source_selects_exchange() {
+        assert!(feed_source("binance", FUTURES_WS_URL, None, None).is_ok());
+        assert!(feed_source("Coinbase", COINBASE_WS_URL, None, None).is_ok());
+        assert!(matches!(
+            feed_source("kraken", FUTURES_WS_URL, None, None),
+            Err(EngineError::ParseError(_))
+        ));
+    }
+
+    #[test]
+    fn test_jittered_backoff_stays_within_bounds() {
+        for backoff in [
+            Duration::ZERO,
+            Duration::from_millis(100),
+            Duration::from_secs(5),
+        ] {
+            for _ in 0..1000 {
+                assert!(jittered(backoff) <= backoff);
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_replies_to_ping_with_pong() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let (tx, _rx) = mpsc::channel::<MarketEvent>(10);
+        let handle = tokio::spawn(run_stream(
+            format!("ws://{}/ws/btcusdt@aggTrade", addr),
+            Subscription::None,
+            |text| parse_trade(text).map(Some),
+            tx,
+            None,
+            None,
+            Arc::default(),
+        ));
+
+        let (socket, _) = listener.accept().await.unwrap();
+        let mut server = tokio_tungstenite::accept_async(socket).await.unwrap();
+        server.send(Message::Ping(b"hb".to_vec())).await.unwrap();
+
+        let reply = tokio::time::timeout(Duration::from_secs(5), server.next())
+            .await
+            .expect("no pong within 5s")
+            .unwrap()
+            .unwrap();
+        assert_eq!(reply, Message::Pong(b"hb".to_vec()));
+        handle.abort();
+    }
+
+    #[test]
+    fn test_subscribe_frame() {
+        let streams = vec![
+            "btcusdt@aggTrade".to_string(),
+            "ethusdt@aggTrade".to_string(),
+        ];
+        let frame: serde_json::Value = serde_json::from_str(&subscribe_frame(&streams, 3)).unwrap();
+        assert_eq!(
+            frame,
+            serde_json::json!({
+                "method": "SUBSCRIBE",
+                "params": ["btcusdt@aggTrade", "ethusdt@aggTrade"],
+                "id": 3
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn test_subscribes_in_one_batch_and_retries_singly_on_reject() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let symbols = ["btcusdt", "ethusdt", "bogus"];
+        let _rx = connect_multi(&format!("ws://{}", addr), &symbols, None, None, None)
+            .await
+            .unwrap();
+
+        let (socket, _) = listener.accept().await.unwrap();
+        let mut server = tokio_tungstenite::accept_async(socket).await.unwrap();
+        // Everything in one request first; once rejected, one stream per request
+        for expected_streams in [3, 1, 1, 1] {
+            let text = loop {
+                let msg = tokio::time::timeout(Duration::from_secs(5), server.next())
+                    .await
+                    .expect("no request within 5s")
+                    .unwrap()
+                    .unwrap();
+                if let Message::Text(text) = msg {
+                    break text;
+                }
+            };
+            let request: serde_json::Value = serde_json::from_str(&text).unwrap();
+            let id = request["id"].as_u64().unwrap();
+            let params = request["params"].as_array().unwrap();
+            assert_eq!(params.len(), expected_streams);
+
+            let reply = if params.iter().any(|p| p == "bogus@aggTrade") {
+                serde_json::json!({"error": {"code": 2, "msg": "Invalid request"}, "id": id})
+            } else {
+                serde_json::json!({"result": null, "id": id})
+            };
+            server.send(Message::Text(reply.to_string())).await.unwrap();
+        }
+    }
+
+    #[test]
+    fn test_parse_ack() {
+        assert_eq!(
+            parse_ack(r#"{"result":null,"id":1}"#),
+            Some(SubscriptionAck::Ok(1))
+        );
+        assert_eq!(
+            parse_ack(r#"{"error":{"code":2,"msg":"Invalid request: unknown stream"},"id":2}"#),
+            Some(SubscriptionAck::Error {
+                id: 2,
+                msg: "Invalid request: unknown stream".to_string()
+            })
+        );
+
+        // Market data is not an ack
+        let trade = r#"{"e":"aggTrade","E":1,"s":"BTCUSDT","a":1,"p":"1","q":"1","f":1,"l":1,"T":1,"m":true}"#;
+        assert_eq!(parse_ack(trade), None);
+    }
+
+    #[test]
+    fn test_record_drop_throttles_warnings() {
+        let drops = AtomicU64::new(0);
+        assert_eq!(record_drop(&drops), Some(1));
+        for _ in 1..DROP_LOG_EVERY {
+            assert_eq!(record_drop(&drops), None);
+        }
+        assert_eq!(record_drop(&drops), Some(DROP_LOG_EVERY + 1));
+        assert_eq!(drops.load(Ordering::Relaxed), DROP_LOG_EVERY + 1);
+    }
 }
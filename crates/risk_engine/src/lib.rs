@@ -1,6 +1,9 @@
-use common::{EngineError, TradeInstruction};
+use common::{EngineError, OrderType, Side, TradeInstruction};
 use once_cell::sync::Lazy;
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
 use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
 
 /// Global Kill Switch
 /// Default: false (Disarmed/Safe) - System will reject orders until explicitly armed.
@@ -23,22 +26,117 @@ pub fn is_armed() -> bool {
     TRADING_ENABLED.load(Ordering::SeqCst)
 }
 
+/// Hard limits enforced by [`RiskEngine::check`]. Shared, so they can be changed live.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct RiskLimits {
+    pub max_order_size: f64,
+    /// Same value as `[risk] max_drawdown` in the config.
+    #[serde(rename = "max_drawdown")]
+    pub max_daily_loss: f64,
+    pub max_position: f64,
+}
+
+impl RiskLimits {
+    /// All limits must be positive, finite numbers.
+    pub fn validate(&self) -> Result<(), EngineError> {
+        let limits = [
+            ("max_order_size", self.max_order_size),
+            ("max_drawdown", self.max_daily_loss),
+            ("max_position", self.max_position),
+        ];
+        for (name, value) in limits {
+            if !value.is_finite() || value <= 0.0 {
+                return Err(EngineError::RiskViolation(format!(
+                    "{} must be a positive number, got {}",
+                    name, value
+                )));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Clones share the limits and the realized-loss tally, so fills booked on another task
+/// count against the same `max_drawdown`.
+#[derive(Clone)]
 pub struct RiskEngine {
-    max_order_size: f64,
-    max_daily_loss: f64,
-    current_daily_loss: f64,
+    limits: Arc<Mutex<RiskLimits>>,
+    /// Net realized loss since start (negative while in profit).
+    current_daily_loss: Arc<Mutex<f64>>,
+    /// Max adverse move (%) of the market from a market order's signal price. 0 disables.
+    max_slippage_pct: f64,
 }
 
 impl RiskEngine {
-    pub fn new(max_order_size: f64, max_daily_loss: f64) -> Self {
-        Self {
+    pub fn new(max_order_size: f64, max_daily_loss: f64, max_position: f64) -> Self {
+        Self::with_shared_limits(Arc::new(Mutex::new(RiskLimits {
             max_order_size,
             max_daily_loss,
-            current_daily_loss: 0.0,
+            max_position,
+        })))
+    }
+
+    /// Enforce `limits` as they are at the time of each check, so updates apply live.
+    pub fn with_shared_limits(limits: Arc<Mutex<RiskLimits>>) -> Self {
+        Self {
+            limits,
+            current_daily_loss: Arc::new(Mutex::new(0.0)),
+            max_slippage_pct: 0.0,
+        }
+    }
+
+    /// Handle to the live limits.
+    pub fn limits(&self) -> Arc<Mutex<RiskLimits>> {
+        self.limits.clone()
+    }
+
+    pub fn with_max_slippage_pct(mut self, max_slippage_pct: f64) -> Self {
+        self.max_slippage_pct = max_slippage_pct;
+        self
+    }
+
+    /// Reject a market order if the market has already moved against its signal price by
+    /// more than `max_slippage_pct` (Buy: market above signal, Sell: market below).
+    /// Skipped for dry runs, non-market orders and when no market price is known yet.
+    pub fn check_slippage(
+        &self,
+        instruction: &TradeInstruction,
+        market_price: f64,
+    ) -> Result<(), EngineError> {
+        if self.max_slippage_pct <= 0.0
+            || instruction.dry_run
+            || instruction.order_type != OrderType::Market
+            || market_price <= 0.0
+            || instruction.price <= 0.0
+        {
+            return Ok(());
+        }
+
+        let adverse_move = match instruction.side {
+            Side::Buy => market_price - instruction.price,
+            Side::Sell => instruction.price - market_price,
+        };
+        let slippage_pct = adverse_move / instruction.price * 100.0;
+        if slippage_pct > self.max_slippage_pct {
+            return Err(EngineError::RiskViolation(format!(
+                "Slippage {:.3}% ({:?} signal {} vs market {}) exceeds limit {}%",
+                slippage_pct,
+                instruction.side,
+                instruction.price,
+                market_price,
+                self.max_slippage_pct
+            )));
         }
+        Ok(())
     }
 
-    pub fn check(&mut self, instruction: &TradeInstruction) -> Result<(), EngineError> {
+    /// Validate an instruction against the hard limits.
+    /// `current_position` is the signed net position before this order is filled.
+    pub fn check(
+        &mut self,
+        instruction: &TradeInstruction,
+        current_position: f64,
+    ) -> Result<(), EngineError> {
         // 1. Kill Switch
         if !is_armed() {
             return Err(EngineError::RiskViolation("System Disarmed".to_string()));
@@ -61,31 +159,215 @@ impl RiskEngine {
             return Err(EngineError::RiskViolation("Invalid Price".to_string()));
         }
 
+        let limits = *self.limits.lock();
+
         // 5. Max Order Size (Hard Rule)
-        if instruction.quantity > self.max_order_size {
+        if instruction.quantity > limits.max_order_size {
             return Err(EngineError::RiskViolation(format!(
                 "Order size {} exceeds limit {}",
-                instruction.quantity, self.max_order_size
+                instruction.quantity, limits.max_order_size
             )));
         }
 
-        // 6. Max Daily Loss (Hard Rule)
-        // Note: This is a simplified check. Real PnL tracking requires fill data.
-        // For now, we check if we've already hit the limit.
-        if self.current_daily_loss >= self.max_daily_loss {
+        // 6. Max Position (Hard Rule)
+        let signed_qty = match instruction.side {
+            Side::Buy => instruction.quantity,
+            Side::Sell => -instruction.quantity,
+        };
+        let resulting_position = current_position + signed_qty;
+        if resulting_position.abs() > limits.max_position {
+            return Err(EngineError::RiskViolation(format!(
+                "Resulting position {} exceeds limit {}",
+                resulting_position, limits.max_position
+            )));
+        }
+
+        // 7. Max Daily Loss (Hard Rule), from the realized PnL of booked fills
+        if *self.current_daily_loss.lock() >= limits.max_daily_loss {
             return Err(EngineError::RiskViolation(format!(
                 "Daily loss limit {} reached",
-                self.max_daily_loss
+                limits.max_daily_loss
             )));
         }
 
         Ok(())
     }
 
-    // Helper to update loss (to be called when fills are processed)
-    pub fn update_loss(&mut self, loss: f64) {
-        if loss > 0.0 {
-            self.current_daily_loss += loss;
+    /// Books a fill's realized PnL (net of fees). Profits offset earlier losses.
+    pub fn record_realized_pnl(&self, realized_pnl: f64) {
+        *self.current_daily_loss.lock() -= realized_pnl;
+    }
+}
+
+/// Circuit breaker over consecutive order failures (rejections, network errors).
+/// A successful order resets the count.
+pub struct FailureBreaker {
+    max_failures: u32,
+    consecutive: u32,
+}
+
+impl FailureBreaker {
+    /// `max_failures == 0` disables the breaker.
+    pub fn new(max_failures: u32) -> Self {
+        Self {
+            max_failures,
+            consecutive: 0,
+        }
+    }
+
+    /// Records a failed order. Returns true when the limit is reached; the count then
+    /// starts over so a manual restart gets a fresh budget.
+    pub fn record_failure(&mut self) -> bool {
+        if self.max_failures == 0 {
+            return false;
+        }
+        self.consecutive += 1;
+        if self.consecutive >= self.max_failures {
+            self.consecutive = 0;
+            return true;
+        }
+        false
+    }
+
+    pub fn record_success(&mut self) {
+        self.consecutive = 0;
+    }
+
+    pub fn consecutive_failures(&self) -> u32 {
+        self.consecutive
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn instruction(side: Side, quantity: f64) -> TradeInstruction {
+        TradeInstruction::market("BTCUSDT", side, 50_000.0, quantity, 0)
+    }
+
+    #[test]
+    fn test_rejects_order_above_max_order_size() {
+        arm();
+        let mut engine = RiskEngine::new(0.1, 1000.0, 1.0);
+
+        let result = engine.check(&instruction(Side::Buy, 0.5), 0.0);
+        assert!(matches!(result, Err(EngineError::RiskViolation(_))));
+
+        assert!(engine.check(&instruction(Side::Buy, 0.1), 0.0).is_ok());
+    }
+
+    #[test]
+    fn test_failure_breaker_trips_on_consecutive_failures() {
+        let mut breaker = FailureBreaker::new(3);
+        assert!(!breaker.record_failure());
+        assert!(!breaker.record_failure());
+
+        // A success in between resets the streak
+        breaker.record_success();
+        assert_eq!(breaker.consecutive_failures(), 0);
+
+        assert!(!breaker.record_failure());
+        assert!(!breaker.record_failure());
+        assert!(breaker.record_failure());
+        assert_eq!(breaker.consecutive_failures(), 0);
+
+        let mut disabled = FailureBreaker::new(0);
+        assert!((0..10).all(|_| !disabled.record_failure()));
+    }
+
+    #[test]
+    fn test_rejects_market_order_beyond_slippage() {
+        let engine = RiskEngine::new(0.1, 1000.0, 1.0).with_max_slippage_pct(0.1);
+
+        // Buy signalled at 50_000; market already 0.2% higher
+        let result = engine.check_slippage(&instruction(Side::Buy, 0.1), 50_100.0);
+        assert!(matches!(result, Err(EngineError::RiskViolation(_))));
+        assert!(engine
+            .check_slippage(&instruction(Side::Buy, 0.1), 50_040.0)
+            .is_ok());
+
+        // Favourable moves are fine; Sell is mirrored
+        assert!(engine
+            .check_slippage(&instruction(Side::Buy, 0.1), 49_000.0)
+            .is_ok());
+        let result = engine.check_slippage(&instruction(Side::Sell, 0.1), 49_900.0);
+        assert!(matches!(result, Err(EngineError::RiskViolation(_))));
+
+        // Limit orders carry their own price protection
+        let mut limit = instruction(Side::Buy, 0.1);
+        limit.order_type = OrderType::Limit;
+        assert!(engine.check_slippage(&limit, 51_000.0).is_ok());
+
+        // Disabled by default
+        let engine = RiskEngine::new(0.1, 1000.0, 1.0);
+        assert!(engine
+            .check_slippage(&instruction(Side::Buy, 0.1), 51_000.0)
+            .is_ok());
+    }
+
+    #[test]
+    fn test_rejects_order_breaching_max_position() {
+        arm();
+        let mut engine = RiskEngine::new(0.1, 1000.0, 0.2);
+
+        // Long 0.15 + Buy 0.1 => 0.25 > 0.2
+        let result = engine.check(&instruction(Side::Buy, 0.1), 0.15);
+        assert!(matches!(result, Err(EngineError::RiskViolation(_))));
+
+        // Short side is symmetric
+        let result = engine.check(&instruction(Side::Sell, 0.1), -0.15);
+        assert!(matches!(result, Err(EngineError::RiskViolation(_))));
+
+        // Reducing an open position is always allowed
+        assert!(engine.check(&instruction(Side::Sell, 0.1), 0.15).is_ok());
+    }
+
+    #[test]
+    fn test_shared_limits_apply_live() {
+        arm();
+        let mut engine = RiskEngine::new(0.1, 1000.0, 1.0);
+        assert!(engine.check(&instruction(Side::Buy, 0.5), 0.0).is_err());
+
+        engine.limits().lock().max_order_size = 0.5;
+        assert!(engine.check(&instruction(Side::Buy, 0.5), 0.0).is_ok());
+    }
+
+    #[test]
+    fn test_rejects_once_realized_loss_reaches_limit() {
+        arm();
+        let mut engine = RiskEngine::new(0.1, 100.0, 1.0);
+        let fills = engine.clone();
+
+        fills.record_realized_pnl(-80.0);
+        assert!(engine.check(&instruction(Side::Buy, 0.1), 0.0).is_ok());
+
+        fills.record_realized_pnl(-30.0);
+        assert!(engine.check(&instruction(Side::Buy, 0.1), 0.0).is_err());
+
+        // A winning trade brings the net loss back under the limit
+        fills.record_realized_pnl(20.0);
+        assert!(engine.check(&instruction(Side::Buy, 0.1), 0.0).is_ok());
+    }
+
+    #[test]
+    fn test_limits_validation() {
+        let limits = RiskLimits {
+            max_order_size: 0.1,
+            max_daily_loss: 1000.0,
+            max_position: 1.0,
+        };
+        assert!(limits.validate().is_ok());
+
+        for bad in [0.0, -1.0, f64::NAN, f64::INFINITY] {
+            let invalid = RiskLimits {
+                max_position: bad,
+                ..limits
+            };
+            assert!(matches!(
+                invalid.validate(),
+                Err(EngineError::RiskViolation(_))
+            ));
         }
     }
 }
@@ -14,16 +14,14 @@ fn main() {
     println!("cargo:rerun-if-changed=src");
 
     if let Ok(entries) = fs::read_dir(src_dir) {
-        for entry in entries {
-            if let Ok(entry) = entry {
-                let path = entry.path();
-                if path.is_file() {
-                    if let Some(stem) = path.file_stem() {
-                        let name = stem.to_string_lossy().to_string();
-                        // Exclude lib.rs and mod.rs
-                        if name != "lib" && name != "mod" {
-                            strategies.push(name.to_uppercase());
-                        }
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if path.is_file() {
+                if let Some(stem) = path.file_stem() {
+                    let name = stem.to_string_lossy().to_string();
+                    // Exclude lib.rs and mod.rs
+                    if name != "lib" && name != "mod" {
+                        strategies.push(name.to_uppercase());
                     }
                 }
             }
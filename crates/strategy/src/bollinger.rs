@@ -0,0 +1,213 @@
+use common::{Clock, ExecSignal, MarketEvent, Side, SystemClock, TradeInstruction};
+use std::collections::VecDeque;
+use std::sync::Arc;
+
+pub struct BollingerStrategy {
+    price_history: VecDeque<f64>,
+    window_size: usize,
+    k: f64,
+    position: i32, // 0 = Flat, 1 = Long, -1 = Short
+    order_quantity: f64,
+    clock: Arc<dyn Clock>,
+}
+
+impl BollingerStrategy {
+    pub fn new(window_size: usize, k: f64, order_quantity: f64) -> Self {
+        Self {
+            price_history: VecDeque::with_capacity(window_size),
+            window_size,
+            k,
+            position: 0,
+            order_quantity,
+            clock: Arc::new(SystemClock),
+        }
+    }
+
+    /// Time source for order timestamps (defaults to `SystemClock`).
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Mean and (population) standard deviation of the current window.
+    fn bands(&self) -> (f64, f64) {
+        let n = self.price_history.len() as f64;
+        let mean = self.price_history.iter().sum::<f64>() / n;
+        let variance = self
+            .price_history
+            .iter()
+            .map(|p| (p - mean).powi(2))
+            .sum::<f64>()
+            / n;
+        (mean, variance.sqrt())
+    }
+
+    fn instruction(&self, event: &MarketEvent, side: Side, now: u64) -> TradeInstruction {
+        TradeInstruction::market(event.symbol, side, event.price, self.order_quantity, now)
+    }
+}
+
+use crate::Strategy;
+
+impl Strategy for BollingerStrategy {
+    fn process_event(&mut self, event: &MarketEvent) -> Vec<ExecSignal> {
+        if !event.is_trade() {
+            return Vec::new();
+        }
+
+        // Step 1: Bands are computed from the window *before* this tick,
+        // so a breakout is measured against prior volatility.
+        let mut instruction = None;
+
+        if self.price_history.len() >= self.window_size {
+            let (mean, std_dev) = self.bands();
+            let upper = mean + self.k * std_dev;
+            let lower = mean - self.k * std_dev;
+            let now = self.clock.now_nanos();
+
+            // Step 2: Entry (breakout) & Exit (return to mean)
+            if self.position == 0 {
+                if event.price > upper {
+                    tracing::info!(
+                        "Bollinger BUY: Price {:.2} > Upper {:.2} (Mean {:.2}, Std {:.2})",
+                        event.price,
+                        upper,
+                        mean,
+                        std_dev
+                    );
+                    instruction = Some(self.instruction(event, Side::Buy, now));
+                    self.position = 1;
+                } else if event.price < lower {
+                    tracing::info!(
+                        "Bollinger SELL: Price {:.2} < Lower {:.2} (Mean {:.2}, Std {:.2})",
+                        event.price,
+                        lower,
+                        mean,
+                        std_dev
+                    );
+                    instruction = Some(self.instruction(event, Side::Sell, now));
+                    self.position = -1;
+                }
+            } else if self.position == 1 {
+                if event.price <= mean {
+                    tracing::info!(
+                        "Bollinger CLOSE LONG: Price {:.2} <= Mean {:.2}",
+                        event.price,
+                        mean
+                    );
+                    instruction = Some(self.instruction(event, Side::Sell, now));
+                    self.position = 0;
+                }
+            } else if self.position == -1 && event.price >= mean {
+                tracing::info!(
+                    "Bollinger CLOSE SHORT: Price {:.2} >= Mean {:.2}",
+                    event.price,
+                    mean
+                );
+                instruction = Some(self.instruction(event, Side::Buy, now));
+                self.position = 0;
+            }
+        }
+
+        // Step 3: Roll the window
+        if self.price_history.len() >= self.window_size {
+            self.price_history.pop_front();
+        }
+        self.price_history.push_back(event.price);
+
+        instruction.into_iter().map(ExecSignal::from).collect()
+    }
+
+    fn position(&self) -> i32 {
+        self.position
+    }
+
+    fn reset(&mut self) {
+        self.price_history.clear();
+        self.position = 0;
+    }
+
+    fn seed_from_history(&mut self, prices: &[f64]) {
+        let skip = prices.len().saturating_sub(self.window_size);
+        for &price in &prices[skip..] {
+            if self.price_history.len() >= self.window_size {
+                self.price_history.pop_front();
+            }
+            self.price_history.push_back(price);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event(price: f64) -> MarketEvent {
+        MarketEvent::trade("BTCUSDT", price, 1.0)
+    }
+
+    #[test]
+    fn test_bands() {
+        let mut strategy = BollingerStrategy::new(4, 2.0, 0.01);
+        for p in [2.0, 4.0, 4.0, 6.0] {
+            strategy.process_event(&event(p));
+        }
+
+        let (mean, std_dev) = strategy.bands();
+        assert_eq!(mean, 4.0);
+        assert!((std_dev - 2.0_f64.sqrt()).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_breakout_long_then_exit_at_mean() {
+        let mut strategy = BollingerStrategy::new(4, 2.0, 0.01);
+        for p in [99.0, 101.0, 99.0, 101.0] {
+            assert!(strategy.process_event(&event(p)).is_empty());
+        }
+
+        // Mean 100, Std 1 -> Upper 102
+        let buy = strategy
+            .process_event(&event(103.0))
+            .pop()
+            .and_then(ExecSignal::into_place)
+            .expect("Expected buy");
+        assert_eq!(buy.side, Side::Buy);
+
+        // Window [101, 99, 101, 103] -> Mean 101
+        let exit = strategy
+            .process_event(&event(100.5))
+            .pop()
+            .and_then(ExecSignal::into_place)
+            .expect("Expected exit");
+        assert_eq!(exit.side, Side::Sell);
+    }
+
+    #[test]
+    fn test_breakout_short() {
+        let mut strategy = BollingerStrategy::new(4, 2.0, 0.01);
+        for p in [99.0, 101.0, 99.0, 101.0] {
+            strategy.process_event(&event(p));
+        }
+
+        let sell = strategy
+            .process_event(&event(97.0))
+            .pop()
+            .and_then(ExecSignal::into_place)
+            .expect("Expected sell");
+        assert_eq!(sell.side, Side::Sell);
+    }
+
+    #[test]
+    fn test_reset_clears_position_and_history() {
+        let mut strategy = BollingerStrategy::new(4, 2.0, 0.01);
+        for p in [99.0, 101.0, 99.0, 101.0, 103.0] {
+            strategy.process_event(&event(p));
+        }
+        assert_eq!(strategy.position(), 1);
+
+        strategy.reset();
+        assert_eq!(strategy.position(), 0);
+        // Window must re-warm before trading again
+        assert!(strategy.process_event(&event(110.0)).is_empty());
+    }
+}
@@ -1,10 +1,13 @@
-use common::{MarketEvent, TradeInstruction};
+use common::{Clock, ExecSignal, MarketEvent, SystemClock};
 use parking_lot::Mutex;
 use rtrb::{Consumer, Producer};
+use serde::{Deserialize, Deserializer, Serialize};
+use std::collections::VecDeque;
 use std::sync::{
-    atomic::{AtomicBool, Ordering},
+    atomic::{AtomicBool, AtomicU64, Ordering},
     Arc,
 };
+use std::time::Duration;
 
 mod momentum;
 use momentum::MomentumStrategy;
@@ -15,30 +18,251 @@ use ping_pong::PingPongStrategy;
 mod liquidation;
 use liquidation::LiquidationStrategy;
 
+mod bollinger;
+use bollinger::BollingerStrategy;
+
+mod vwap_reversion;
+use vwap_reversion::VwapReversionStrategy;
+
+mod market_maker;
+use market_maker::MarketMakerStrategy;
+
 include!(concat!(env!("OUT_DIR"), "/strategies.rs"));
 
+/// Log one stale-tick warning per this many occurrences.
+const STALE_TICK_LOG_EVERY: u64 = 1000;
+
+/// Max events taken from the ring buffer per pass of the strategy loop, bounding how
+/// long a burst can delay strategy switches and resets.
+const MAX_EVENT_BATCH: usize = 64;
+
+/// How long a strategy/engine position disagreement may persist before the strategy is reset.
+const POSITION_MISMATCH_RESET_NS: u64 = 5_000_000_000;
+/// Minimum gap between position consistency checks.
+const POSITION_CHECK_INTERVAL_NS: u64 = 100_000_000;
+
 pub trait Strategy: Send {
-    fn process_event(&mut self, event: &MarketEvent) -> Option<TradeInstruction>;
+    /// Orders to place or cancel for this event, in order; usually empty or a single
+    /// `ExecSignal::Place`. Strategies driven by trades alone return nothing for book
+    /// updates (`!event.is_trade()`).
+    fn process_event(&mut self, event: &MarketEvent) -> Vec<ExecSignal>;
+
+    /// Internal position state: 0 = Flat, 1 = Long, -1 = Short.
+    /// Strategies that don't track a position keep the default.
+    fn position(&self) -> i32 {
+        0
+    }
+
+    /// Clears internal position and price/volume history so the strategy starts
+    /// Flat and re-warms its window. Parameters and signal cooldowns are kept.
+    fn reset(&mut self) {}
+
+    /// Cancels for the orders the strategy has resting, forgetting them. Sent before the
+    /// strategy is reset or replaced so its quotes aren't left orphaned on the exchange.
+    fn cancel_resting(&mut self) -> Vec<ExecSignal> {
+        Vec::new()
+    }
+
+    /// Pre-fills the price window from history (oldest first) so the strategy can trade
+    /// on its first live tick. Strategies whose window needs more than prices ignore it.
+    fn seed_from_history(&mut self, _prices: &[f64]) {}
+}
+
+/// Drops the oldest prices so at most `window` remain.
+fn trim_recent(prices: &mut VecDeque<f64>, window: usize) {
+    let excess = prices.len().saturating_sub(window);
+    prices.drain(..excess);
+}
+
+/// True if the strategy believes it holds a position the engine doesn't have
+/// (engine flat or on the other side). Strategies reporting Flat never mismatch.
+fn position_mismatch(strategy_position: i32, engine_position: f64) -> bool {
+    if strategy_position == 0 {
+        return false;
+    }
+    let engine_side = if engine_position > 0.000001 {
+        1
+    } else if engine_position < -0.000001 {
+        -1
+    } else {
+        0
+    };
+    engine_side != strategy_position.signum()
+}
+
+/// Strategy tunables shared between the engine and the strategy thread.
+/// A change (via `Arc<Mutex<StrategyParams>>`) makes `run` rebuild the active strategy.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StrategyParams {
+    pub fee_maker: f64,
+    pub fee_taker: f64,
+    pub window: usize,
+    pub threshold: f64,
+    pub price_threshold: f64,
+    pub volume_multiplier: f64,
+    pub limit_offset_ticks: f64,
+    pub use_limit_orders: bool,
+    pub order_quantity: f64,
+    pub cooldown_ns: u64,
 }
 
-fn create_strategy(
+impl Default for StrategyParams {
+    fn default() -> Self {
+        Self {
+            fee_maker: 0.0002,
+            fee_taker: 0.0005,
+            window: 50,
+            threshold: 2.0,
+            price_threshold: 10.0,
+            volume_multiplier: 3.0,
+            limit_offset_ticks: 0.0,
+            use_limit_orders: false,
+            order_quantity: 0.01,
+            cooldown_ns: 1_000_000_000,
+        }
+    }
+}
+
+/// Builds a strategy by name. Unknown names fall back to `PING_PONG`.
+pub fn create_strategy(name: &str, params: &StrategyParams) -> Box<dyn Strategy> {
+    create_strategy_with_clock(name, params, Arc::new(SystemClock))
+}
+
+/// `create_strategy` with the time source used for cooldowns, throttles and order
+/// timestamps, e.g. a `MockClock`.
+pub fn create_strategy_with_clock(
     name: &str,
-    fee_maker: f64,
-    fee_taker: f64,
-    window: usize,
-    threshold: f64,
-    price_threshold: f64,
-    volume_multiplier: f64,
+    params: &StrategyParams,
+    clock: Arc<dyn Clock>,
 ) -> Box<dyn Strategy> {
     match name {
-        "PING_PONG" => Box::new(PingPongStrategy::new(false)),
-        "MOMENTUM" => Box::new(MomentumStrategy::new(
-            window, threshold, fee_maker, fee_taker,
-        )),
-        "LIQUIDATION" => Box::new(LiquidationStrategy::new(price_threshold, volume_multiplier)),
+        "PING_PONG" => Box::new(
+            PingPongStrategy::new(
+                false,
+                params.limit_offset_ticks,
+                params.use_limit_orders,
+                params.order_quantity,
+            )
+            .with_clock(clock),
+        ),
+        "MOMENTUM" => Box::new(
+            MomentumStrategy::new(
+                params.window,
+                params.threshold,
+                params.fee_maker,
+                params.fee_taker,
+                params.order_quantity,
+                params.cooldown_ns,
+            )
+            .with_clock(clock),
+        ),
+        "LIQUIDATION" => Box::new(
+            LiquidationStrategy::new(
+                params.price_threshold,
+                params.volume_multiplier,
+                params.order_quantity,
+                params.cooldown_ns,
+            )
+            .with_clock(clock),
+        ),
+        "BOLLINGER" => Box::new(
+            BollingerStrategy::new(params.window, params.threshold, params.order_quantity)
+                .with_clock(clock),
+        ),
+        "VWAP_REVERSION" => Box::new(
+            VwapReversionStrategy::new(params.window, params.threshold, params.order_quantity)
+                .with_clock(clock),
+        ),
+        "MARKET_MAKER" => Box::new(
+            MarketMakerStrategy::new(params.order_quantity, params.threshold).with_clock(clock),
+        ),
         _ => {
             tracing::warn!("Unknown strategy: {}, defaulting to PING_PONG", name);
-            Box::new(PingPongStrategy::new(false))
+            Box::new(
+                PingPongStrategy::new(
+                    false,
+                    params.limit_offset_ticks,
+                    params.use_limit_orders,
+                    params.order_quantity,
+                )
+                .with_clock(clock),
+            )
+        }
+    }
+}
+
+/// What the strategy thread does while the market ring buffer is empty.
+///
+/// In TOML: `"spin"`, `"yield"` or `{ park_us = 50 }`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WaitStrategy {
+    /// Busy-spin: lowest latency, burns a full core.
+    Spin,
+    /// Give up the time slice but stay runnable.
+    #[default]
+    Yield,
+    /// Sleep up to this long: saves power, adds up to that much latency.
+    #[serde(rename = "park_us", deserialize_with = "duration_from_micros")]
+    Park(Duration),
+}
+
+fn duration_from_micros<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Duration, D::Error> {
+    u64::deserialize(deserializer).map(Duration::from_micros)
+}
+
+impl WaitStrategy {
+    fn wait(self) {
+        match self {
+            WaitStrategy::Spin => std::hint::spin_loop(),
+            WaitStrategy::Yield => std::thread::yield_now(),
+            WaitStrategy::Park(timeout) => std::thread::park_timeout(timeout),
+        }
+    }
+}
+
+/// Counts a tick whose tick-to-strategy latency exceeds `latency_warn_ns` (0 disables).
+/// Returns the running stale count when a throttled warning is due.
+fn record_stale_tick(
+    latency_ns: u64,
+    latency_warn_ns: u64,
+    stale_ticks: &AtomicU64,
+) -> Option<u64> {
+    if latency_warn_ns == 0 || latency_ns <= latency_warn_ns {
+        return None;
+    }
+    let stale = stale_ticks.fetch_add(1, Ordering::Relaxed) + 1;
+    (stale - 1)
+        .is_multiple_of(STALE_TICK_LOG_EVERY)
+        .then_some(stale)
+}
+
+/// Pops up to `max` queued events as one chunk and hands them to `handle` in order.
+/// Returns how many were handled (0 when the buffer is empty).
+fn drain_batch(
+    consumer: &mut Consumer<MarketEvent>,
+    max: usize,
+    handle: impl FnMut(MarketEvent),
+) -> usize {
+    let n = consumer.slots().min(max);
+    if n == 0 {
+        return 0;
+    }
+    match consumer.read_chunk(n) {
+        Ok(chunk) => {
+            chunk.into_iter().for_each(handle);
+            n
+        }
+        // `n` never exceeds the readable slots
+        Err(_) => 0,
+    }
+}
+
+/// Queue the strategy's resting-order cancels ahead of a reset or replacement.
+fn cancel_resting(strategy: &mut dyn Strategy, producer: &mut Producer<ExecSignal>) {
+    for signal in strategy.cancel_resting() {
+        if let Err(e) = producer.push(signal) {
+            tracing::warn!("Failed to push cancel: {:?}", e);
         }
     }
 }
@@ -46,35 +270,43 @@ fn create_strategy(
 /// Runs the synchronous strategy consumer loop on the current OS thread.
 /// This function MUST NOT return under normal operation; it should read from the consumer
 /// forever until `shutdown` is set to true.
+#[allow(clippy::too_many_arguments)]
 pub fn run(
     mut consumer: Consumer<MarketEvent>,
-    mut producer: Producer<TradeInstruction>,
+    mut producer: Producer<ExecSignal>,
     shutdown: Arc<AtomicBool>,
     is_running: Arc<AtomicBool>,
     active_strategy: Arc<Mutex<String>>,
+    strategy_params: Arc<Mutex<StrategyParams>>,
     _dry_run: bool,
     _disable_throttle: bool,
-    fee_maker: f64,
-    fee_taker: f64,
-    strategy_window: usize,
-    strategy_threshold: f64,
-    price_threshold: f64,
-    volume_multiplier: f64,
+    latency_warn_ns: u64,
+    stale_ticks: Arc<AtomicU64>,
+    last_latency_ns: Arc<AtomicU64>,
+    engine_position: Arc<Mutex<f64>>,
+    force_reset: Arc<AtomicBool>,
+    seed_prices: Vec<f64>,
+    wait: WaitStrategy,
 ) {
     tracing::info!("Strategy thread started");
 
     // Initialize Strategy
     let mut current_strategy_name = active_strategy.lock().clone();
-    let mut strategy = create_strategy(
-        &current_strategy_name,
-        fee_maker,
-        fee_taker,
-        strategy_window,
-        strategy_threshold,
-        price_threshold,
-        volume_multiplier,
+    let mut current_params = strategy_params.lock().clone();
+    let mut strategy = create_strategy(&current_strategy_name, &current_params);
+    // Latest trade prices, starting from the startup seed. Every rebuilt or reset strategy
+    // is re-seeded from them so it doesn't have to re-warm its window from live ticks.
+    let mut recent_prices: VecDeque<f64> = seed_prices.into_iter().collect();
+    trim_recent(&mut recent_prices, current_params.window);
+    strategy.seed_from_history(recent_prices.make_contiguous());
+    tracing::info!(
+        "Active Strategy: {} (seeded with {} prices)",
+        current_strategy_name,
+        recent_prices.len()
     );
-    tracing::info!("Active Strategy: {}", current_strategy_name);
+
+    let mut last_position_check = 0u64;
+    let mut mismatch_since: Option<u64> = None;
 
     while !shutdown.load(Ordering::Relaxed) {
         // Check if engine is running
@@ -87,37 +319,182 @@ pub fn run(
         if let Some(guard) = active_strategy.try_lock() {
             if *guard != current_strategy_name {
                 current_strategy_name = guard.clone();
-                strategy = create_strategy(
-                    &current_strategy_name,
-                    fee_maker,
-                    fee_taker,
-                    strategy_window,
-                    strategy_threshold,
-                    price_threshold,
-                    volume_multiplier,
-                );
+                cancel_resting(strategy.as_mut(), &mut producer);
+                strategy = create_strategy(&current_strategy_name, &current_params);
+                strategy.seed_from_history(recent_prices.make_contiguous());
                 tracing::info!("Switched Strategy to: {}", current_strategy_name);
             }
         }
 
-        match consumer.pop() {
-            Ok(event) => {
-                let now = common::now_nanos();
-                let _latency_ns = now.saturating_sub(event.received_timestamp);
+        // Check for parameter change
+        if let Some(guard) = strategy_params.try_lock() {
+            if *guard != current_params {
+                current_params = guard.clone();
+                cancel_resting(strategy.as_mut(), &mut producer);
+                strategy = create_strategy(&current_strategy_name, &current_params);
+                strategy.seed_from_history(recent_prices.make_contiguous());
+                tracing::info!("Strategy params updated: {:?}", current_params);
+            }
+        }
 
-                // Process Event via Strategy
-                if let Some(instr) = strategy.process_event(&event) {
-                    if let Err(e) = producer.push(instr) {
-                        tracing::warn!("Failed to push instruction: {:?}", e);
+        // Manual reset request (e.g. position closed outside the engine)
+        if force_reset.swap(false, Ordering::Relaxed) {
+            cancel_resting(strategy.as_mut(), &mut producer);
+            strategy.reset();
+            strategy.seed_from_history(recent_prices.make_contiguous());
+            mismatch_since = None;
+            tracing::info!("Strategy {} state reset", current_strategy_name);
+        }
+
+        // Reset a strategy stuck in a position the engine doesn't hold (e.g. a failed fill)
+        let now = common::now_nanos();
+        if now.saturating_sub(last_position_check) >= POSITION_CHECK_INTERVAL_NS {
+            last_position_check = now;
+            if let Some(engine_pos) = engine_position.try_lock().map(|p| *p) {
+                let strategy_pos = strategy.position();
+                if position_mismatch(strategy_pos, engine_pos) {
+                    let since = *mismatch_since.get_or_insert(now);
+                    if now.saturating_sub(since) >= POSITION_MISMATCH_RESET_NS {
+                        tracing::warn!(
+                            "Position mismatch: {} strategy position {} vs engine {}. Resetting strategy state",
+                            current_strategy_name,
+                            strategy_pos,
+                            engine_pos
+                        );
+                        cancel_resting(strategy.as_mut(), &mut producer);
+                        strategy.reset();
+                        strategy.seed_from_history(recent_prices.make_contiguous());
+                        mismatch_since = None;
                     }
+                } else {
+                    mismatch_since = None;
                 }
             }
-            Err(_) => {
-                // Buffer is empty, yield to avoid 100% CPU on dev machines
-                std::thread::yield_now();
+        }
+
+        let handled = drain_batch(&mut consumer, MAX_EVENT_BATCH, |event| {
+            let now = common::now_nanos();
+            let latency_ns = now.saturating_sub(event.received_timestamp);
+            last_latency_ns.store(latency_ns, Ordering::Relaxed);
+            if let Some(stale) = record_stale_tick(latency_ns, latency_warn_ns, &stale_ticks) {
+                tracing::warn!(
+                    "Stale tick: {}us tick-to-strategy latency (budget {}us, {} stale so far)",
+                    latency_ns / 1_000,
+                    latency_warn_ns / 1_000,
+                    stale
+                );
+            }
+
+            if event.is_trade() {
+                recent_prices.push_back(event.price);
+                trim_recent(&mut recent_prices, current_params.window);
+            }
+
+            // Process Event via Strategy
+            for mut signal in strategy.process_event(&event) {
+                if let ExecSignal::Place(instr) = &mut signal {
+                    instr.tick_timestamp = event.received_timestamp;
+                }
+                if let Err(e) = producer.push(signal) {
+                    tracing::warn!("Failed to push instruction: {:?}", e);
+                }
             }
+        });
+        if handled == 0 {
+            // Buffer is empty
+            wait.wait();
         }
     }
 
     tracing::info!("Strategy thread shutting down");
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_create_strategy_uses_order_quantity() {
+        let params = StrategyParams {
+            order_quantity: 0.05,
+            ..Default::default()
+        };
+        let mut strategy = create_strategy("PING_PONG", &params);
+
+        let event = MarketEvent::trade("BTCUSDT", 50_001.0, 1.0);
+
+        let signals = strategy.process_event(&event);
+        assert_eq!(signals.len(), 1);
+        let instr = signals[0].clone().into_place().expect("Expected a place");
+        assert_eq!(instr.quantity, 0.05);
+    }
+
+    #[test]
+    fn test_drain_batch_keeps_order() {
+        let (mut producer, mut consumer) = rtrb::RingBuffer::<MarketEvent>::new(16);
+        for i in 0..10 {
+            producer
+                .push(MarketEvent::trade("BTCUSDT", i as f64, 1.0))
+                .unwrap();
+        }
+
+        let mut seen = Vec::new();
+        let mut batches = Vec::new();
+        loop {
+            let n = drain_batch(&mut consumer, 4, |event| seen.push(event.price));
+            if n == 0 {
+                break;
+            }
+            batches.push(n);
+        }
+
+        assert_eq!(batches, vec![4, 4, 2]);
+        assert_eq!(seen, (0..10).map(|i| i as f64).collect::<Vec<_>>());
+        assert!(consumer.is_empty());
+    }
+
+    #[test]
+    fn test_trim_recent_keeps_latest_window() {
+        let mut prices: VecDeque<f64> = (0..5).map(|i| i as f64).collect();
+        trim_recent(&mut prices, 3);
+        assert_eq!(prices, VecDeque::from(vec![2.0, 3.0, 4.0]));
+        trim_recent(&mut prices, 10);
+        assert_eq!(prices.len(), 3);
+    }
+
+    #[test]
+    fn test_position_mismatch() {
+        // Stateless / flat strategies never mismatch
+        assert!(!position_mismatch(0, 0.5));
+        // Agreeing sides
+        assert!(!position_mismatch(1, 0.01));
+        assert!(!position_mismatch(-1, -0.01));
+        // Strategy thinks it's in a position the engine doesn't have
+        assert!(position_mismatch(1, 0.0));
+        assert!(position_mismatch(-1, 0.0000001));
+        assert!(position_mismatch(1, -0.01));
+    }
+
+    #[test]
+    fn test_record_stale_tick_counts_and_throttles() {
+        let stale_ticks = AtomicU64::new(0);
+
+        // Within budget / disabled
+        assert_eq!(record_stale_tick(500_000, 1_000_000, &stale_ticks), None);
+        assert_eq!(record_stale_tick(5_000_000, 0, &stale_ticks), None);
+        assert_eq!(stale_ticks.load(Ordering::Relaxed), 0);
+
+        // First stale tick logs, the rest are counted silently until the next window
+        assert_eq!(
+            record_stale_tick(2_000_000, 1_000_000, &stale_ticks),
+            Some(1)
+        );
+        for _ in 1..STALE_TICK_LOG_EVERY {
+            assert_eq!(record_stale_tick(2_000_000, 1_000_000, &stale_ticks), None);
+        }
+        assert_eq!(
+            record_stale_tick(2_000_000, 1_000_000, &stale_ticks),
+            Some(STALE_TICK_LOG_EVERY + 1)
+        );
+    }
+}
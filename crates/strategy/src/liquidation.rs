@@ -1,64 +1,109 @@
-use common::{MarketEvent, OrderType, Side, TradeInstruction};
+use common::{Clock, ExecSignal, MarketEvent, Side, SystemClock, TradeInstruction};
 use std::collections::VecDeque;
+use std::sync::Arc;
+
+/// Trades making up the "current volume" burst compared against the rolling average.
+const BURST_WINDOW: usize = 5;
 
 pub struct LiquidationStrategy {
     price_history: VecDeque<f64>,
     volume_history: VecDeque<f64>,
+    /// Running sum of `volume_history`, kept in step with pushes and pops.
+    volume_sum: f64,
+    /// Running sum of the last `BURST_WINDOW` entries of `volume_history`.
+    burst_sum: f64,
     avg_volume: f64,
     position: i32, // 0 = Flat, 1 = Long, -1 = Short
     last_signal_time: u64,
     price_threshold: f64,
     volume_multiplier: f64,
     window_size: usize,
+    order_quantity: f64,
+    cooldown_ns: u64,
+    clock: Arc<dyn Clock>,
 }
 
 impl LiquidationStrategy {
-    pub fn new(price_threshold: f64, volume_multiplier: f64) -> Self {
+    pub fn new(
+        price_threshold: f64,
+        volume_multiplier: f64,
+        order_quantity: f64,
+        cooldown_ns: u64,
+    ) -> Self {
         let window_size = 50;
         Self {
             price_history: VecDeque::with_capacity(window_size),
             volume_history: VecDeque::with_capacity(window_size),
+            volume_sum: 0.0,
+            burst_sum: 0.0,
             avg_volume: 0.0,
             position: 0,
             last_signal_time: 0,
             price_threshold,
             volume_multiplier,
             window_size,
+            order_quantity,
+            cooldown_ns,
+            clock: Arc::new(SystemClock),
+        }
+    }
+
+    /// Time source for the signal cooldown (defaults to `SystemClock`).
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Append a trade's volume to the window, updating the running sums in O(1).
+    fn push_volume(&mut self, quantity: f64) {
+        if self.volume_history.len() >= self.window_size {
+            if let Some(oldest) = self.volume_history.pop_front() {
+                self.volume_sum -= oldest;
+                // Only when the whole window fits in the burst
+                if self.volume_history.len() < BURST_WINDOW {
+                    self.burst_sum -= oldest;
+                }
+            }
+        }
+        self.volume_history.push_back(quantity);
+        self.volume_sum += quantity;
+        self.burst_sum += quantity;
+
+        let len = self.volume_history.len();
+        if len > BURST_WINDOW {
+            self.burst_sum -= self.volume_history[len - 1 - BURST_WINDOW];
         }
+        self.avg_volume = self.volume_sum / len as f64;
     }
 }
 
 use crate::Strategy;
 
 impl Strategy for LiquidationStrategy {
-    fn process_event(&mut self, event: &MarketEvent) -> Option<TradeInstruction> {
+    fn process_event(&mut self, event: &MarketEvent) -> Vec<ExecSignal> {
+        if !event.is_trade() {
+            return Vec::new();
+        }
+
         // Step 1: Track Data
         if self.price_history.len() >= self.window_size {
             self.price_history.pop_front();
         }
         self.price_history.push_back(event.price);
 
-        if self.volume_history.len() >= self.window_size {
-            self.volume_history.pop_front();
-        }
-        self.volume_history.push_back(event.quantity);
-
-        // Update rolling average volume
-        if !self.volume_history.is_empty() {
-            self.avg_volume =
-                self.volume_history.iter().sum::<f64>() / self.volume_history.len() as f64;
-        }
+        // Also updates the rolling average volume
+        self.push_volume(event.quantity);
 
         // Need full history before trading
         if self.price_history.len() < self.window_size {
-            return None;
+            return Vec::new();
         }
 
-        let now = common::now_nanos();
+        let now = self.clock.now_nanos();
 
-        // Cooldown check (1 second)
-        if now - self.last_signal_time < 1_000_000_000 {
-            return None;
+        // Cooldown check
+        if now.saturating_sub(self.last_signal_time) < self.cooldown_ns {
+            return Vec::new();
         }
 
         // Step 2: Detect Cascade
@@ -67,13 +112,13 @@ impl Strategy for LiquidationStrategy {
         let price_velocity = current_price - price_50_ticks_ago;
 
         // Current volume burst (last 5 ticks)
-        let burst_window = 5.min(self.volume_history.len());
-        let current_volume: f64 = self.volume_history.iter().rev().take(burst_window).sum();
+        let burst_window = BURST_WINDOW.min(self.volume_history.len());
+        let current_volume = self.burst_sum;
 
         let mut instruction = None;
 
         // Debug Logging (every ~100 ticks)
-        if now % 100 == 0 {
+        if now.is_multiple_of(100) {
             let recent_avg_volume = current_volume / burst_window as f64;
             tracing::info!(
                 "LIQUIDATION Debug: Velocity={:.2}, PriceThreshold={:.2}, RecentVolAvg={:.4}, RollingVolAvg={:.4}, VolMultiplier={:.1}x, Position={}",
@@ -91,15 +136,13 @@ impl Strategy for LiquidationStrategy {
                     "LIQUIDATION BUY: Velocity={:.2}, Threshold={:.2}, Volume={:.4}, AvgVol={:.4} ({}x)",
                     price_velocity, self.price_threshold, current_volume, self.avg_volume, self.volume_multiplier
                 );
-                instruction = Some(TradeInstruction {
-                    symbol: event.symbol.clone(),
-                    side: Side::Buy,
-                    price: event.price,
-                    order_type: OrderType::Market,
-                    quantity: 0.01,
-                    timestamp: now,
-                    dry_run: false,
-                });
+                instruction = Some(TradeInstruction::market(
+                    event.symbol,
+                    Side::Buy,
+                    event.price,
+                    self.order_quantity,
+                    now,
+                ));
                 self.position = 1;
                 self.last_signal_time = now;
             }
@@ -111,15 +154,13 @@ impl Strategy for LiquidationStrategy {
                     "LIQUIDATION SELL: Velocity={:.2}, Threshold={:.2}, Volume={:.4}, AvgVol={:.4} ({}x)",
                     price_velocity, self.price_threshold, current_volume, self.avg_volume, self.volume_multiplier
                 );
-                instruction = Some(TradeInstruction {
-                    symbol: event.symbol.clone(),
-                    side: Side::Sell,
-                    price: event.price,
-                    order_type: OrderType::Market,
-                    quantity: 0.01,
-                    timestamp: now,
-                    dry_run: false,
-                });
+                instruction = Some(TradeInstruction::market(
+                    event.symbol,
+                    Side::Sell,
+                    event.price,
+                    self.order_quantity,
+                    now,
+                ));
                 self.position = -1;
                 self.last_signal_time = now;
             }
@@ -141,19 +182,86 @@ impl Strategy for LiquidationStrategy {
                     recent_avg_volume, self.avg_volume
                 );
                 instruction = Some(TradeInstruction {
-                    symbol: event.symbol.clone(),
-                    side: exit_side,
-                    price: event.price,
-                    order_type: OrderType::Market,
-                    quantity: 0.01,
-                    timestamp: now,
-                    dry_run: false,
+                    reduce_only: true,
+                    ..TradeInstruction::market(
+                        event.symbol,
+                        exit_side,
+                        event.price,
+                        self.order_quantity,
+                        now,
+                    )
                 });
                 self.position = 0;
                 self.last_signal_time = now;
             }
         }
 
-        instruction
+        instruction.into_iter().map(ExecSignal::from).collect()
+    }
+
+    fn position(&self) -> i32 {
+        self.position
+    }
+
+    fn reset(&mut self) {
+        self.price_history.clear();
+        self.volume_history.clear();
+        self.volume_sum = 0.0;
+        self.burst_sum = 0.0;
+        self.avg_volume = 0.0;
+        self.position = 0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cooldown_with_clock_behind_last_signal_does_not_panic() {
+        let clock = common::MockClock::new(1_000_000_000);
+        let mut strategy =
+            LiquidationStrategy::new(10.0, 3.0, 0.01, 1_000_000_000).with_clock(Arc::new(clock));
+        // Last signal a minute ahead of the clock: every event stays inside the cooldown
+        strategy.last_signal_time = 61_000_000_000;
+
+        for i in 0..strategy.window_size + 5 {
+            let event = MarketEvent::trade("BTCUSDT", 50_000.0 + i as f64 * 10.0, 1.0);
+            assert!(strategy.process_event(&event).is_empty());
+        }
+    }
+
+    #[test]
+    fn test_running_volume_sums_match_naive() {
+        let mut strategy = LiquidationStrategy::new(10.0, 3.0, 0.01, 0);
+        // xorshift64: reproducible, no rand dependency
+        let mut state: u64 = 0x9E37_79B9_7F4A_7C15;
+        for _ in 0..10_000 {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            // Mostly small trades with occasional large prints
+            let quantity = (state % 1_000_000) as f64 / 1_000.0
+                * if state.is_multiple_of(17) { 50.0 } else { 1.0 };
+            strategy.push_volume(quantity);
+
+            let history = &strategy.volume_history;
+            let naive_avg = history.iter().sum::<f64>() / history.len() as f64;
+            let naive_burst: f64 = history.iter().rev().take(BURST_WINDOW).sum();
+            assert!((strategy.avg_volume - naive_avg).abs() < 1e-9);
+            assert!((strategy.burst_sum - naive_burst).abs() < 1e-9);
+        }
+        assert_eq!(strategy.volume_history.len(), strategy.window_size);
+    }
+
+    #[test]
+    fn test_burst_sum_with_window_smaller_than_burst() {
+        let mut strategy = LiquidationStrategy::new(10.0, 3.0, 0.01, 0);
+        strategy.window_size = 3;
+        for quantity in [1.0, 2.0, 3.0, 4.0, 5.0] {
+            strategy.push_volume(quantity);
+        }
+        assert_eq!(strategy.burst_sum, 12.0);
+        assert_eq!(strategy.avg_volume, 4.0);
     }
 }
@@ -0,0 +1,387 @@
+use common::{
+    Clock, EventKind, ExecSignal, MarketEvent, Side, SymbolId, SystemClock, TradeInstruction,
+};
+use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Inventory, in lots of `order_quantity`, at which the side adding to it stops quoting.
+const DEFAULT_MAX_INVENTORY_LOTS: f64 = 5.0;
+/// How far the inventory-increasing quote is pulled back per lot held, in bps of mid.
+const DEFAULT_SKEW_BPS_PER_LOT: f64 = 1.0;
+
+/// Quotes both sides of the book with post-only limit orders and leans them to work
+/// inventory back to flat. Needs `BookTop` events (`trading.depth_levels`).
+///
+/// Fills aren't reported to strategies, so inventory is inferred from the trade tape: a
+/// quote counts as filled once a trade prints through it. Without queue sizes we assume
+/// we are at the back of the queue, so a trade merely at our price doesn't count. A quote
+/// whose price is unchanged on a requote is left resting to keep its queue position;
+/// the others are cancelled and replaced.
+pub struct MarketMakerStrategy {
+    order_quantity: f64,
+    /// Re-quote once either side of the book moves more than this from the quoted book.
+    requote_threshold: f64,
+    max_inventory_lots: f64,
+    skew_bps_per_lot: f64,
+    /// Net inventory inferred from fills, in base units.
+    inventory: f64,
+    /// Top of book (bid, ask) the resting quotes were made from.
+    quoted_book: Option<(f64, f64)>,
+    bid: Option<Quote>,
+    ask: Option<Quote>,
+    /// Symbol the resting quotes are on.
+    symbol: Option<SymbolId>,
+    /// A quote was filled since the last requote.
+    filled: bool,
+    /// `client_order_id`s are `<id_prefix>-<n>`, unique across restarts.
+    id_prefix: String,
+    next_id: u64,
+    clock: Arc<dyn Clock>,
+}
+
+/// A resting quote and the `client_order_id` it was placed with.
+#[derive(Debug, Clone, PartialEq)]
+struct Quote {
+    price: f64,
+    client_order_id: String,
+}
+
+impl MarketMakerStrategy {
+    pub fn new(order_quantity: f64, requote_threshold: f64) -> Self {
+        let started_ms = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_millis();
+        Self {
+            order_quantity,
+            requote_threshold,
+            max_inventory_lots: DEFAULT_MAX_INVENTORY_LOTS,
+            skew_bps_per_lot: DEFAULT_SKEW_BPS_PER_LOT,
+            inventory: 0.0,
+            quoted_book: None,
+            bid: None,
+            ask: None,
+            symbol: None,
+            filled: false,
+            id_prefix: format!("MM{}", started_ms),
+            next_id: 0,
+            clock: Arc::new(SystemClock),
+        }
+    }
+
+    /// Time source for order timestamps (defaults to `SystemClock`).
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    fn inventory_lots(&self) -> f64 {
+        if self.order_quantity > 0.0 {
+            self.inventory / self.order_quantity
+        } else {
+            0.0
+        }
+    }
+
+    fn needs_requote(&self, bid: f64, ask: f64) -> bool {
+        match self.quoted_book {
+            None => true,
+            // A filled side is replenished on the next book update
+            Some(_) if self.filled => true,
+            Some((quoted_bid, quoted_ask)) => {
+                (bid - quoted_bid).abs() > self.requote_threshold
+                    || (ask - quoted_ask).abs() > self.requote_threshold
+            }
+        }
+    }
+
+    /// Move one side to `price` (or pull it for `None`), appending the cancel/place
+    /// signals. An unchanged resting quote is kept.
+    fn requote_side(
+        &mut self,
+        event: &MarketEvent,
+        side: Side,
+        price: Option<f64>,
+        signals: &mut Vec<ExecSignal>,
+    ) {
+        let resting = match side {
+            Side::Buy => self.bid.take(),
+            Side::Sell => self.ask.take(),
+        };
+        let quote = match (resting, price) {
+            (Some(quote), Some(price)) if quote.price == price => Some(quote),
+            (resting, price) => {
+                if let Some(quote) = resting {
+                    signals.push(ExecSignal::Cancel {
+                        symbol: event.symbol,
+                        client_order_id: quote.client_order_id,
+                    });
+                }
+                price.map(|price| {
+                    self.next_id += 1;
+                    let quote = Quote {
+                        price,
+                        client_order_id: format!("{}-{}", self.id_prefix, self.next_id),
+                    };
+                    signals.push(ExecSignal::Place(TradeInstruction {
+                        post_only: true,
+                        client_order_id: Some(quote.client_order_id.clone()),
+                        ..TradeInstruction::limit(
+                            event.symbol,
+                            side,
+                            price,
+                            self.order_quantity,
+                            self.clock.now_nanos(),
+                        )
+                    }));
+                    quote
+                })
+            }
+        };
+        match side {
+            Side::Buy => self.bid = quote,
+            Side::Sell => self.ask = quote,
+        }
+    }
+
+    fn on_book(&mut self, event: &MarketEvent, bid: f64, ask: f64) -> Vec<ExecSignal> {
+        if bid <= 0.0 || ask <= bid || !self.needs_requote(bid, ask) {
+            return Vec::new();
+        }
+
+        let (bid_quote, ask_quote) = skewed_quotes(
+            bid,
+            ask,
+            self.inventory_lots(),
+            self.max_inventory_lots,
+            self.skew_bps_per_lot,
+        );
+        tracing::debug!(
+            "MM requote: book {:.2}/{:.2}, inventory {:.4} -> bid {:?} ask {:?}",
+            bid,
+            ask,
+            self.inventory,
+            bid_quote,
+            ask_quote
+        );
+
+        let mut signals = Vec::with_capacity(4);
+        self.symbol = Some(event.symbol);
+        self.requote_side(event, Side::Buy, bid_quote, &mut signals);
+        self.requote_side(event, Side::Sell, ask_quote, &mut signals);
+        self.quoted_book = Some((bid, ask));
+        self.filled = false;
+        signals
+    }
+
+    fn on_trade(&mut self, event: &MarketEvent) {
+        if self.bid.as_ref().is_some_and(|q| event.price < q.price) {
+            self.inventory += self.order_quantity;
+            self.bid = None;
+            self.filled = true;
+        }
+        if self.ask.as_ref().is_some_and(|q| event.price > q.price) {
+            self.inventory -= self.order_quantity;
+            self.ask = None;
+            self.filled = true;
+        }
+    }
+}
+
+/// Bid and ask quotes for a `bid`/`ask` top of book while holding `inventory_lots`
+/// (signed). Both sides join the touch; the side that would grow the inventory is pulled
+/// back by `skew_bps_per_lot` of mid per lot held and stops quoting (`None`) at
+/// `max_lots`. The side that reduces inventory stays at the touch, so quotes never cross.
+fn skewed_quotes(
+    bid: f64,
+    ask: f64,
+    inventory_lots: f64,
+    max_lots: f64,
+    skew_bps_per_lot: f64,
+) -> (Option<f64>, Option<f64>) {
+    let mid = (bid + ask) / 2.0;
+    let skew = mid * skew_bps_per_lot / 10_000.0 * inventory_lots.abs();
+
+    if inventory_lots > 0.0 {
+        let bid_quote = (inventory_lots < max_lots).then_some(bid - skew);
+        (bid_quote, Some(ask))
+    } else if inventory_lots < 0.0 {
+        let ask_quote = (-inventory_lots < max_lots).then_some(ask + skew);
+        (Some(bid), ask_quote)
+    } else {
+        (Some(bid), Some(ask))
+    }
+}
+
+use crate::Strategy;
+
+impl Strategy for MarketMakerStrategy {
+    fn process_event(&mut self, event: &MarketEvent) -> Vec<ExecSignal> {
+        match event.kind {
+            EventKind::BookTop { bid, ask } => self.on_book(event, bid, ask),
+            EventKind::Trade => {
+                self.on_trade(event);
+                Vec::new()
+            }
+        }
+    }
+
+    fn position(&self) -> i32 {
+        if self.inventory > 0.000001 {
+            1
+        } else if self.inventory < -0.000001 {
+            -1
+        } else {
+            0
+        }
+    }
+
+    fn cancel_resting(&mut self) -> Vec<ExecSignal> {
+        let Some(symbol) = self.symbol else {
+            return Vec::new();
+        };
+        [self.bid.take(), self.ask.take()]
+            .into_iter()
+            .flatten()
+            .map(|quote| ExecSignal::Cancel {
+                symbol,
+                client_order_id: quote.client_order_id,
+            })
+            .collect()
+    }
+
+    fn reset(&mut self) {
+        self.inventory = 0.0;
+        self.quoted_book = None;
+        self.bid = None;
+        self.ask = None;
+        self.filled = false;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn book(bid: f64, ask: f64) -> MarketEvent {
+        MarketEvent {
+            symbol: "BTCUSDT".into(),
+            price: (bid + ask) / 2.0,
+            quantity: 0.0,
+            exchange_timestamp: 0,
+            received_timestamp: 0,
+            is_buyer_maker: false,
+            kind: EventKind::BookTop { bid, ask },
+        }
+    }
+
+    fn trade(price: f64) -> MarketEvent {
+        MarketEvent::trade("BTCUSDT", price, 1.0)
+    }
+
+    #[test]
+    fn test_flat_inventory_joins_the_touch() {
+        assert_eq!(
+            skewed_quotes(99.0, 101.0, 0.0, 5.0, 10.0),
+            (Some(99.0), Some(101.0))
+        );
+    }
+
+    #[test]
+    fn test_long_inventory_pulls_the_bid_back() {
+        // mid 100, 10bps per lot, 2 lots long: bid 0.2 lower, ask stays at the touch
+        let (bid, ask) = skewed_quotes(99.0, 101.0, 2.0, 5.0, 10.0);
+        assert!((bid.unwrap() - 98.8).abs() < 1e-9);
+        assert_eq!(ask, Some(101.0));
+
+        // At the limit only the reducing side quotes
+        assert_eq!(
+            skewed_quotes(99.0, 101.0, 5.0, 5.0, 10.0),
+            (None, Some(101.0))
+        );
+    }
+
+    #[test]
+    fn test_short_inventory_pulls_the_ask_back() {
+        let (bid, ask) = skewed_quotes(99.0, 101.0, -3.0, 5.0, 10.0);
+        assert_eq!(bid, Some(99.0));
+        assert!((ask.unwrap() - 101.3).abs() < 1e-9);
+
+        assert_eq!(
+            skewed_quotes(99.0, 101.0, -6.0, 5.0, 10.0),
+            (Some(99.0), None)
+        );
+    }
+
+    fn placed(signal: &ExecSignal) -> (Side, f64, String) {
+        match signal {
+            ExecSignal::Place(instr) => (
+                instr.side,
+                instr.price,
+                instr.client_order_id.clone().expect("Quote without id"),
+            ),
+            other => panic!("Expected a place, got {:?}", other),
+        }
+    }
+
+    fn cancelled(signal: &ExecSignal) -> String {
+        match signal {
+            ExecSignal::Cancel {
+                client_order_id, ..
+            } => client_order_id.clone(),
+            other => panic!("Expected a cancel, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_quotes_requotes_and_tracks_inventory() {
+        let mut mm = MarketMakerStrategy::new(0.01, 1.0);
+
+        // Trades before any book are ignored
+        assert!(mm.process_event(&trade(100.0)).is_empty());
+
+        let signals = mm.process_event(&book(99.0, 101.0));
+        assert_eq!(signals.len(), 2);
+        let (side, price, bid_id) = placed(&signals[0]);
+        assert_eq!((side, price), (Side::Buy, 99.0));
+        let (side, price, ask_id) = placed(&signals[1]);
+        assert_eq!((side, price), (Side::Sell, 101.0));
+        assert_ne!(bid_id, ask_id);
+
+        // Small move: keep resting
+        assert!(mm.process_event(&book(99.5, 101.5)).is_empty());
+
+        // A trade at our bid isn't a fill (back of the queue), through it is
+        assert!(mm.process_event(&trade(99.0)).is_empty());
+        assert_eq!(mm.position(), 0);
+        assert!(mm.process_event(&trade(98.9)).is_empty());
+        assert_eq!(mm.position(), 1);
+
+        // Filled bid replaced with a skewed one; the unchanged ask keeps its place
+        let signals = mm.process_event(&book(99.0, 101.0));
+        assert_eq!(signals.len(), 1);
+        let (side, price, skewed_id) = placed(&signals[0]);
+        assert_eq!(side, Side::Buy);
+        assert!((price - 98.99).abs() < 1e-9);
+
+        // Book moves away: both resting quotes are cancelled before replacing
+        let signals = mm.process_event(&book(105.0, 107.0));
+        assert_eq!(signals.len(), 4);
+        assert_eq!(cancelled(&signals[0]), skewed_id);
+        let (side, _, bid_id) = placed(&signals[1]);
+        assert_eq!(side, Side::Buy);
+        assert_eq!(cancelled(&signals[2]), ask_id);
+        let (side, price, ask_id) = placed(&signals[3]);
+        assert_eq!((side, price), (Side::Sell, 107.0));
+
+        // Pulled before a reset or strategy switch
+        let signals = mm.cancel_resting();
+        assert_eq!(signals.len(), 2);
+        assert_eq!(cancelled(&signals[0]), bid_id);
+        assert_eq!(cancelled(&signals[1]), ask_id);
+        assert!(mm.cancel_resting().is_empty());
+
+        mm.reset();
+        assert_eq!(mm.position(), 0);
+    }
+}
@@ -1,5 +1,6 @@
-use common::{MarketEvent, OrderType, Side, TradeInstruction};
+use common::{Clock, ExecSignal, MarketEvent, Side, SystemClock, TradeInstruction};
 use std::collections::VecDeque;
+use std::sync::Arc;
 
 pub struct MomentumStrategy {
     price_history: VecDeque<f64>,
@@ -9,10 +10,20 @@ pub struct MomentumStrategy {
     last_signal_time: u64,
     _fee_maker: f64,
     fee_taker: f64,
+    order_quantity: f64,
+    cooldown_ns: u64,
+    clock: Arc<dyn Clock>,
 }
 
 impl MomentumStrategy {
-    pub fn new(window_size: usize, threshold: f64, fee_maker: f64, fee_taker: f64) -> Self {
+    pub fn new(
+        window_size: usize,
+        threshold: f64,
+        fee_maker: f64,
+        fee_taker: f64,
+        order_quantity: f64,
+        cooldown_ns: u64,
+    ) -> Self {
         Self {
             price_history: VecDeque::with_capacity(window_size),
             window_size,
@@ -21,14 +32,27 @@ impl MomentumStrategy {
             last_signal_time: 0,
             _fee_maker: fee_maker,
             fee_taker,
+            order_quantity,
+            cooldown_ns,
+            clock: Arc::new(SystemClock),
         }
     }
+
+    /// Time source for the signal cooldown (defaults to `SystemClock`).
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = clock;
+        self
+    }
 }
 
 use crate::Strategy;
 
 impl Strategy for MomentumStrategy {
-    fn process_event(&mut self, event: &MarketEvent) -> Option<TradeInstruction> {
+    fn process_event(&mut self, event: &MarketEvent) -> Vec<ExecSignal> {
+        if !event.is_trade() {
+            return Vec::new();
+        }
+
         // Step 1: Add price to history
         if self.price_history.len() >= self.window_size {
             self.price_history.pop_front();
@@ -37,17 +61,17 @@ impl Strategy for MomentumStrategy {
 
         // Step 2: Calculate velocity (only if history is full)
         if self.price_history.len() < self.window_size {
-            return None;
+            return Vec::new();
         }
 
         let current_price = event.price;
         let oldest_price = *self.price_history.front().unwrap();
         let velocity = current_price - oldest_price;
-        let now = common::now_nanos();
+        let now = self.clock.now_nanos();
 
-        // Step 5 (Safety): Cooldown check (1 second = 1_000_000_000 ns)
-        if now - self.last_signal_time < 1_000_000_000 {
-            return None;
+        // Step 5 (Safety): Cooldown check
+        if now.saturating_sub(self.last_signal_time) < self.cooldown_ns {
+            return Vec::new();
         }
 
         let mut instruction = None;
@@ -58,7 +82,7 @@ impl Strategy for MomentumStrategy {
         let effective_threshold = self.threshold + fee_cost;
 
         // Debug Logging (Throttle to every 100 ticks approx)
-        if now % 100 == 0 {
+        if now.is_multiple_of(100) {
             tracing::info!(
                 "Momentum Debug: Velocity={:.2}, Threshold={:.2} (Base={:.2} + Fee={:.2})",
                 velocity,
@@ -80,15 +104,13 @@ impl Strategy for MomentumStrategy {
                     self.threshold,
                     fee_cost
                 );
-                instruction = Some(TradeInstruction {
-                    symbol: event.symbol.clone(),
-                    side: Side::Buy,
-                    price: event.price,
-                    order_type: OrderType::Market,
-                    quantity: 0.01, // Fixed quantity for now
-                    timestamp: now,
-                    dry_run: false, // Default to false or pass in config if needed
-                });
+                instruction = Some(TradeInstruction::market(
+                    event.symbol,
+                    Side::Buy,
+                    event.price,
+                    self.order_quantity,
+                    now,
+                ));
                 self.position = 1;
                 self.last_signal_time = now;
             } else if velocity < -effective_threshold {
@@ -100,15 +122,13 @@ impl Strategy for MomentumStrategy {
                     self.threshold,
                     fee_cost
                 );
-                instruction = Some(TradeInstruction {
-                    symbol: event.symbol.clone(),
-                    side: Side::Sell,
-                    price: event.price,
-                    order_type: OrderType::Market,
-                    quantity: 0.01,
-                    timestamp: now,
-                    dry_run: false,
-                });
+                instruction = Some(TradeInstruction::market(
+                    event.symbol,
+                    Side::Sell,
+                    event.price,
+                    self.order_quantity,
+                    now,
+                ));
                 self.position = -1;
                 self.last_signal_time = now;
             }
@@ -117,13 +137,14 @@ impl Strategy for MomentumStrategy {
             if velocity < 0.0 {
                 tracing::info!("Momentum CLOSE LONG: Velocity {:.2} < 0", velocity);
                 instruction = Some(TradeInstruction {
-                    symbol: event.symbol.clone(),
-                    side: Side::Sell, // Close Long by Selling
-                    price: event.price,
-                    order_type: OrderType::Market,
-                    quantity: 0.01,
-                    timestamp: now,
-                    dry_run: false,
+                    reduce_only: true,
+                    ..TradeInstruction::market(
+                        event.symbol,
+                        Side::Sell, // Close Long by Selling
+                        event.price,
+                        self.order_quantity,
+                        now,
+                    )
                 });
                 self.position = 0;
                 self.last_signal_time = now;
@@ -133,19 +154,135 @@ impl Strategy for MomentumStrategy {
             if velocity > 0.0 {
                 tracing::info!("Momentum CLOSE SHORT: Velocity {:.2} > 0", velocity);
                 instruction = Some(TradeInstruction {
-                    symbol: event.symbol.clone(),
-                    side: Side::Buy, // Close Short by Buying
-                    price: event.price,
-                    order_type: OrderType::Market,
-                    quantity: 0.01,
-                    timestamp: now,
-                    dry_run: false,
+                    reduce_only: true,
+                    ..TradeInstruction::market(
+                        event.symbol,
+                        Side::Buy, // Close Short by Buying
+                        event.price,
+                        self.order_quantity,
+                        now,
+                    )
                 });
                 self.position = 0;
                 self.last_signal_time = now;
             }
         }
 
-        instruction
+        instruction.into_iter().map(ExecSignal::from).collect()
+    }
+
+    fn position(&self) -> i32 {
+        self.position
+    }
+
+    fn reset(&mut self) {
+        self.price_history.clear();
+        self.position = 0;
+    }
+
+    fn seed_from_history(&mut self, prices: &[f64]) {
+        let skip = prices.len().saturating_sub(self.window_size);
+        for &price in &prices[skip..] {
+            if self.price_history.len() >= self.window_size {
+                self.price_history.pop_front();
+            }
+            self.price_history.push_back(price);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cooldown_with_clock_behind_last_signal_does_not_panic() {
+        let clock = common::MockClock::new(1_000_000_000);
+        let mut strategy = MomentumStrategy::new(5, 1.0, 0.0002, 0.0005, 0.01, 1_000_000_000)
+            .with_clock(Arc::new(clock.clone()));
+        // Last signal a minute ahead of the clock: the elapsed time saturates to 0
+        strategy.last_signal_time = 61_000_000_000;
+
+        let trade = |i: u32| MarketEvent::trade("BTCUSDT", 50_000.0 + i as f64 * 100.0, 1.0);
+        for i in 0..10 {
+            assert!(strategy.process_event(&trade(i)).is_empty());
+        }
+
+        // Signals resume once the clock passes the cooldown
+        clock.set(62_000_000_000);
+        let entry = strategy
+            .process_event(&trade(10))
+            .pop()
+            .and_then(ExecSignal::into_place)
+            .expect("Expected an entry after the cooldown");
+        assert_eq!(entry.side, Side::Buy);
+    }
+
+    #[test]
+    fn test_close_leg_is_reduce_only() {
+        let mut strategy = MomentumStrategy::new(3, 1.0, 0.0, 0.0, 0.01, 0);
+        let trade = |price: f64| MarketEvent::trade("BTCUSDT", price, 1.0);
+
+        assert!(strategy.process_event(&trade(100.0)).is_empty());
+        assert!(strategy.process_event(&trade(110.0)).is_empty());
+        let entry = strategy
+            .process_event(&trade(120.0))
+            .pop()
+            .and_then(ExecSignal::into_place)
+            .expect("entry");
+        assert_eq!(entry.side, Side::Buy);
+        assert!(!entry.reduce_only);
+
+        let exit = strategy
+            .process_event(&trade(100.0))
+            .pop()
+            .and_then(ExecSignal::into_place)
+            .expect("exit");
+        assert_eq!(exit.side, Side::Sell);
+        assert!(exit.reduce_only);
+    }
+
+    #[test]
+    fn test_seeded_strategy_trades_on_first_live_event() {
+        let mut strategy = MomentumStrategy::new(3, 1.0, 0.0, 0.0, 0.01, 0);
+        strategy.seed_from_history(&[90.0, 100.0, 105.0, 110.0]);
+
+        let event = MarketEvent::trade("BTCUSDT", 120.0, 1.0);
+        let entry = strategy
+            .process_event(&event)
+            .pop()
+            .and_then(ExecSignal::into_place)
+            .expect("entry on first tick");
+        assert_eq!(entry.side, Side::Buy);
+    }
+
+    #[test]
+    fn test_cooldown_elapses_on_mock_clock() {
+        let clock = common::MockClock::new(10_000_000_000);
+        let mut strategy = MomentumStrategy::new(3, 1.0, 0.0, 0.0, 0.01, 1_000_000_000)
+            .with_clock(Arc::new(clock.clone()));
+        let trade = |price: f64| MarketEvent::trade("BTCUSDT", price, 1.0);
+
+        assert!(strategy.process_event(&trade(100.0)).is_empty());
+        assert!(strategy.process_event(&trade(110.0)).is_empty());
+        let entry = strategy
+            .process_event(&trade(120.0))
+            .pop()
+            .and_then(ExecSignal::into_place)
+            .expect("entry");
+        assert_eq!(entry.timestamp, 10_000_000_000);
+
+        // Exit signal inside the cooldown is suppressed
+        clock.advance(std::time::Duration::from_millis(999));
+        assert!(strategy.process_event(&trade(100.0)).is_empty());
+
+        clock.advance(std::time::Duration::from_millis(1));
+        let exit = strategy
+            .process_event(&trade(90.0))
+            .pop()
+            .and_then(ExecSignal::into_place)
+            .expect("exit");
+        assert_eq!(exit.side, Side::Sell);
+        assert_eq!(exit.timestamp, 11_000_000_000);
     }
 }
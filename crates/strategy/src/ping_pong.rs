@@ -1,40 +1,81 @@
-use common::{MarketEvent, OrderType, Side, TradeInstruction};
-use std::time::{Duration, Instant};
+use common::{Clock, ExecSignal, MarketEvent, OrderType, Side, SystemClock, TradeInstruction};
+use std::sync::Arc;
+
+/// Minimum gap between orders.
+const THROTTLE_NS: u64 = 10_000_000_000;
 
 pub struct PingPongStrategy {
-    last_trade_time: Instant,
+    /// Clock time of the last order; `None` until the first one.
+    last_trade_time: Option<u64>,
     next_side: Side,
     dry_run: bool,
+    /// Price offset from the last trade when quoting limit orders.
+    offset_ticks: f64,
+    /// Emit `Limit` orders (maker) instead of `Market` orders (taker).
+    use_limit: bool,
+    order_quantity: f64,
+    clock: Arc<dyn Clock>,
 }
 
 impl PingPongStrategy {
-    pub fn new(dry_run: bool) -> Self {
+    pub fn new(dry_run: bool, offset_ticks: f64, use_limit: bool, order_quantity: f64) -> Self {
         Self {
-            last_trade_time: Instant::now() - Duration::from_secs(20),
+            last_trade_time: None,
             next_side: Side::Buy,
             dry_run,
+            offset_ticks,
+            use_limit,
+            order_quantity,
+            clock: Arc::new(SystemClock),
         }
     }
+
+    /// Time source for the order throttle (defaults to `SystemClock`).
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = clock;
+        self
+    }
 }
 
 use crate::Strategy;
 
 impl Strategy for PingPongStrategy {
-    fn process_event(&mut self, event: &MarketEvent) -> Option<TradeInstruction> {
-        let throttle_passed = self.last_trade_time.elapsed() > Duration::from_secs(10);
+    fn process_event(&mut self, event: &MarketEvent) -> Vec<ExecSignal> {
+        if !event.is_trade() {
+            return Vec::new();
+        }
+
+        let now = self.clock.now_nanos();
+        let throttle_passed = self
+            .last_trade_time
+            .is_none_or(|last| now.saturating_sub(last) > THROTTLE_NS);
 
         if event.price > 50_000.0 && throttle_passed {
+            // Limit mode: Buy below / Sell above the last trade to rest on the book
+            let (order_type, price) = if self.use_limit {
+                let price = match self.next_side {
+                    Side::Buy => event.price - self.offset_ticks,
+                    Side::Sell => event.price + self.offset_ticks,
+                };
+                (OrderType::Limit, price)
+            } else {
+                (OrderType::Market, event.price)
+            };
+
             let instr = TradeInstruction {
-                symbol: event.symbol.clone(),
-                side: self.next_side,
-                order_type: OrderType::Market,
-                price: event.price,
-                quantity: 0.01,
-                timestamp: common::now_nanos(),
+                order_type,
                 dry_run: self.dry_run,
+                post_only: self.use_limit,
+                ..TradeInstruction::market(
+                    event.symbol,
+                    self.next_side,
+                    price,
+                    self.order_quantity,
+                    now,
+                )
             };
 
-            self.last_trade_time = Instant::now();
+            self.last_trade_time = Some(now);
 
             // Toggle side
             self.next_side = match self.next_side {
@@ -44,9 +85,65 @@ impl Strategy for PingPongStrategy {
 
             tracing::info!("Strategy: Switched next side to {:?}", self.next_side);
 
-            Some(instr)
+            vec![instr.into()]
         } else {
-            None
+            Vec::new()
         }
     }
+
+    fn reset(&mut self) {
+        self.next_side = Side::Buy;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use common::MockClock;
+    use std::time::Duration;
+
+    fn event(price: f64) -> MarketEvent {
+        MarketEvent::trade("BTCUSDT", price, 1.0)
+    }
+
+    #[test]
+    fn test_limit_mode_offsets_price() {
+        let clock = MockClock::new(0);
+        let mut strategy =
+            PingPongStrategy::new(true, 5.0, true, 0.01).with_clock(Arc::new(clock.clone()));
+
+        let buy = strategy
+            .process_event(&event(50_100.0))
+            .pop()
+            .and_then(ExecSignal::into_place)
+            .expect("Expected buy");
+        assert_eq!(buy.side, Side::Buy);
+        assert_eq!(buy.order_type, OrderType::Limit);
+        assert_eq!(buy.price, 50_095.0);
+
+        // Throttled until 10s have passed on the strategy's clock
+        assert!(strategy.process_event(&event(50_100.0)).is_empty());
+        clock.advance(Duration::from_secs(11));
+        let sell = strategy
+            .process_event(&event(50_100.0))
+            .pop()
+            .and_then(ExecSignal::into_place)
+            .expect("Expected sell");
+        assert_eq!(sell.side, Side::Sell);
+        assert_eq!(sell.order_type, OrderType::Limit);
+        assert_eq!(sell.price, 50_105.0);
+    }
+
+    #[test]
+    fn test_market_mode_uses_trade_price() {
+        let mut strategy = PingPongStrategy::new(true, 5.0, false, 0.01);
+
+        let instr = strategy
+            .process_event(&event(50_100.0))
+            .pop()
+            .and_then(ExecSignal::into_place)
+            .expect("Expected buy");
+        assert_eq!(instr.order_type, OrderType::Market);
+        assert_eq!(instr.price, 50_100.0);
+    }
 }
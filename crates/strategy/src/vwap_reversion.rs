@@ -0,0 +1,213 @@
+use common::{Clock, ExecSignal, MarketEvent, Side, SystemClock, TradeInstruction};
+use std::collections::VecDeque;
+use std::sync::Arc;
+
+pub struct VwapReversionStrategy {
+    trade_history: VecDeque<(f64, f64)>, // (price, quantity)
+    window_size: usize,
+    deviation_pct: f64,
+    position: i32, // 0 = Flat, 1 = Long, -1 = Short
+    price_volume_sum: f64,
+    volume_sum: f64,
+    order_quantity: f64,
+    clock: Arc<dyn Clock>,
+}
+
+impl VwapReversionStrategy {
+    pub fn new(window_size: usize, deviation_pct: f64, order_quantity: f64) -> Self {
+        Self {
+            trade_history: VecDeque::with_capacity(window_size),
+            window_size,
+            deviation_pct,
+            position: 0,
+            price_volume_sum: 0.0,
+            volume_sum: 0.0,
+            order_quantity,
+            clock: Arc::new(SystemClock),
+        }
+    }
+
+    /// Time source for order timestamps (defaults to `SystemClock`).
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    fn vwap(&self) -> Option<f64> {
+        if self.volume_sum > 0.0 {
+            Some(self.price_volume_sum / self.volume_sum)
+        } else {
+            None
+        }
+    }
+
+    fn instruction(&self, event: &MarketEvent, side: Side, now: u64) -> TradeInstruction {
+        TradeInstruction::market(event.symbol, side, event.price, self.order_quantity, now)
+    }
+}
+
+use crate::Strategy;
+
+impl Strategy for VwapReversionStrategy {
+    fn process_event(&mut self, event: &MarketEvent) -> Vec<ExecSignal> {
+        if !event.is_trade() {
+            return Vec::new();
+        }
+
+        // Step 1: Track rolling price * volume
+        if self.trade_history.len() >= self.window_size {
+            if let Some((price, qty)) = self.trade_history.pop_front() {
+                self.price_volume_sum -= price * qty;
+                self.volume_sum -= qty;
+            }
+        }
+        self.trade_history.push_back((event.price, event.quantity));
+        self.price_volume_sum += event.price * event.quantity;
+        self.volume_sum += event.quantity;
+
+        // Need full history before trading
+        if self.trade_history.len() < self.window_size {
+            return Vec::new();
+        }
+
+        let Some(vwap) = self.vwap() else {
+            return Vec::new();
+        };
+        let band = vwap * (self.deviation_pct / 100.0);
+        let now = self.clock.now_nanos();
+
+        let mut instruction = None;
+
+        // Step 2: Entry (fade the deviation) & Exit (reversion to VWAP)
+        if self.position == 0 {
+            if event.price < vwap - band {
+                tracing::info!(
+                    "VWAP BUY: Price {:.2} < VWAP {:.2} - {:.2}%",
+                    event.price,
+                    vwap,
+                    self.deviation_pct
+                );
+                instruction = Some(self.instruction(event, Side::Buy, now));
+                self.position = 1;
+            } else if event.price > vwap + band {
+                tracing::info!(
+                    "VWAP SELL: Price {:.2} > VWAP {:.2} + {:.2}%",
+                    event.price,
+                    vwap,
+                    self.deviation_pct
+                );
+                instruction = Some(self.instruction(event, Side::Sell, now));
+                self.position = -1;
+            }
+        } else if self.position == 1 {
+            // Exit Long once price is back at (or above) the mean
+            if event.price >= vwap {
+                tracing::info!(
+                    "VWAP CLOSE LONG: Price {:.2} >= VWAP {:.2}",
+                    event.price,
+                    vwap
+                );
+                instruction = Some(self.instruction(event, Side::Sell, now));
+                self.position = 0;
+            }
+        } else if self.position == -1 {
+            // Exit Short once price is back at (or below) the mean
+            if event.price <= vwap {
+                tracing::info!(
+                    "VWAP CLOSE SHORT: Price {:.2} <= VWAP {:.2}",
+                    event.price,
+                    vwap
+                );
+                instruction = Some(self.instruction(event, Side::Buy, now));
+                self.position = 0;
+            }
+        }
+
+        instruction.into_iter().map(ExecSignal::from).collect()
+    }
+
+    fn position(&self) -> i32 {
+        self.position
+    }
+
+    fn reset(&mut self) {
+        self.trade_history.clear();
+        self.price_volume_sum = 0.0;
+        self.volume_sum = 0.0;
+        self.position = 0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use common::EventKind;
+
+    fn event(price: f64, quantity: f64) -> MarketEvent {
+        MarketEvent::trade("BTCUSDT", price, quantity)
+    }
+
+    #[test]
+    fn test_vwap_is_volume_weighted() {
+        let mut strategy = VwapReversionStrategy::new(2, 1.0, 0.01);
+        strategy.process_event(&event(100.0, 1.0));
+        strategy.process_event(&event(200.0, 3.0));
+
+        assert_eq!(strategy.vwap(), Some(175.0));
+
+        // Oldest trade rolls out of the window
+        strategy.process_event(&event(300.0, 1.0));
+        assert_eq!(strategy.vwap(), Some(225.0));
+    }
+
+    #[test]
+    fn test_buy_below_vwap_then_exit_on_reversion() {
+        let mut strategy = VwapReversionStrategy::new(5, 1.0, 0.01);
+        for _ in 0..4 {
+            assert!(strategy.process_event(&event(100.0, 1.0)).is_empty());
+        }
+
+        // VWAP = 98.0, band = 0.98 -> 90 is well below
+        let buy = strategy
+            .process_event(&event(90.0, 1.0))
+            .pop()
+            .and_then(ExecSignal::into_place)
+            .expect("Expected buy");
+        assert_eq!(buy.side, Side::Buy);
+
+        let sell = strategy
+            .process_event(&event(100.0, 1.0))
+            .pop()
+            .and_then(ExecSignal::into_place)
+            .expect("Expected exit");
+        assert_eq!(sell.side, Side::Sell);
+    }
+
+    #[test]
+    fn test_sell_above_vwap() {
+        let mut strategy = VwapReversionStrategy::new(5, 1.0, 0.01);
+        for _ in 0..4 {
+            strategy.process_event(&event(100.0, 1.0));
+        }
+
+        let sell = strategy
+            .process_event(&event(110.0, 1.0))
+            .pop()
+            .and_then(ExecSignal::into_place)
+            .expect("Expected sell");
+        assert_eq!(sell.side, Side::Sell);
+    }
+
+    #[test]
+    fn test_ignores_book_updates() {
+        let mut strategy = VwapReversionStrategy::new(2, 1.0, 0.01);
+        let mut book = event(100.0, 0.0);
+        book.kind = EventKind::BookTop {
+            bid: 99.0,
+            ask: 101.0,
+        };
+
+        assert!(strategy.process_event(&book).is_empty());
+        assert_eq!(strategy.vwap(), None);
+    }
+}
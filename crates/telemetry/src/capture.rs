@@ -0,0 +1,155 @@
+use parking_lot::Mutex;
+use serde::Serialize;
+use std::collections::VecDeque;
+use std::fmt::Write as _;
+use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tokio::sync::broadcast;
+use tracing::field::{Field, Visit};
+use tracing::{Event, Subscriber};
+use tracing_subscriber::layer::{Context, Layer};
+
+/// Recent log lines shown on the dashboard (`/api/logs`), newest last.
+pub type LogBuffer = Arc<Mutex<VecDeque<String>>>;
+
+/// Max lines kept in a `LogBuffer`; the oldest are dropped first.
+pub const LOG_BUFFER_CAPACITY: usize = 200;
+
+/// Lines a slow `/api/logs/stream` client may fall behind before it starts missing some.
+pub const LOG_STREAM_CAPACITY: usize = 1024;
+
+pub fn new_log_buffer() -> LogBuffer {
+    Arc::new(Mutex::new(VecDeque::with_capacity(LOG_BUFFER_CAPACITY)))
+}
+
+/// Live feed of log lines as they are captured (buffer lines are `LEVEL message`).
+pub fn new_log_stream() -> broadcast::Sender<LogLine> {
+    broadcast::channel(LOG_STREAM_CAPACITY).0
+}
+
+/// One dashboard log line, as pushed to `/api/logs/stream` subscribers.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct LogLine {
+    pub ts_ms: u64,
+    pub level: String,
+    pub message: String,
+}
+
+impl LogLine {
+    pub fn now(level: &str, message: String) -> Self {
+        Self {
+            ts_ms: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_millis() as u64,
+            level: level.to_string(),
+            message,
+        }
+    }
+}
+
+/// Append a line, evicting the oldest once at capacity.
+pub fn push_log(buffer: &LogBuffer, line: String) {
+    let mut logs = buffer.lock();
+    if logs.len() >= LOG_BUFFER_CAPACITY {
+        logs.pop_front();
+    }
+    logs.push_back(line);
+}
+
+/// Copies every event it sees into a `LogBuffer` as `LEVEL target: message k=v`, and
+/// publishes it on the log stream. `init` registers it behind a WARN filter.
+pub struct LogCaptureLayer {
+    buffer: LogBuffer,
+    stream: broadcast::Sender<LogLine>,
+}
+
+impl LogCaptureLayer {
+    pub fn new(buffer: LogBuffer, stream: broadcast::Sender<LogLine>) -> Self {
+        Self { buffer, stream }
+    }
+}
+
+impl<S: Subscriber> Layer<S> for LogCaptureLayer {
+    fn on_event(&self, event: &Event<'_>, _ctx: Context<'_, S>) {
+        let meta = event.metadata();
+        let mut visitor = LineVisitor::default();
+        event.record(&mut visitor);
+        let line = LogLine::now(
+            meta.level().as_str(),
+            format!("{}: {}{}", meta.target(), visitor.message, visitor.fields),
+        );
+        push_log(&self.buffer, format!("{} {}", line.level, line.message));
+        // No subscribers is fine
+        let _ = self.stream.send(line);
+    }
+}
+
+#[derive(Default)]
+struct LineVisitor {
+    message: String,
+    fields: String,
+}
+
+impl Visit for LineVisitor {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        if field.name() == "message" {
+            self.message.push_str(value);
+        } else {
+            let _ = write!(self.fields, " {}={}", field.name(), value);
+        }
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+        if field.name() == "message" {
+            let _ = write!(self.message, "{:?}", value);
+        } else {
+            let _ = write!(self.fields, " {}={:?}", field.name(), value);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tracing_subscriber::prelude::*;
+
+    #[test]
+    fn test_capture_layer_formats_events() {
+        let buffer = new_log_buffer();
+        let stream = new_log_stream();
+        let mut rx = stream.subscribe();
+        let subscriber =
+            tracing_subscriber::registry().with(LogCaptureLayer::new(buffer.clone(), stream));
+
+        tracing::subscriber::with_default(subscriber, || {
+            tracing::warn!(target: "feed_handler", code = 1006, "Feed disconnected: {}", "eof");
+        });
+
+        let logs = buffer.lock();
+        assert_eq!(
+            logs.back().map(String::as_str),
+            Some("WARN feed_handler: Feed disconnected: eof code=1006")
+        );
+
+        let line = rx.try_recv().expect("Line not streamed");
+        assert_eq!(line.level, "WARN");
+        assert_eq!(
+            line.message,
+            "feed_handler: Feed disconnected: eof code=1006"
+        );
+        assert!(line.ts_ms > 0);
+    }
+
+    #[test]
+    fn test_push_log_evicts_oldest() {
+        let buffer = new_log_buffer();
+        for i in 0..LOG_BUFFER_CAPACITY + 5 {
+            push_log(&buffer, i.to_string());
+        }
+
+        let logs = buffer.lock();
+        assert_eq!(logs.len(), LOG_BUFFER_CAPACITY);
+        assert_eq!(logs.front().map(String::as_str), Some("5"));
+    }
+}
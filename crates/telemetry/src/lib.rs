@@ -1,57 +1,129 @@
+pub mod capture;
+pub mod rotation;
+
+pub use capture::{
+    new_log_buffer, new_log_stream, push_log, LogBuffer, LogCaptureLayer, LogLine,
+    LOG_BUFFER_CAPACITY, LOG_STREAM_CAPACITY,
+};
+pub use rotation::{Rotation, SizeRollingWriter};
+
 use std::fs;
+use tokio::sync::broadcast;
 use tracing_appender::non_blocking::WorkerGuard;
-use tracing_subscriber::{fmt, prelude::*, EnvFilter};
+use tracing_subscriber::{filter::LevelFilter, fmt, prelude::*, EnvFilter};
 
 /// Initialize telemetry (logging).
 ///
 /// - Creates `log_dir` if it doesn't exist.
-/// - Sets up a daily rolling file appender (non-blocking).
+/// - Sets up a rolling file appender (non-blocking), rotated per `rotation`.
 /// - Configures a console layer (Compact, INFO+).
 /// - Configures a file layer (JSON, DEBUG+).
+/// - Copies WARN+ events into `logs` for the dashboard and publishes them on `stream`.
+/// - `filter` (EnvFilter directives, e.g. `info,feed_handler=warn,strategy=debug`)
+///   replaces both defaults and `RUST_LOG` when set.
 /// - Returns a `WorkerGuard` that must be kept alive to ensure logs are flushed on exit.
-pub fn init(log_dir: &str) -> WorkerGuard {
+pub fn init(
+    log_dir: &str,
+    filter: Option<&str>,
+    rotation: Rotation,
+    logs: LogBuffer,
+    stream: broadcast::Sender<LogLine>,
+) -> WorkerGuard {
     // 1. Create log directory
     fs::create_dir_all(log_dir).expect("Failed to create log directory");
 
-    // 2. File Appender (Daily Rolling)
-    let file_appender = tracing_appender::rolling::daily(log_dir, "hft.log");
-    let (non_blocking, guard) = tracing_appender::non_blocking(file_appender);
+    // 2. File Appender (Daily by default)
+    let (non_blocking, guard) = match rotation {
+        Rotation::Daily => {
+            tracing_appender::non_blocking(tracing_appender::rolling::daily(log_dir, "hft.log"))
+        }
+        Rotation::Hourly => {
+            tracing_appender::non_blocking(tracing_appender::rolling::hourly(log_dir, "hft.log"))
+        }
+        Rotation::SizeMb(mb) => tracing_appender::non_blocking(
+            SizeRollingWriter::new(log_dir, "hft.log", mb.max(1) * 1024 * 1024)
+                .expect("Failed to open log file"),
+        ),
+    };
+
+    // 3. Configured filter, if any (an invalid one falls back to the defaults)
+    let configured = filter.and_then(validate_filter);
 
-    // 3. Console Layer (Compact, Human-readable)
-    // Uses RUST_LOG env var if set, otherwise defaults to INFO.
+    // 4. Console Layer (Compact, Human-readable)
+    // Uses the configured filter, else RUST_LOG env var if set, otherwise defaults to INFO.
+    let console_filter = match &configured {
+        Some(directives) => EnvFilter::new(directives),
+        None => EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info")),
+    };
     let console_layer = fmt::layer()
         .with_target(false)
         .with_level(true)
         .compact()
-        .with_filter(EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info")));
+        .with_filter(console_filter);
 
-    // 4. File Layer (Structured JSON)
+    // 5. File Layer (Structured JSON)
     // Captures more detail (DEBUG level by default).
     let file_layer = fmt::layer()
         .json()
         .with_writer(non_blocking)
-        .with_filter(EnvFilter::new("debug"));
+        .with_filter(EnvFilter::new(configured.as_deref().unwrap_or("debug")));
+
+    // 6. Dashboard Layer (WARN+ into the shared buffer)
+    let capture_layer = LogCaptureLayer::new(logs, stream).with_filter(LevelFilter::WARN);
 
-    // 5. Register Layers
+    // 7. Register Layers
     tracing_subscriber::registry()
         .with(console_layer)
         .with(file_layer)
+        .with(capture_layer)
         .init();
 
     guard
 }
 
+/// Returns the directives if they parse as an `EnvFilter`, warning on stderr otherwise
+/// (the subscriber isn't installed yet).
+fn validate_filter(directives: &str) -> Option<String> {
+    match EnvFilter::try_new(directives) {
+        Ok(_) => Some(directives.to_string()),
+        Err(e) => {
+            eprintln!(
+                "Invalid log filter '{}': {}. Using defaults.",
+                directives, e
+            );
+            None
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_validate_filter() {
+        assert_eq!(
+            validate_filter("info,feed_handler=warn,strategy=debug").as_deref(),
+            Some("info,feed_handler=warn,strategy=debug")
+        );
+        assert!(validate_filter("strategy=loud").is_none());
+    }
 
     #[test]
     fn test_telemetry_init() {
         let log_dir = "test_logs";
-        let _guard = init(log_dir);
+        let logs = new_log_buffer();
+        let _guard = init(
+            log_dir,
+            None,
+            Rotation::Daily,
+            logs.clone(),
+            new_log_stream(),
+        );
 
         tracing::info!("Test log message");
+        tracing::warn!("Test warning");
+        assert_eq!(logs.lock().len(), 1);
 
         // Give it a moment to flush (async writer)
         std::thread::sleep(std::time::Duration::from_millis(100));
@@ -0,0 +1,128 @@
+use serde::Deserialize;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// When the JSON log file is rolled over.
+///
+/// In TOML: `rotation = "daily"`, `rotation = "hourly"` or `rotation = { size_mb = 100 }`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Rotation {
+    #[default]
+    Daily,
+    Hourly,
+    /// Roll once the file would exceed this many MiB.
+    SizeMb(u64),
+}
+
+/// Appends to `<dir>/<file_name>` and, once the next write would push it past
+/// `max_bytes`, renames it to `<file_name>.<unix_ms>` and starts a fresh file.
+/// tracing-appender only rotates on time, hence this wrapper.
+pub struct SizeRollingWriter {
+    dir: PathBuf,
+    file_name: String,
+    max_bytes: u64,
+    file: File,
+    written: u64,
+}
+
+impl SizeRollingWriter {
+    pub fn new(dir: impl AsRef<Path>, file_name: &str, max_bytes: u64) -> io::Result<Self> {
+        let dir = dir.as_ref().to_path_buf();
+        let file = open_append(&dir.join(file_name))?;
+        // Resume counting from an existing file left by a previous run
+        let written = file.metadata()?.len();
+        Ok(Self {
+            dir,
+            file_name: file_name.to_string(),
+            max_bytes,
+            file,
+            written,
+        })
+    }
+
+    fn roll(&mut self) -> io::Result<()> {
+        self.file.flush()?;
+        let stamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_millis();
+        let mut rolled = self.dir.join(format!("{}.{}", self.file_name, stamp));
+        let mut n = 1;
+        while rolled.exists() {
+            rolled = self.dir.join(format!("{}.{}.{}", self.file_name, stamp, n));
+            n += 1;
+        }
+
+        let current = self.dir.join(&self.file_name);
+        fs::rename(&current, &rolled)?;
+        self.file = open_append(&current)?;
+        self.written = 0;
+        Ok(())
+    }
+}
+
+fn open_append(path: &Path) -> io::Result<File> {
+    OpenOptions::new().create(true).append(true).open(path)
+}
+
+impl Write for SizeRollingWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        // A single oversized line still goes into its own file rather than being split
+        if self.written > 0 && self.written + buf.len() as u64 > self.max_bytes {
+            self.roll()?;
+        }
+        let n = self.file.write(buf)?;
+        self.written += n as u64;
+        Ok(n)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.file.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_size_rolling_writer_rolls_past_limit() {
+        let dir = "test_logs_size_rotation";
+        let _ = fs::remove_dir_all(dir);
+        fs::create_dir_all(dir).unwrap();
+
+        let mut writer = SizeRollingWriter::new(dir, "hft.log", 100).unwrap();
+        let line = [b'x'; 39];
+        for _ in 0..5 {
+            writer.write_all(&line).unwrap();
+            writer.write_all(b"\n").unwrap();
+        }
+        writer.flush().unwrap();
+
+        let mut sizes: Vec<u64> = fs::read_dir(dir)
+            .unwrap()
+            .map(|e| e.unwrap().metadata().unwrap().len())
+            .collect();
+        sizes.sort_unstable();
+        let _ = fs::remove_dir_all(dir);
+
+        // 5 lines of 40 bytes with a 100 byte cap: 80 + 80 + 40
+        assert_eq!(sizes, vec![40, 80, 80]);
+    }
+
+    #[test]
+    fn test_rotation_from_toml() {
+        #[derive(Deserialize)]
+        struct Wrapper {
+            rotation: Rotation,
+        }
+
+        let parse = |raw: &str| toml::from_str::<Wrapper>(raw).unwrap().rotation;
+        assert_eq!(parse("rotation = \"hourly\""), Rotation::Hourly);
+        assert_eq!(parse("rotation = { size_mb = 50 }"), Rotation::SizeMb(50));
+        assert_eq!(Rotation::default(), Rotation::Daily);
+    }
+}
@@ -0,0 +1,159 @@
+use anyhow::{bail, Context};
+use common::{ExecSignal, MockClock};
+use feed_handler::parse_trade;
+use std::fs;
+use std::sync::Arc;
+use strategy::StrategyParams;
+use trading_engine::db::{TradeRecord, TradeStats};
+use trading_engine::state::{self, EngineState};
+
+struct Args {
+    data_path: String,
+    strategy: String,
+    fee_maker: f64,
+    fee_taker: f64,
+    window: usize,
+    threshold: f64,
+    order_quantity: f64,
+}
+
+impl Default for Args {
+    fn default() -> Self {
+        // Mirrors the engine's config fallbacks
+        Self {
+            data_path: "../../data/fixtures/raw_ticks.jsonl".to_string(),
+            strategy: "PING_PONG".to_string(),
+            fee_maker: 0.0002,
+            fee_taker: 0.0005,
+            window: 50,
+            threshold: 2.0,
+            order_quantity: 0.01,
+        }
+    }
+}
+
+const USAGE: &str = "Usage: backtest [--data PATH] [--strategy NAME] [--fee-maker F] [--fee-taker F] [--window N] [--threshold T] [--quantity Q]";
+
+fn parse_args() -> anyhow::Result<Args> {
+    let mut args = Args::default();
+    let mut iter = std::env::args().skip(1);
+
+    while let Some(flag) = iter.next() {
+        if flag == "--help" || flag == "-h" {
+            println!("{}", USAGE);
+            std::process::exit(0);
+        }
+
+        let value = iter
+            .next()
+            .with_context(|| format!("Missing value for {}\n{}", flag, USAGE))?;
+        match flag.as_str() {
+            "--data" => args.data_path = value,
+            "--strategy" => args.strategy = value.to_uppercase(),
+            "--fee-maker" => args.fee_maker = value.parse().context("Invalid --fee-maker")?,
+            "--fee-taker" => args.fee_taker = value.parse().context("Invalid --fee-taker")?,
+            "--window" => args.window = value.parse().context("Invalid --window")?,
+            "--threshold" => args.threshold = value.parse().context("Invalid --threshold")?,
+            "--quantity" => args.order_quantity = value.parse().context("Invalid --quantity")?,
+            _ => bail!("Unknown argument: {}\n{}", flag, USAGE),
+        }
+    }
+
+    Ok(args)
+}
+
+fn main() -> anyhow::Result<()> {
+    let args = parse_args()?;
+
+    let content = fs::read_to_string(&args.data_path)
+        .with_context(|| format!("Failed to read {}", args.data_path))?;
+
+    let params = StrategyParams {
+        fee_maker: args.fee_maker,
+        fee_taker: args.fee_taker,
+        window: args.window,
+        threshold: args.threshold,
+        order_quantity: args.order_quantity,
+        ..Default::default()
+    };
+    // Cooldowns and throttles run on recorded time, not on how fast the file replays
+    let clock = MockClock::new(0);
+    let mut strategy =
+        strategy::create_strategy_with_clock(&args.strategy, &params, Arc::new(clock.clone()));
+
+    // Same accounting path as the live execution task
+    let state = EngineState::new();
+    let mut trades: Vec<TradeRecord> = Vec::new();
+    let mut ticks = 0usize;
+    let mut parse_errors = 0usize;
+
+    println!("Backtesting {} on {}...", args.strategy, args.data_path);
+
+    for line in content.lines() {
+        let event = match parse_trade(line) {
+            Ok(event) => event,
+            Err(_) => {
+                parse_errors += 1;
+                continue;
+            }
+        };
+        ticks += 1;
+        *state.last_price.lock() = event.price;
+        clock.set(event.exchange_timestamp.max(0) as u64 * 1_000_000);
+
+        // Orders fill immediately here, so cancels have nothing to act on
+        let signals = strategy.process_event(&event);
+        for instruction in signals.into_iter().filter_map(ExecSignal::into_place) {
+            // Simulated fill at the tick price
+            let price = event.price;
+            let signed_qty = match instruction.side {
+                common::Side::Buy => instruction.quantity,
+                common::Side::Sell => -instruction.quantity,
+            };
+            let fee = state::estimate_fee(
+                instruction.order_type,
+                instruction.quantity,
+                price,
+                args.fee_maker,
+                args.fee_taker,
+            );
+            let realized_pnl = state.update_from_trade(signed_qty, price, fee);
+
+            trades.push(TradeRecord {
+                exchange_ts_ms: event.exchange_timestamp,
+                monotonic_ns: event.received_timestamp,
+                symbol: instruction.symbol.to_string(),
+                side: format!("{:?}", instruction.side),
+                price,
+                quantity: instruction.quantity,
+                pnl: realized_pnl,
+                strategy: args.strategy.clone(),
+                order_id: None,
+                exec_id: None,
+                fee: Some(fee),
+                fee_currency: Some("USDT".to_string()),
+                raw: None,
+                decision_latency_ns: None,
+                tick_to_order_ns: None,
+            });
+        }
+    }
+
+    let stats = TradeStats::from_trades(&trades);
+    let decided = stats.win_count + stats.loss_count;
+    let win_rate = if decided > 0 {
+        stats.win_count as f64 / decided as f64 * 100.0
+    } else {
+        0.0
+    };
+
+    println!("\n--- Backtest Summary ---");
+    println!("Ticks: {} ({} parse errors)", ticks, parse_errors);
+    println!("Final PnL: {:.4}", *state.current_pnl.lock());
+    println!("Open Position: {}", *state.current_position.lock());
+    println!("Win Rate: {:.2}%", win_rate);
+    println!("Max Drawdown: {:.4}", stats.max_drawdown);
+    println!("{}", serde_json::to_string_pretty(&stats)?);
+
+    Ok(())
+}
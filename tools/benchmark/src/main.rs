@@ -1,14 +1,29 @@
 use anyhow::Context;
-use common::{MarketEvent, TradeInstruction};
+use common::{ExecSignal, MarketEvent};
 use feed_handler::parse_trade;
+use flate2::read::MultiGzDecoder;
 use hdrhistogram::Histogram;
 use std::fs;
-use std::sync::{atomic::AtomicBool, Arc};
+use std::io::Read;
+use std::sync::{
+    atomic::{AtomicBool, AtomicU64},
+    Arc,
+};
 use std::time::Instant;
-
-fn load_ticks() -> anyhow::Result<Vec<String>> {
-    let path = "../../data/fixtures/raw_ticks.jsonl";
-    let content = fs::read_to_string(path).context("Failed to read raw_ticks.jsonl")?;
+use strategy::{StrategyParams, WaitStrategy};
+
+/// Loads ticks from `path` (first CLI arg, default `raw_ticks.jsonl`); `.gz` is decompressed.
+fn load_ticks(path: &str) -> anyhow::Result<Vec<String>> {
+    let content = if path.ends_with(".gz") {
+        let file = fs::File::open(path).with_context(|| format!("Failed to open {}", path))?;
+        let mut content = String::new();
+        MultiGzDecoder::new(file)
+            .read_to_string(&mut content)
+            .with_context(|| format!("Failed to decompress {}", path))?;
+        content
+    } else {
+        fs::read_to_string(path).with_context(|| format!("Failed to read {}", path))?
+    };
     Ok(content.lines().map(|s| s.to_string()).collect())
 }
 
@@ -29,6 +44,32 @@ fn bench_parsing(ticks: &[String]) -> Histogram<u64> {
     hist
 }
 
+/// Same loop as `bench_parsing` on simd-json. It parses in place, so each iteration copies
+/// the tick into a reused buffer first, as the feed would from a websocket frame.
+#[cfg(feature = "simd")]
+fn bench_parsing_simd(ticks: &[String]) -> Histogram<u64> {
+    let mut hist = Histogram::<u64>::new_with_bounds(1, 100_000_000, 3).unwrap();
+    let iterations = 1_000_000;
+    let tick_count = ticks.len();
+    let mut buf = Vec::with_capacity(1024);
+
+    println!(
+        "Running SIMD Parse Benchmark ({} iterations)...",
+        iterations
+    );
+
+    for i in 0..iterations {
+        let line = &ticks[i % tick_count];
+        buf.clear();
+        buf.extend_from_slice(line.as_bytes());
+        let start = Instant::now();
+        let _ = feed_handler::parse_trade_simd(&mut buf).unwrap();
+        let elapsed = start.elapsed().as_nanos() as u64;
+        hist.record(elapsed).unwrap();
+    }
+    hist
+}
+
 fn bench_e2e(ticks: &[String]) -> Histogram<u64> {
     let mut hist = Histogram::<u64>::new_with_bounds(1, 100_000_000, 3).unwrap(); // Up to 100ms
     let iterations = 100_000;
@@ -41,7 +82,7 @@ fn bench_e2e(ticks: &[String]) -> Histogram<u64> {
 
     // Setup Pipeline
     let (mut market_prod, market_cons) = rtrb::RingBuffer::<MarketEvent>::new(4096);
-    let (trade_prod, mut trade_cons) = rtrb::RingBuffer::<TradeInstruction>::new(4096);
+    let (trade_prod, mut trade_cons) = rtrb::RingBuffer::<ExecSignal>::new(4096);
     let shutdown = Arc::new(AtomicBool::new(false));
     let is_running = Arc::new(AtomicBool::new(true));
 
@@ -62,14 +103,16 @@ fn bench_e2e(ticks: &[String]) -> Histogram<u64> {
             s_shutdown,
             s_running,
             active_strategy,
-            true,  // dry_run
-            true,  // disable_throttle
-            0.0002, // fee_maker
-            0.0005, // fee_taker
-            50,    // strategy_window
-            2.0,   // strategy_threshold
-            10.0,  // price_threshold
-            3.0,   // volume_multiplier
+            Arc::new(parking_lot::Mutex::new(StrategyParams::default())),
+            true,                                   // dry_run
+            true,                                   // disable_throttle
+            1_000_000,                              // latency_warn_ns
+            Arc::new(AtomicU64::new(0)),            // stale_ticks
+            Arc::new(AtomicU64::new(0)),            // last_latency_ns
+            Arc::new(parking_lot::Mutex::new(0.0)), // engine_position
+            Arc::new(AtomicBool::new(false)),       // force_reset
+            Vec::new(),                             // seed_prices
+            WaitStrategy::Yield,                    // wait
         );
     });
 
@@ -90,7 +133,7 @@ fn bench_e2e(ticks: &[String]) -> Histogram<u64> {
 
         // 3. Wait for Output
         loop {
-            if let Ok(_instr) = trade_cons.pop() {
+            if let Ok(_signal) = trade_cons.pop() {
                 // 4. Measure Latency
                 let end_ts = common::now_nanos();
                 // Use instruction timestamp if we want "Strategy Decision Time",
@@ -119,12 +162,21 @@ fn print_stats(name: &str, hist: &Histogram<u64>) {
 }
 
 fn main() -> anyhow::Result<()> {
-    let ticks = load_ticks()?;
+    let path = std::env::args()
+        .nth(1)
+        .unwrap_or_else(|| "../../data/fixtures/raw_ticks.jsonl".to_string());
+    let ticks = load_ticks(&path)?;
     println!("Loaded {} ticks for benchmarking.", ticks.len());
 
     let parse_hist = bench_parsing(&ticks);
     print_stats("Tick-to-Parse", &parse_hist);
 
+    #[cfg(feature = "simd")]
+    {
+        let simd_hist = bench_parsing_simd(&ticks);
+        print_stats("Tick-to-Parse (simd-json)", &simd_hist);
+    }
+
     let e2e_hist = bench_e2e(&ticks);
     print_stats("End-to-End (Tick-to-Order)", &e2e_hist);
 
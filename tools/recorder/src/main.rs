@@ -1,34 +1,112 @@
 use anyhow::Context;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use std::fs::{self, File};
+use std::io::{BufWriter, Write};
 use std::path::Path;
 use std::time::Duration;
-use tokio::fs::{self, File};
-use tokio::io::AsyncWriteExt;
-use tokio::sync::mpsc;
+use tokio::sync::{mpsc, oneshot};
+
+/// Opens the output file, wrapping it in a gzip encoder when requested.
+fn open_output(path: &Path, gzip: bool) -> anyhow::Result<Box<dyn Write + Send>> {
+    let file = File::create(path).with_context(|| format!("Failed to create {:?}", path))?;
+    let writer = BufWriter::new(file);
+    if gzip {
+        Ok(Box::new(GzEncoder::new(writer, Compression::default())))
+    } else {
+        Ok(Box::new(writer))
+    }
+}
+
+struct Args {
+    ws_url: String,
+    symbol: String,
+    duration: Duration,
+    out: String,
+    gzip: bool,
+}
+
+const USAGE: &str =
+    "Usage: recorder [--ws-url URL] [--symbol SYMBOL] [--duration-secs N] [--out PATH] [--gzip]";
+
+fn parse_args() -> anyhow::Result<Args> {
+    let mut ws_url = feed_handler::FUTURES_WS_URL.to_string();
+    let mut symbol = "BTCUSDT".to_string();
+    let mut duration_secs = 60u64;
+    let mut out = None;
+    let mut gzip = false;
+
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--gzip" => gzip = true,
+            "--ws-url" => ws_url = args.next().context("Missing value for --ws-url")?,
+            "--symbol" => {
+                symbol = args
+                    .next()
+                    .context("Missing value for --symbol")?
+                    .to_uppercase()
+            }
+            "--duration-secs" => {
+                duration_secs = args
+                    .next()
+                    .context("Missing value for --duration-secs")?
+                    .parse()
+                    .context("Invalid --duration-secs")?
+            }
+            "--out" => out = Some(args.next().context("Missing value for --out")?),
+            "--help" | "-h" => {
+                println!("{}", USAGE);
+                std::process::exit(0);
+            }
+            _ => anyhow::bail!("Unknown argument: {}\n{}", arg, USAGE),
+        }
+    }
+
+    // A `.gz` output path implies compression
+    let out = match out {
+        Some(out) => {
+            gzip |= out.ends_with(".gz");
+            out
+        }
+        None if gzip => "../../data/fixtures/raw_ticks.jsonl.gz".to_string(),
+        None => "../../data/fixtures/raw_ticks.jsonl".to_string(),
+    };
+
+    Ok(Args {
+        ws_url,
+        symbol,
+        duration: Duration::from_secs(duration_secs),
+        out,
+        gzip,
+    })
+}
 
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
-    let path_str = "../../data/fixtures/raw_ticks.jsonl";
-    let path = Path::new(path_str);
+    let args = parse_args()?;
+    let path = Path::new(&args.out);
 
     if let Some(parent) = path.parent() {
-        fs::create_dir_all(parent)
-            .await
-            .context("Failed to create fixtures directory")?;
+        fs::create_dir_all(parent).context("Failed to create fixtures directory")?;
     }
 
-    let mut file = File::create(path)
-        .await
-        .context("Failed to create raw_ticks.jsonl")?;
+    let mut output = open_output(path, args.gzip)?;
 
-    println!("Recording to {}...", path_str);
+    println!("Recording {} to {}...", args.symbol, args.out);
 
     let (raw_tx, mut raw_rx) = mpsc::channel::<String>(10_000);
+    let (stop_tx, mut stop_rx) = oneshot::channel::<()>();
 
     // Spawn writer task
-    let _writer_handle = tokio::spawn(async move {
+    let writer_handle = tokio::spawn(async move {
         let mut count = 0;
-        while let Some(line) = raw_rx.recv().await {
-            if let Err(e) = file.write_all(format!("{}\n", line).as_bytes()).await {
+        loop {
+            let line = tokio::select! {
+                Some(line) = raw_rx.recv() => line,
+                _ = &mut stop_rx => break,
+            };
+            if let Err(e) = writeln!(output, "{}", line) {
                 eprintln!("Failed to write to file: {}", e);
                 break;
             }
@@ -37,19 +115,34 @@ async fn main() -> anyhow::Result<()> {
                 println!("Recorded {} ticks", count);
             }
         }
+        if let Err(e) = output.flush() {
+            eprintln!("Failed to flush file: {}", e);
+        }
+        // Dropping the encoder writes the gzip trailer
+        drop(output);
         println!("Writer task finished. Total recorded: {}", count);
+        count
     });
 
     // Connect to Binance
-    let _rx = feed_handler::connect("BTCUSDT", Some(raw_tx)).await?;
+    let _rx = feed_handler::connect(&args.ws_url, &args.symbol, Some(raw_tx), None, None).await?;
 
-    println!("Connected to Binance. Recording for 60 seconds...");
-    tokio::time::sleep(Duration::from_secs(60)).await;
+    println!(
+        "Connected to Binance. Recording for {} seconds...",
+        args.duration.as_secs()
+    );
+    tokio::time::sleep(args.duration).await;
 
     println!("Recording complete.");
 
-    // Give writer task a moment to flush
-    tokio::time::sleep(Duration::from_millis(500)).await;
+    // Stop the writer and wait for it to flush
+    let _ = stop_tx.send(());
+    let count = writer_handle.await.context("Writer task panicked")?;
+
+    let secs = args.duration.as_secs_f64();
+    if secs > 0.0 {
+        println!("Effective rate: {:.2} ticks/sec", count as f64 / secs);
+    }
 
     Ok(())
 }
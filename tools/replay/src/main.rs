@@ -1,15 +1,17 @@
 use anyhow::Context;
 use feed_handler::parse_trade;
+use flate2::read::MultiGzDecoder;
 use serde_json::json;
+use std::io::{Cursor, Read};
 use std::path::Path;
-use std::time::Instant;
+use std::time::{Duration, Instant};
 use tokio::fs::{self, File};
-use tokio::io::{AsyncBufReadExt, BufReader};
+use tokio::io::{AsyncBufRead, AsyncBufReadExt, BufReader};
 use tokio_stream::wrappers::LinesStream;
 use tokio_stream::StreamExt;
 
 async fn generate_sample_data() -> anyhow::Result<()> {
-    let path_str = "../../data/fixtures/raw_ticks.jsonl";
+    let path_str = DEFAULT_DATA_PATH;
     let path = Path::new(path_str);
 
     if path.exists() {
@@ -36,10 +38,11 @@ async fn generate_sample_data() -> anyhow::Result<()> {
             "s": "BTCUSDT",
             "p": format!("{:.1}", price),
             "q": "0.1",
-            "T": timestamp
+            "T": timestamp,
+            "m": i % 2 == 0
         });
 
-        let line = format!("{}\n", record.to_string());
+        let line = format!("{}\n", record);
         file.write_all(line.as_bytes()).await?;
     }
 
@@ -47,23 +50,102 @@ async fn generate_sample_data() -> anyhow::Result<()> {
     Ok(())
 }
 
+const DEFAULT_DATA_PATH: &str = "../../data/fixtures/raw_ticks.jsonl";
+
+struct Args {
+    /// Replay speed multiplier; `None` replays as fast as possible.
+    speed: Option<f64>,
+    /// Tick file; `.gz` files are decompressed transparently.
+    data_path: String,
+}
+
+/// Parses `[--speed <multiplier>] [--data <path>]`.
+fn parse_args() -> anyhow::Result<Args> {
+    let mut args = std::env::args().skip(1);
+    let mut parsed = Args {
+        speed: None,
+        data_path: DEFAULT_DATA_PATH.to_string(),
+    };
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--speed" => {
+                let value: f64 = args
+                    .next()
+                    .context("Missing value for --speed")?
+                    .parse()
+                    .context("Invalid --speed")?;
+                if !(value > 0.0 && value.is_finite()) {
+                    anyhow::bail!("--speed must be a positive number");
+                }
+                parsed.speed = Some(value);
+            }
+            "--data" => {
+                parsed.data_path = args.next().context("Missing value for --data")?;
+            }
+            _ => anyhow::bail!(
+                "Unknown argument: {} (usage: replay [--speed N] [--data PATH])",
+                arg
+            ),
+        }
+    }
+
+    Ok(parsed)
+}
+
+/// Opens a tick file for line reading, gunzipping `.gz` files up front.
+async fn open_ticks(path: &str) -> anyhow::Result<Box<dyn AsyncBufRead + Unpin + Send>> {
+    if path.ends_with(".gz") {
+        let owned = path.to_string();
+        let bytes = tokio::task::spawn_blocking(move || -> anyhow::Result<Vec<u8>> {
+            let file =
+                std::fs::File::open(&owned).with_context(|| format!("Failed to open {}", owned))?;
+            let mut bytes = Vec::new();
+            MultiGzDecoder::new(file)
+                .read_to_end(&mut bytes)
+                .with_context(|| format!("Failed to decompress {}", owned))?;
+            Ok(bytes)
+        })
+        .await??;
+        Ok(Box::new(Cursor::new(bytes)))
+    } else {
+        let file = File::open(path)
+            .await
+            .with_context(|| format!("Failed to open {}", path))?;
+        Ok(Box::new(BufReader::new(file)))
+    }
+}
+
+/// Wall-clock delay for an exchange-time gap at the given speed.
+/// Out-of-order or identical timestamps yield no delay.
+fn replay_delay(prev_ts_ms: i64, ts_ms: i64, speed: f64) -> Duration {
+    let gap_ms = ts_ms.saturating_sub(prev_ts_ms);
+    if gap_ms <= 0 {
+        return Duration::ZERO;
+    }
+    Duration::from_secs_f64(gap_ms as f64 / 1000.0 / speed)
+}
+
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
-    generate_sample_data().await?;
+    let Args { speed, data_path } = parse_args()?;
+    if data_path == DEFAULT_DATA_PATH {
+        generate_sample_data().await?;
+    }
 
-    let path = "../../data/fixtures/raw_ticks.jsonl";
-    let file = File::open(path)
-        .await
-        .context("Failed to open raw_ticks.jsonl")?;
-    let reader = BufReader::new(file);
+    let reader = open_ticks(&data_path).await?;
     let mut lines = LinesStream::new(reader.lines());
 
     let mut total_lines = 0;
     let mut success_count = 0;
     let mut error_count = 0;
+    let mut prev_ts: Option<i64> = None;
     let start_time = Instant::now();
 
-    println!("Starting replay...");
+    match speed {
+        Some(speed) => println!("Starting replay at {}x...", speed),
+        None => println!("Starting replay..."),
+    }
 
     while let Some(line) = lines.next().await {
         let line = line?;
@@ -71,6 +153,19 @@ async fn main() -> anyhow::Result<()> {
 
         match parse_trade(&line) {
             Ok(event) => {
+                if let Some(speed) = speed {
+                    if let Some(prev) = prev_ts {
+                        let delay = replay_delay(prev, event.exchange_timestamp, speed);
+                        if !delay.is_zero() {
+                            tokio::time::sleep(delay).await;
+                        }
+                    }
+                    // Keep the high-water mark so a late tick doesn't stretch the next gap
+                    prev_ts = Some(prev_ts.map_or(event.exchange_timestamp, |p| {
+                        p.max(event.exchange_timestamp)
+                    }));
+                }
+
                 success_count += 1;
                 if success_count <= 5 {
                     println!("Parsed: {:?}", event);
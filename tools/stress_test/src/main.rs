@@ -1,9 +1,30 @@
 use common::MarketEvent;
 use rtrb::{PopError, PushError};
+use std::alloc::{GlobalAlloc, Layout, System};
 use std::hint;
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::thread;
 use std::time::Instant;
 
+/// System allocator that counts allocations, to check the handoff path doesn't allocate.
+struct CountingAlloc;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAlloc {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAlloc = CountingAlloc;
+
 const TOTAL_MESSAGES: usize = 10_000_000;
 const QUEUE_CAPACITY: usize = 4096;
 
@@ -16,14 +37,9 @@ fn main() {
     let (mut producer, mut consumer) = rtrb::RingBuffer::<MarketEvent>::new(QUEUE_CAPACITY);
 
     // Create dummy event
-    let event = MarketEvent {
-        symbol: "BTCUSDT".into(),
-        price: 50000.0,
-        quantity: 0.1,
-        exchange_timestamp: 0,
-        received_timestamp: 0,
-    };
+    let event = MarketEvent::trade("BTCUSDT", 50000.0, 0.1);
 
+    let allocations_before = ALLOCATIONS.load(Ordering::Relaxed);
     let start = Instant::now();
 
     // Thread A — PRODUCER
@@ -60,6 +76,8 @@ fn main() {
     let received_count = consumer_handle.join().expect("Consumer thread panicked");
 
     let duration = start.elapsed();
+    // Includes the two thread spawns
+    let allocations = ALLOCATIONS.load(Ordering::Relaxed) - allocations_before;
     let duration_secs = duration.as_secs_f64();
     let duration_ns = duration.as_nanos() as f64;
 
@@ -71,6 +89,11 @@ fn main() {
     println!("Duration: {:.2?}", duration);
     println!("Throughput: {:.2} msgs/sec", throughput);
     println!("Avg Handoff: {:.2} ns/msg", avg_ns);
+    println!(
+        "Allocations: {} ({:.4} per msg)",
+        allocations,
+        allocations as f64 / TOTAL_MESSAGES as f64
+    );
 
     assert_eq!(received_count, TOTAL_MESSAGES, "Message count mismatch!");
     println!("\nSUCCESS: Zero drops confirmed.");